@@ -0,0 +1,141 @@
+#!/usr/bin/env python3
+"""
+Convert a knowledge base package between the JSON format ({ manifest, documents, embeddings })
+and the compact binary .ckb format read by the app (see src-tauri/src/kb_binary.rs).
+
+The input format is detected from the file header; the output is the other format.
+The app can do the same conversion via the convert_kb_package command.
+
+Requires: zstandard (pip install zstandard); numpy is not needed.
+
+Usage:
+  python convert_kb_package.py ../src-tauri/resources/default_kb.json -o default_kb.ckb
+  python convert_kb_package.py default_kb.json -o default_kb.ckb --encoding f16
+  python convert_kb_package.py default_kb.ckb -o default_kb.json
+"""
+import argparse
+import io
+import json
+import struct
+import sys
+from pathlib import Path
+
+try:
+    import zstandard
+except ImportError:
+    print("Error: zstandard not installed. Run: pip install zstandard", file=sys.stderr)
+    sys.exit(1)
+
+MAGIC = b"CKB1"
+FORMAT_VERSION = 1
+ENCODINGS = {"f32": 0, "f16": 1, "int8": 2}
+ZSTD_LEVEL = 19
+
+
+def encode_row(row, encoding: str) -> bytes:
+    if encoding == "f32":
+        return struct.pack("<%df" % len(row), *row)
+    if encoding == "f16":
+        return struct.pack("<%de" % len(row), *row)
+    max_abs = max((abs(v) for v in row), default=0.0)
+    scale = max_abs / 127.0 if max_abs > 0 else 0.0
+    quantized = [max(-127, min(127, round(v / scale))) if scale > 0 else 0 for v in row]
+    return struct.pack("<f", scale) + struct.pack("<%db" % len(row), *quantized)
+
+
+def decode_row(data: bytes, encoding: str, dim: int):
+    if encoding == "f32":
+        return list(struct.unpack("<%df" % dim, data))
+    if encoding == "f16":
+        return list(struct.unpack("<%de" % dim, data))
+    (scale,) = struct.unpack("<f", data[:4])
+    return [v * scale for v in struct.unpack("<%db" % dim, data[4:])]
+
+
+def row_size(encoding: str, dim: int) -> int:
+    return {"f32": dim * 4, "f16": dim * 2, "int8": 4 + dim}[encoding]
+
+
+def json_to_binary(package: dict, out_path: Path, encoding: str) -> None:
+    manifest = dict(package["manifest"])
+    documents = package["documents"]
+    embeddings = package["embeddings"]
+    if len(documents) != len(embeddings):
+        raise ValueError(f"{len(embeddings)} embeddings for {len(documents)} documents")
+    dim = int(manifest["embeddingDimension"])
+    for i, e in enumerate(embeddings):
+        if len(e) != dim:
+            raise ValueError(f"embeddings[{i}]: expected {dim} values, got {len(e)}")
+    manifest["documentCount"] = len(documents)
+    manifest_json = json.dumps(manifest, ensure_ascii=False, separators=(",", ":")).encode("utf-8")
+
+    with open(out_path, "wb") as f:
+        f.write(MAGIC)
+        f.write(struct.pack("<HBB", FORMAT_VERSION, ENCODINGS[encoding], 0))
+        f.write(struct.pack("<I", len(manifest_json)))
+        f.write(manifest_json)
+        f.write(struct.pack("<QI", len(documents), dim))
+        for e in embeddings:
+            f.write(encode_row(e, encoding))
+        lines = "".join(
+            json.dumps({"id": d["id"], "text": d["text"], "metadata": d.get("metadata") or {}},
+                       ensure_ascii=False, separators=(",", ":")) + "\n"
+            for d in documents
+        )
+        f.write(zstandard.ZstdCompressor(level=ZSTD_LEVEL).compress(lines.encode("utf-8")))
+
+
+def binary_to_json(in_path: Path) -> dict:
+    data = in_path.read_bytes()
+    if data[:4] != MAGIC:
+        raise ValueError("not a binary knowledge base package")
+    version, tag, _ = struct.unpack_from("<HBB", data, 4)
+    if version != FORMAT_VERSION:
+        raise ValueError(f"unsupported binary package version {version}")
+    encoding = {v: k for k, v in ENCODINGS.items()}[tag]
+    (manifest_len,) = struct.unpack_from("<I", data, 8)
+    offset = 12
+    manifest = json.loads(data[offset:offset + manifest_len].decode("utf-8"))
+    offset += manifest_len
+    count, dim = struct.unpack_from("<QI", data, offset)
+    offset += 12
+
+    size = row_size(encoding, dim)
+    embeddings = []
+    for _ in range(count):
+        embeddings.append(decode_row(data[offset:offset + size], encoding, dim))
+        offset += size
+
+    reader = zstandard.ZstdDecompressor().stream_reader(io.BytesIO(data[offset:]))
+    text = io.TextIOWrapper(reader, encoding="utf-8")
+    documents = [json.loads(line) for line in text if line.strip()]
+    if len(documents) != count:
+        raise ValueError(f"header declares {count} documents, found {len(documents)}")
+    return {"manifest": manifest, "documents": documents, "embeddings": embeddings}
+
+
+def main():
+    ap = argparse.ArgumentParser(description="Convert KB packages between JSON and binary (.ckb)")
+    ap.add_argument("input", type=Path, help="JSON or .ckb package")
+    ap.add_argument("-o", "--output", type=Path, required=True, help="Output path")
+    ap.add_argument("--encoding", choices=sorted(ENCODINGS), default="f32",
+                    help="Embedding encoding when writing binary (default: f32)")
+    args = ap.parse_args()
+
+    with open(args.input, "rb") as f:
+        is_binary = f.read(4) == MAGIC
+
+    if is_binary:
+        package = binary_to_json(args.input)
+        with open(args.output, "w", encoding="utf-8") as f:
+            json.dump(package, f, ensure_ascii=False)
+        print(f"Wrote JSON package with {len(package['documents'])} documents to {args.output}")
+    else:
+        with open(args.input, encoding="utf-8") as f:
+            package = json.load(f)
+        json_to_binary(package, args.output, args.encoding)
+        print(f"Wrote {args.encoding} binary package with {len(package['documents'])} documents to {args.output}")
+
+
+if __name__ == "__main__":
+    main()
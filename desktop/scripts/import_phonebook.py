@@ -8,6 +8,7 @@ Usage:
 Default db: current dir data/chromadb or env CONFIDANT_PHONEBOOK_DB_PATH.
 """
 import argparse
+import hashlib
 import json
 import os
 import subprocess
@@ -47,6 +48,18 @@ def run_python_script(script_name: str, command: str, args: list, stdin_data: st
     return result.stdout
 
 
+def seed_entry_ids(entries: list) -> list:
+    """Stable ids matching the app (bundled_defaults.rs): country, postal code and lower-cased name."""
+    seen = {}
+    ids = []
+    for e in entries:
+        key = f"{str(e.get('country', '')).strip()}|{str(e.get('postal_code', '')).strip()}|{str(e.get('name', '')).strip().lower()}"
+        doc_id = "phonebook_" + hashlib.sha256(key.encode("utf-8")).hexdigest()[:16]
+        seen[doc_id] = seen.get(doc_id, 0) + 1
+        ids.append(doc_id if seen[doc_id] == 1 else f"{doc_id}_{seen[doc_id]}")
+    return ids
+
+
 def main():
     parser = argparse.ArgumentParser(description="Import phone book JSON into ChromaDB")
     parser.add_argument("json_path", help="Path to JSON file (array of entries)")
@@ -84,6 +97,7 @@ def main():
     run_python_script("chromadb_helper.py", "init", [db_path, "dant_phonebook"])
 
     collection = "dant_phonebook"
+    ids = seed_entry_ids(entries)
     batch_size = 20
     for i in range(0, len(entries), batch_size):
         batch_entries = entries[i : i + batch_size]
@@ -91,7 +105,7 @@ def main():
         batch_embeddings = embeddings[i : i + batch_size]
         documents = []
         for j, (e, text, emb) in enumerate(zip(batch_entries, batch_texts, batch_embeddings)):
-            doc_id = f"phonebook_{i + j}_{e.get('name', 'entry').replace(' ', '_')}"
+            doc_id = ids[i + j]
             metadata = {
                 "country": str(e.get("country", "")),
                 "postal_code": str(e.get("postal_code", "")),
@@ -101,11 +115,12 @@ def main():
                 "address": str(e.get("address", "")),
                 "city": str(e.get("city", "")),
                 "state": str(e.get("state", "")),
+                "source": "seed",
             }
             documents.append({"id": doc_id, "text": text, "embedding": emb, "metadata": metadata})
         docs_json = json.dumps(documents)
-        run_python_script("chromadb_helper.py", "add", [db_path, collection], stdin_data=docs_json)
-        print(f"  Added {len(documents)} documents", file=sys.stderr)
+        run_python_script("chromadb_helper.py", "upsert", [db_path, collection], stdin_data=docs_json)
+        print(f"  Upserted {len(documents)} documents", file=sys.stderr)
 
     print("Done.", file=sys.stderr)
 
@@ -0,0 +1,67 @@
+#!/usr/bin/env python3
+"""
+Write the parity fixtures for the native (Rust) embeddings: all-MiniLM-L6-v2 embeddings of fixed texts,
+computed with sentence-transformers exactly as embeddings_helper.py does (normalize_embeddings=True).
+
+The Rust tests in src-tauri/src/native_embeddings.rs compare their output to this file, which is committed
+(regenerate and commit it when TEXTS or the model change):
+  python scripts/make_embedding_fixtures.py
+  git add src-tauri/tests/fixtures/minilm_embeddings.json
+  cd src-tauri && cargo test --features native-embeddings native_embeddings
+
+Requires: pip install sentence-transformers
+"""
+import argparse
+import json
+import sys
+from pathlib import Path
+
+MODEL_ID = "all-MiniLM-L6-v2"
+DEFAULT_OUTPUT = Path(__file__).resolve().parent.parent / "src-tauri" / "tests" / "fixtures" / "minilm_embeddings.json"
+
+# Short and long, punctuation, non-ASCII, and a text longer than the 256-token limit (truncation)
+TEXTS = [
+    "I feel anxious about tomorrow.",
+    "Where can I find a counselor near me?",
+    "Breathing exercises can help calm the nervous system.",
+    "crisis hotline",
+    "¿Dónde puedo encontrar ayuda cerca de mí?",
+    "The quick brown fox jumps over the lazy dog!!! 123 — 456",
+    "",
+    " ".join(["Sleep, exercise and social contact all support mental health."] * 60),
+]
+
+
+def main():
+    ap = argparse.ArgumentParser(description="Write all-MiniLM-L6-v2 parity fixtures for the native embeddings")
+    ap.add_argument("-o", "--output", default=str(DEFAULT_OUTPUT), help="Output JSON path")
+    args = ap.parse_args()
+
+    try:
+        from sentence_transformers import SentenceTransformer
+    except ImportError:
+        print("sentence-transformers is not installed: pip install sentence-transformers", file=sys.stderr)
+        sys.exit(1)
+
+    model = SentenceTransformer(MODEL_ID)
+    embeddings = model.encode(TEXTS, normalize_embeddings=True, convert_to_numpy=True)
+
+    out = Path(args.output)
+    out.parent.mkdir(parents=True, exist_ok=True)
+    out.write_text(
+        json.dumps(
+            {
+                "model": MODEL_ID,
+                "texts": TEXTS,
+                "embeddings": [[float(v) for v in row] for row in embeddings],
+            },
+            indent=1,
+        )
+        + "\n",
+        encoding="utf-8",
+    )
+    print(f"Wrote {len(TEXTS)} embeddings ({embeddings.shape[1]} dims) to {out}")
+
+
+if __name__ == "__main__":
+    main()
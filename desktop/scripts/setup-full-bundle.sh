@@ -50,6 +50,10 @@ if [ -f "$RESOURCES/default_kb.json" ]; then
 else
   if [ -f "$DESKTOP_DIR/test_knowledge_base.json" ]; then
     cp "$DESKTOP_DIR/test_knowledge_base.json" "$RESOURCES/default_kb.json"
+    # The app refuses unsigned bundled packages; copy the detached signature along with it
+    if [ -f "$DESKTOP_DIR/test_knowledge_base.json.sig" ]; then
+      cp "$DESKTOP_DIR/test_knowledge_base.json.sig" "$RESOURCES/default_kb.json.sig"
+    fi
     echo "  Copied test_knowledge_base.json to resources/default_kb.json"
   else
     echo "  No default_kb.json or test_knowledge_base.json found. Add resources/default_kb.json for zero-config KB."
@@ -57,6 +61,26 @@ else
 fi
 echo ""
 
+# --- Optional: native embedding model (for builds with --features native-embeddings) ---
+if [ -n "${NATIVE_EMBEDDINGS:-}" ]; then
+  echo "Native embeddings: all-MiniLM-L6-v2 model files ..."
+  MINILM_DIR="$MODELS_DIR/all-MiniLM-L6-v2"
+  MINILM_URL="https://huggingface.co/sentence-transformers/all-MiniLM-L6-v2/resolve/main"
+  mkdir -p "$MINILM_DIR"
+  for f in config.json tokenizer.json model.safetensors; do
+    if [ -f "$MINILM_DIR/$f" ]; then
+      echo "  $f already present"
+    elif curl -# -L -f -o "$MINILM_DIR/$f" "$MINILM_URL/$f"; then
+      echo "  Downloaded $f"
+    else
+      rm -f "$MINILM_DIR/$f"
+      echo "  Download of $f failed. Re-run, or build without --features native-embeddings."
+      exit 1
+    fi
+  done
+  echo ""
+fi
+
 echo "Full bundle ready. Run: npm run build"
 if [ -n "${SKIP_MODEL:-}" ]; then
   echo "Installers will include Python and KB; download the model once in Settings."
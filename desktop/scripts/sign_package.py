@@ -0,0 +1,118 @@
+#!/usr/bin/env python3
+"""
+Sign knowledge base and phone book packages with a detached ed25519 signature.
+
+The app verifies `<package>.sig` (hex-encoded signature of the SHA-256 digest of the package's exact bytes) against the
+public key compiled into src-tauri/src/package_signing.rs before ingesting bundled or imported
+packages. Re-sign a package after every change to it, or the app will refuse it.
+
+Keep the private key out of the repository.
+
+Requires: cryptography (pip install cryptography)
+
+Usage:
+  python sign_package.py generate-key -o package_signing_key.pem   # prints the public key hex
+  python sign_package.py sign --key package_signing_key.pem ../src-tauri/resources/default_kb.json ../src-tauri/resources/phonebook_seed.json
+  python sign_package.py verify --public-key <hex> ../src-tauri/resources/default_kb.json
+"""
+import argparse
+import hashlib
+import sys
+from pathlib import Path
+
+try:
+    from cryptography.exceptions import InvalidSignature
+    from cryptography.hazmat.primitives import serialization
+    from cryptography.hazmat.primitives.asymmetric.ed25519 import Ed25519PrivateKey, Ed25519PublicKey
+except ImportError:
+    print("Error: cryptography not installed. Run: pip install cryptography", file=sys.stderr)
+    sys.exit(1)
+
+
+def public_hex(key: Ed25519PrivateKey) -> str:
+    return key.public_key().public_bytes(serialization.Encoding.Raw, serialization.PublicFormat.Raw).hex()
+
+
+def load_key(path: Path) -> Ed25519PrivateKey:
+    key = serialization.load_pem_private_key(path.read_bytes(), password=None)
+    if not isinstance(key, Ed25519PrivateKey):
+        raise ValueError(f"{path} is not an ed25519 private key")
+    return key
+
+
+def file_digest(path: Path) -> bytes:
+    """SHA-256 of the file, read in chunks (packages can be large)."""
+    digest = hashlib.sha256()
+    with path.open("rb") as f:
+        for chunk in iter(lambda: f.read(1 << 20), b""):
+            digest.update(chunk)
+    return digest.digest()
+
+
+def signature_path(path: Path) -> Path:
+    return path.with_name(path.name + ".sig")
+
+
+def cmd_generate(args) -> int:
+    if args.output.exists():
+        print(f"Error: {args.output} already exists", file=sys.stderr)
+        return 1
+    key = Ed25519PrivateKey.generate()
+    args.output.write_bytes(key.private_bytes(
+        serialization.Encoding.PEM, serialization.PrivateFormat.PKCS8, serialization.NoEncryption()))
+    print(public_hex(key))
+    return 0
+
+
+def cmd_sign(args) -> int:
+    key = load_key(args.key)
+    for path in args.files:
+        signature = key.sign(file_digest(path))
+        signature_path(path).write_text(signature.hex() + "\n")
+        print(f"Signed {path}")
+    print(f"Public key: {public_hex(key)}")
+    return 0
+
+
+def cmd_verify(args) -> int:
+    public_key = Ed25519PublicKey.from_public_bytes(bytes.fromhex(args.public_key))
+    failed = 0
+    for path in args.files:
+        sig_path = signature_path(path)
+        if not sig_path.exists():
+            print(f"{path}: unsigned")
+            failed += 1
+            continue
+        try:
+            public_key.verify(bytes.fromhex(sig_path.read_text().strip()), file_digest(path))
+            print(f"{path}: valid")
+        except (InvalidSignature, ValueError):
+            print(f"{path}: INVALID")
+            failed += 1
+    return 1 if failed else 0
+
+
+def main() -> int:
+    ap = argparse.ArgumentParser(description="Sign or verify KB / phone book packages")
+    sub = ap.add_subparsers(dest="command", required=True)
+
+    gen = sub.add_parser("generate-key", help="Create a new ed25519 signing key")
+    gen.add_argument("-o", "--output", type=Path, required=True, help="Private key PEM path")
+    gen.set_defaults(func=cmd_generate)
+
+    sign = sub.add_parser("sign", help="Write <file>.sig for each file")
+    sign.add_argument("--key", type=Path, required=True, help="Private key PEM path")
+    sign.add_argument("files", type=Path, nargs="+")
+    sign.set_defaults(func=cmd_sign)
+
+    verify = sub.add_parser("verify", help="Check <file>.sig for each file")
+    verify.add_argument("--public-key", required=True, help="Public key as 64 hex chars")
+    verify.add_argument("files", type=Path, nargs="+")
+    verify.set_defaults(func=cmd_verify)
+
+    args = ap.parse_args()
+    return args.func(args)
+
+
+if __name__ == "__main__":
+    sys.exit(main())
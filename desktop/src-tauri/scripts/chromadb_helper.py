@@ -29,8 +29,12 @@ def get_chroma_client(db_path: str):
     return client
 
 
-def initialize_collection(db_path: str, collection_name: str):
-    """Initialize or get ChromaDB collection"""
+# Every collection built before the model was recorded used this model
+LEGACY_EMBEDDING_MODEL = "all-MiniLM-L6-v2"
+
+
+def initialize_collection(db_path: str, collection_name: str, embedding_model: str = None):
+    """Initialize or get ChromaDB collection, recording the embedding model that fills it"""
     try:
         client = get_chroma_client(db_path)
         
@@ -38,17 +42,26 @@ def initialize_collection(db_path: str, collection_name: str):
         try:
             collection = client.get_collection(name=collection_name)
             print(f"INFO: Collection '{collection_name}' already exists", file=sys.stderr)
+            metadata = dict(collection.metadata or {})
+            if "embedding_model" not in metadata:
+                # Stamp collections created before models were recorded
+                metadata["embedding_model"] = LEGACY_EMBEDDING_MODEL if collection.count() > 0 else (embedding_model or LEGACY_EMBEDDING_MODEL)
+                collection.modify(metadata={k: v for k, v in metadata.items() if not k.startswith("hnsw:")})
         except Exception as e:
+            metadata = {"description": "dant knowledge base"}
+            if embedding_model:
+                metadata["embedding_model"] = embedding_model
             collection = client.create_collection(
                 name=collection_name,
-                metadata={"description": "dant knowledge base"}
+                metadata=metadata
             )
             print(f"INFO: Created collection '{collection_name}'", file=sys.stderr)
         
         return {
             "status": "success",
             "collection_name": collection_name,
-            "db_path": db_path
+            "db_path": db_path,
+            "embedding_model": metadata.get("embedding_model")
         }
     except Exception as e:
         return {
@@ -86,16 +99,19 @@ def add_documents(db_path: str, collection_name: str, documents: list):
     }
 
 
-def search_similar(db_path: str, collection_name: str, query_embedding: list, limit: int):
+def search_similar(db_path: str, collection_name: str, query_embedding: list, limit: int, include_embeddings: bool = False):
     """Search for similar documents - optimized for speed"""
     client = get_chroma_client(db_path)
     collection = client.get_collection(name=collection_name)
     
     # Optimize query: only fetch what we need, use efficient search
+    include = ["documents", "distances", "metadatas"]
+    if include_embeddings:
+        include.append("embeddings")
     results = collection.query(
         query_embeddings=[query_embedding],
         n_results=limit,
-        include=["documents", "distances", "metadatas"]  # Only fetch what we need
+        include=include
     )
     
     # Format results (optimized loop)
@@ -105,14 +121,19 @@ def search_similar(db_path: str, collection_name: str, query_embedding: list, li
         documents = results.get("documents", [[]])[0]
         distances = results.get("distances", [[]])[0]
         metadatas = results.get("metadatas", [[]])[0]
+        embeddings = results.get("embeddings") if include_embeddings else None
+        embeddings = embeddings[0] if embeddings is not None and len(embeddings) > 0 else []
         
         for i in range(len(ids)):
-            search_results.append({
+            item = {
                 "id": ids[i],
                 "text": documents[i] if i < len(documents) else "",
                 "score": 1.0 - distances[i] if i < len(distances) else 0.0,
                 "metadata": metadatas[i] if i < len(metadatas) else {}
-            })
+            }
+            if include_embeddings and i < len(embeddings):
+                item["embedding"] = [float(x) for x in embeddings[i]]
+            search_results.append(item)
     
     return {
         "status": "success",
@@ -128,51 +149,406 @@ def get_stats(db_path: str, collection_name: str):
         collection = client.get_collection(name=collection_name)
         count = collection.count()
         
+        # Dimension of stored vectors (None for an empty collection)
+        dimension = None
+        if count > 0:
+            sample = collection.get(limit=1, include=["embeddings"]).get("embeddings")
+            if sample is not None and len(sample) > 0:
+                dimension = len(sample[0])
+        
         return {
             "status": "success",
             "document_count": count,
+            "embedding_dimension": dimension,
             "collection_name": collection_name
         }
     except:
         return {
             "status": "success",
             "document_count": 0,
+            "embedding_dimension": None,
             "collection_name": collection_name
         }
 
 
-def get_by_filter(db_path: str, collection_name: str, where_json: str):
-    """Get documents by metadata filter only (no query embedding). Used for phone book lookup."""
+# Fields the caller can select in get_by_filter, mapped to Chroma's include names
+FILTER_FIELDS = {"text": "documents", "metadata": "metadatas", "embeddings": "embeddings"}
+
+
+def _metadata_sort_key(value):
+    """Order metadata values: numbers, then strings, then anything else"""
+    if isinstance(value, (int, float)) and not isinstance(value, bool):
+        return (0, value)
+    if isinstance(value, str):
+        return (1, value)
+    return (2, json.dumps(value))
+
+
+def _filter_rows(result, include):
+    """Rows of a collection.get() result with only the selected fields"""
+    ids = _flatten(result.get("ids"))
+    documents = _flatten(result.get("documents"))
+    metadatas = _flatten(result.get("metadatas"))
+    embeddings = result.get("embeddings")
+    if embeddings is None:
+        embeddings = []
+    items = []
+    for i in range(len(ids)):
+        item = {"id": ids[i]}
+        if "documents" in include:
+            item["text"] = documents[i] if i < len(documents) else ""
+        if "metadatas" in include:
+            item["metadata"] = (metadatas[i] if i < len(metadatas) else None) or {}
+        if "embeddings" in include:
+            item["embedding"] = [float(x) for x in embeddings[i]] if i < len(embeddings) else []
+        items.append(item)
+    return items
+
+
+def get_by_filter(db_path: str, collection_name: str, where_json: str, options: dict = None):
+    """Get documents by metadata filter only (no query embedding). Used for phone book lookup.
+    `options` pages and sorts the matches: offset, limit, include (text/metadata/embeddings), sort_by (a
+    metadata field; documents without it come last) and descending. Also returns the number of matches."""
+    options = options or {}
+    client = get_chroma_client(db_path)
+    collection = client.get_collection(name=collection_name)
+    where = json.loads(where_json) or None
+    offset = int(options.get("offset") or 0)
+    limit = options.get("limit")
+    include = [FILTER_FIELDS[f] for f in (options.get("include") or ["text", "metadata"])]
+    sort_by = options.get("sort_by")
+
+    if sort_by:
+        # Chroma cannot order results: sort the matching ids by the field, then fetch the page by id
+        matched = collection.get(where=where, include=["metadatas"])
+        ids = _flatten(matched.get("ids"))
+        metadatas = _flatten(matched.get("metadatas"))
+        keyed = []
+        unsorted = []
+        for i, doc_id in enumerate(ids):
+            value = ((metadatas[i] if i < len(metadatas) else None) or {}).get(sort_by)
+            if value is None:
+                unsorted.append(doc_id)
+            else:
+                keyed.append((_metadata_sort_key(value), doc_id))
+        keyed.sort(key=lambda k: k[0], reverse=bool(options.get("descending")))
+        ordered = [doc_id for _, doc_id in keyed] + unsorted
+        page_ids = ordered[offset:] if limit is None else ordered[offset:offset + int(limit)]
+        rows = _filter_rows(collection.get(ids=page_ids, include=include), include) if page_ids else []
+        # A get by ids does not keep the order of the ids
+        by_id = {row["id"]: row for row in rows}
+        return {
+            "status": "success",
+            "documents": [by_id[doc_id] for doc_id in page_ids if doc_id in by_id],
+            "total": len(ordered)
+        }
+
+    total = len(_flatten(collection.get(where=where, include=[]).get("ids")))
+    if limit is None:
+        items = _filter_rows(collection.get(where=where, include=include), include)[offset:]
+    else:
+        items = _filter_rows(collection.get(where=where, offset=offset, limit=int(limit), include=include), include)
+    return {
+        "status": "success",
+        "documents": items,
+        "total": total
+    }
+
+
+def _flatten(values):
+    """Chroma may return list-of-lists for some APIs; flatten one level if needed"""
+    values = values or []
+    if values and isinstance(values[0], list):
+        return values[0]
+    return values
+
+
+def upsert_documents(db_path: str, collection_name: str, documents: list):
+    """Insert or replace documents by id. Reports how many ids were new vs. already present."""
+    client = get_chroma_client(db_path)
+    collection = client.get_collection(name=collection_name)
+
+    ids = []
+    texts = []
+    embeddings = []
+    metadatas = []
+
+    for doc in documents:
+        ids.append(doc["id"])
+        texts.append(doc["text"])
+        embeddings.append(doc["embedding"])
+        metadatas.append(doc.get("metadata", {}))
+
+    if not ids:
+        return {"status": "success", "inserted": 0, "updated": 0}
+
+    existing = set(_flatten(collection.get(ids=ids, include=[]).get("ids")))
+    unique_ids = set(ids)
+
+    collection.upsert(
+        ids=ids,
+        embeddings=embeddings,
+        documents=texts,
+        metadatas=metadatas
+    )
+
+    return {
+        "status": "success",
+        "inserted": len(unique_ids - existing),
+        "updated": len(unique_ids & existing)
+    }
+
+
+def delete_documents(db_path: str, collection_name: str, ids: list):
+    """Delete documents by id. Ids that do not exist are ignored and not counted."""
+    client = get_chroma_client(db_path)
+    collection = client.get_collection(name=collection_name)
+
+    if not ids:
+        return {"status": "success", "deleted": 0}
+
+    existing = _flatten(collection.get(ids=ids, include=[]).get("ids"))
+    if existing:
+        collection.delete(ids=existing)
+
+    return {
+        "status": "success",
+        "deleted": len(existing)
+    }
+
+
+def delete_by_filter(db_path: str, collection_name: str, where_json: str):
+    """Delete all documents matching a metadata filter"""
     client = get_chroma_client(db_path)
     collection = client.get_collection(name=collection_name)
     where = json.loads(where_json)
+    if not where:
+        # An empty filter would match (and delete) every document
+        return {"status": "error", "message": "delete_by_filter needs a non-empty filter"}
+
+    matching = _flatten(collection.get(where=where, include=[]).get("ids"))
+    if matching:
+        collection.delete(ids=matching)
+
+    return {
+        "status": "success",
+        "deleted": len(matching)
+    }
+
+
+def get_documents(db_path: str, collection_name: str, ids: list):
+    """Get documents by id. Ids that do not exist are reported in 'missing'."""
+    client = get_chroma_client(db_path)
+    collection = client.get_collection(name=collection_name)
+
+    if not ids:
+        return {"status": "success", "documents": [], "missing": []}
+
     result = collection.get(
-        where=where,
+        ids=ids,
         include=["documents", "metadatas"]
     )
-    ids = result.get("ids") or []
-    documents = result.get("documents") or []
-    metadatas = result.get("metadatas") or []
-    # Chroma may return ids/documents/metadatas as list-of-lists for some APIs; flatten if needed
-    if ids and isinstance(ids[0], list):
-        ids = ids[0]
-    if documents and isinstance(documents[0], list):
-        documents = documents[0]
-    if metadatas and isinstance(metadatas[0], list):
-        metadatas = metadatas[0]
+    found_ids = _flatten(result.get("ids"))
+    documents = _flatten(result.get("documents"))
+    metadatas = _flatten(result.get("metadatas"))
     items = []
-    for i in range(len(ids)):
+    for i in range(len(found_ids)):
         items.append({
-            "id": ids[i] if i < len(ids) else "",
+            "id": found_ids[i],
             "text": documents[i] if i < len(documents) else "",
             "metadata": metadatas[i] if i < len(metadatas) else {}
         })
+    found = set(found_ids)
+    return {
+        "status": "success",
+        "documents": items,
+        "missing": [i for i in ids if i not in found]
+    }
+
+
+def export_documents(db_path: str, collection_name: str):
+    """Get every document in a collection with its embedding (for writing a KB package)"""
+    client = get_chroma_client(db_path)
+    collection = client.get_collection(name=collection_name)
+    result = collection.get(include=["documents", "metadatas", "embeddings"])
+    ids = _flatten(result.get("ids"))
+    documents = _flatten(result.get("documents"))
+    metadatas = _flatten(result.get("metadatas"))
+    embeddings = result.get("embeddings")
+    if embeddings is None:
+        embeddings = []
+    items = []
+    for i in range(len(ids)):
+        items.append({
+            "id": ids[i],
+            "text": documents[i] if i < len(documents) else "",
+            "metadata": (metadatas[i] if i < len(metadatas) else None) or {},
+            "embedding": [float(x) for x in embeddings[i]] if i < len(embeddings) else []
+        })
+    return {
+        "status": "success",
+        "documents": items,
+        "collection_metadata": collection.metadata or {}
+    }
+
+
+def list_ids(db_path: str, collection_name: str):
+    """Get every document id in a collection (no documents or embeddings)"""
+    client = get_chroma_client(db_path)
+    collection = client.get_collection(name=collection_name)
+    result = collection.get(include=[])
+    return {
+        "status": "success",
+        "ids": _flatten(result.get("ids"))
+    }
+
+
+def swap_collections(db_path: str, collection_name: str, replacement_name: str):
+    """Replace a collection with a fully built replacement by renaming.
+    Idempotent: calling it again after an interruption completes the swap."""
+    client = get_chroma_client(db_path)
+    retired_name = f"{collection_name}__retired"
+    names = set()
+    for c in client.list_collections():
+        names.add(c if isinstance(c, str) else c.name)
+    if replacement_name in names:
+        if collection_name in names:
+            if retired_name in names:
+                client.delete_collection(name=retired_name)
+            client.get_collection(name=collection_name).modify(name=retired_name)
+            names.add(retired_name)
+        client.get_collection(name=replacement_name).modify(name=collection_name)
+    elif collection_name not in names:
+        return {"status": "error", "message": f"Neither {collection_name} nor {replacement_name} exists"}
+    if retired_name in names:
+        client.delete_collection(name=retired_name)
+    return {"status": "success", "collection_name": collection_name}
+
+
+def _vector_length(value):
+    try:
+        return len(value) if value is not None else 0
+    except TypeError:
+        return 0
+
+
+def verify_store(db_path: str, page_size: int = 256):
+    """Integrity check of the whole store: sqlite integrity, and per collection that it opens, that every
+    stored id has a vector in the index (no dangling ids) and that all vectors have the same dimension.
+    Problem rows are returned with their text and metadata so the caller can re-embed them."""
+    import sqlite3
+    sqlite_ok = True
+    sqlite_message = "ok"
+    sqlite_path = os.path.join(db_path, "chroma.sqlite3")
+    if os.path.exists(sqlite_path):
+        try:
+            conn = sqlite3.connect(f"file:{sqlite_path}?mode=ro", uri=True)
+            rows = conn.execute("PRAGMA integrity_check").fetchall()
+            conn.close()
+            messages = [r[0] for r in rows]
+            sqlite_ok = messages == ["ok"]
+            sqlite_message = "; ".join(messages[:10])
+        except Exception as e:
+            sqlite_ok = False
+            sqlite_message = str(e)
+
+    client = get_chroma_client(db_path)
+    checks = []
+    for c in client.list_collections():
+        name = c if isinstance(c, str) else c.name
+        check = {
+            "name": name,
+            "opened": False,
+            "error": None,
+            "count": 0,
+            "vector_count": 0,
+            "dimension": None,
+            "embedding_model": None,
+            "missing_vectors": [],
+            "wrong_dimension": []
+        }
+        checks.append(check)
+        try:
+            collection = client.get_collection(name=name)
+            check["count"] = collection.count()
+            check["embedding_model"] = (collection.metadata or {}).get("embedding_model")
+            check["opened"] = True
+        except Exception as e:
+            check["error"] = str(e)
+            continue
+
+        rows = []
+        offset = 0
+        try:
+            while True:
+                page = collection.get(offset=offset, limit=page_size, include=["documents", "metadatas"])
+                ids = _flatten(page.get("ids"))
+                if not ids:
+                    break
+                documents = _flatten(page.get("documents"))
+                metadatas = _flatten(page.get("metadatas"))
+                try:
+                    vectors = list(collection.get(ids=ids, include=["embeddings"]).get("embeddings"))
+                except Exception:
+                    # Fetch one by one to find the ids whose vector is missing
+                    vectors = []
+                    for doc_id in ids:
+                        try:
+                            got = collection.get(ids=[doc_id], include=["embeddings"]).get("embeddings")
+                            vectors.append(got[0] if got is not None and len(got) > 0 else None)
+                        except Exception:
+                            vectors.append(None)
+                for i, doc_id in enumerate(ids):
+                    rows.append((
+                        doc_id,
+                        documents[i] if i < len(documents) else "",
+                        (metadatas[i] if i < len(metadatas) else None) or {},
+                        _vector_length(vectors[i] if i < len(vectors) else None)
+                    ))
+                offset += len(ids)
+        except Exception as e:
+            check["error"] = str(e)
+
+        lengths = {}
+        for _, _, _, length in rows:
+            if length:
+                lengths[length] = lengths.get(length, 0) + 1
+        dimension = max(lengths, key=lengths.get) if lengths else None
+        check["dimension"] = dimension
+        for doc_id, text, metadata, length in rows:
+            entry = {"id": doc_id, "text": text or "", "metadata": metadata}
+            if not length:
+                check["missing_vectors"].append(entry)
+            elif length != dimension:
+                check["wrong_dimension"].append(entry)
+            else:
+                check["vector_count"] += 1
+        check["vector_count"] += len(check["wrong_dimension"])
+
     return {
         "status": "success",
-        "documents": items
+        "sqlite_ok": sqlite_ok,
+        "sqlite_message": sqlite_message,
+        "collections": checks
     }
 
 
+def list_collections(db_path: str):
+    """List every collection with its document count and recorded embedding model"""
+    client = get_chroma_client(db_path)
+    items = []
+    for c in client.list_collections():
+        name = c if isinstance(c, str) else c.name
+        try:
+            collection = client.get_collection(name=name)
+            count = collection.count()
+            embedding_model = (collection.metadata or {}).get("embedding_model")
+        except Exception:
+            count, embedding_model = None, None
+        items.append({"name": name, "count": count, "embedding_model": embedding_model})
+    return {"status": "success", "collections": items}
+
+
 def delete_collection(db_path: str, collection_name: str):
     """Delete a ChromaDB collection"""
     try:
@@ -211,7 +587,8 @@ def main():
                 sys.exit(1)
             db_path = sys.argv[2]
             collection_name = sys.argv[3]
-            result = initialize_collection(db_path, collection_name)
+            embedding_model = sys.argv[4] if len(sys.argv) > 4 else None
+            result = initialize_collection(db_path, collection_name, embedding_model)
             print(json.dumps(result))
             sys.stdout.flush()  # Ensure output is flushed
             
@@ -227,9 +604,10 @@ def main():
             db_path = sys.argv[2]
             collection_name = sys.argv[3]
             limit = int(sys.argv[4])
+            include_embeddings = len(sys.argv) > 5 and sys.argv[5] == "embeddings"
             query_embedding_json = sys.stdin.read()
             query_embedding = json.loads(query_embedding_json)
-            result = search_similar(db_path, collection_name, query_embedding, limit)
+            result = search_similar(db_path, collection_name, query_embedding, limit, include_embeddings)
             print(json.dumps(result))
             
         elif command == "stats":
@@ -245,7 +623,68 @@ def main():
             db_path = sys.argv[2]
             collection_name = sys.argv[3]
             where_json = sys.argv[4]
-            result = get_by_filter(db_path, collection_name, where_json)
+            options = json.loads(sys.argv[5]) if len(sys.argv) > 5 else None
+            result = get_by_filter(db_path, collection_name, where_json, options)
+            print(json.dumps(result))
+
+        elif command == "upsert":
+            db_path = sys.argv[2]
+            collection_name = sys.argv[3]
+            documents = json.loads(sys.stdin.read())
+            result = upsert_documents(db_path, collection_name, documents)
+            print(json.dumps(result))
+
+        elif command == "delete_documents":
+            db_path = sys.argv[2]
+            collection_name = sys.argv[3]
+            ids = json.loads(sys.stdin.read())
+            result = delete_documents(db_path, collection_name, ids)
+            print(json.dumps(result))
+
+        elif command == "delete_by_filter":
+            if len(sys.argv) < 5:
+                print("ERROR: Missing arguments for delete_by_filter command", file=sys.stderr)
+                sys.exit(1)
+            db_path = sys.argv[2]
+            collection_name = sys.argv[3]
+            where_json = sys.argv[4]
+            result = delete_by_filter(db_path, collection_name, where_json)
+            print(json.dumps(result))
+
+        elif command == "get_documents":
+            db_path = sys.argv[2]
+            collection_name = sys.argv[3]
+            ids = json.loads(sys.stdin.read())
+            result = get_documents(db_path, collection_name, ids)
+            print(json.dumps(result))
+
+        elif command == "export":
+            db_path = sys.argv[2]
+            collection_name = sys.argv[3]
+            result = export_documents(db_path, collection_name)
+            print(json.dumps(result))
+
+        elif command == "list_ids":
+            db_path = sys.argv[2]
+            collection_name = sys.argv[3]
+            result = list_ids(db_path, collection_name)
+            print(json.dumps(result))
+
+        elif command == "swap_collections":
+            db_path = sys.argv[2]
+            collection_name = sys.argv[3]
+            replacement_name = sys.argv[4]
+            result = swap_collections(db_path, collection_name, replacement_name)
+            print(json.dumps(result))
+
+        elif command == "verify":
+            db_path = sys.argv[2]
+            result = verify_store(db_path)
+            print(json.dumps(result))
+
+        elif command == "list_collections":
+            db_path = sys.argv[2]
+            result = list_collections(db_path)
             print(json.dumps(result))
 
         elif command == "delete_collection":
@@ -18,6 +18,9 @@ except ImportError:
 _model = None
 _model_name = "all-MiniLM-L6-v2"  # 384 dimensions
 
+# Cross-encoder instances for reranking, keyed by model name
+_cross_encoders = {}
+
 
 def load_model():
     """Load embedding model"""
@@ -76,6 +79,126 @@ def generate_batch(texts: list):
         return {"status": "error", "message": str(e)}
 
 
+# Extra embedding models (e.g. the target of a re-embed), keyed by model name
+_models = {}
+
+
+def generate_batch_with_model(texts: list, model_name: str, normalize: bool = True, max_seq_length=None):
+    """Generate embeddings (normalized by default) for multiple texts with a specific model"""
+    try:
+        if model_name == _model_name:
+            if _model is None:
+                load_model()
+            model = _model
+        else:
+            if model_name not in _models:
+                _models[model_name] = SentenceTransformer(model_name)
+            model = _models[model_name]
+        # The model is cached and shared (the default one also serves generate_batch), so the token limit
+        # applies to this call only
+        previous_max_seq_length = model.max_seq_length
+        if max_seq_length:
+            model.max_seq_length = int(max_seq_length)
+        try:
+            embeddings = model.encode(
+                texts,
+                convert_to_numpy=True,
+                show_progress_bar=False,
+                normalize_embeddings=normalize
+            ).tolist()
+        finally:
+            model.max_seq_length = previous_max_seq_length
+        return {
+            "status": "success",
+            "embeddings": embeddings,
+            "count": len(embeddings),
+            "dimension": len(embeddings[0]) if embeddings else 0
+        }
+    except Exception as e:
+        return {"status": "error", "message": str(e)}
+
+
+def rerank(query: str, passages: list, model_name: str):
+    """Score (query, passage) pairs with a cross-encoder. Higher scores are more relevant."""
+    try:
+        from sentence_transformers import CrossEncoder
+    except ImportError as e:
+        return {"status": "error", "message": str(e)}
+
+    try:
+        if model_name not in _cross_encoders:
+            _cross_encoders[model_name] = CrossEncoder(model_name)
+        model = _cross_encoders[model_name]
+        if not passages:
+            return {"status": "success", "scores": []}
+        scores = model.predict(
+            [(query, p) for p in passages],
+            show_progress_bar=False
+        )
+        return {
+            "status": "success",
+            "scores": [float(s) for s in scores]
+        }
+    except Exception as e:
+        return {"status": "error", "message": str(e)}
+
+
+def serve():
+    """Warm worker: load the model, print {"ready": true}, then answer one JSON request per stdin line.
+
+    Requests are {"id", "command": "embed", "text"}, {"id", "command": "batch", "texts"},
+    {"id", "command": "batch_model", "texts", "model", "normalize", "max_seq_length"} or
+    {"id", "command": "rerank", "query", "passages", "model"}; each response is the result of generate_embedding /
+    generate_batch / generate_batch_with_model / rerank with the same "id". An empty line or EOF stops the worker.
+    """
+    # Model loading may print progress; keep stdout for the protocol
+    _save_stdout = sys.stdout
+    try:
+        sys.stdout = sys.stderr
+        load_result = load_model()
+    finally:
+        sys.stdout = _save_stdout
+    if load_result["status"] != "success":
+        print(json.dumps({"error": load_result.get("message", "Failed to load model")}), flush=True)
+        sys.exit(1)
+    print(json.dumps({
+        "ready": True,
+        "model": _model_name,
+        "dimension": _model.get_sentence_embedding_dimension(),
+    }), flush=True)
+
+    while True:
+        line = sys.stdin.readline()
+        if not line or not line.strip():
+            break
+        request_id = None
+        try:
+            req = json.loads(line)
+            request_id = req.get("id")
+            command = req.get("command")
+            if command == "embed":
+                result = generate_embedding(req["text"])
+            elif command == "batch":
+                result = generate_batch(req["texts"])
+            elif command == "batch_model":
+                result = generate_batch_with_model(
+                    req["texts"],
+                    req["model"],
+                    req.get("normalize", True),
+                    req.get("max_seq_length"),
+                )
+            elif command == "rerank":
+                result = rerank(req["query"], req["passages"], req["model"])
+            else:
+                result = {"status": "error", "message": f"Unknown command: {command}"}
+        except json.JSONDecodeError as e:
+            result = {"status": "error", "message": f"Invalid JSON: {e}"}
+        except Exception as e:
+            result = {"status": "error", "message": str(e)}
+        result["id"] = request_id
+        print(json.dumps(result), flush=True)
+
+
 def main():
     if len(sys.argv) < 2:
         print("ERROR: Missing command", file=sys.stderr)
@@ -95,6 +218,24 @@ def main():
             result = generate_batch(texts)
             print(json.dumps(result))
             
+        elif command == "batch_model":
+            request = json.loads(sys.stdin.read())
+            result = generate_batch_with_model(
+                request["texts"],
+                request["model"],
+                request.get("normalize", True),
+                request.get("max_seq_length"),
+            )
+            print(json.dumps(result))
+
+        elif command == "serve":
+            serve()
+
+        elif command == "rerank":
+            request = json.loads(sys.stdin.read())
+            result = rerank(request["query"], request["passages"], request["model"])
+            print(json.dumps(result))
+
         else:
             print(f"ERROR: Unknown command: {command}", file=sys.stderr)
             sys.exit(1)
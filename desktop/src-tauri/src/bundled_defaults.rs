@@ -1,18 +1,26 @@
 // Bundled defaults: resolve and initialize default model and global KB from bundled resources.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use std::fs;
 use tauri::{AppHandle, Manager};
 
-use crate::embeddings::generate_embeddings_batch;
+use crate::embeddings::embed_passages;
+use crate::error::ConfidantError;
 use crate::llm::{initialize_model, is_model_loaded};
 use crate::vector_store::{
     initialize_vector_store,
-    add_documents_to_collection,
     get_collection_stats_by_name,
+    upsert_documents,
+    delete_documents,
+    get_all_documents,
 };
 use crate::vector_store::VectorDocument;
+use crate::kb_binary::{is_binary_package, BinaryPackageReader};
+use crate::kb_package::{import_package_file, load_installed_packages};
+use crate::package_signing::{read_trusted_package, verify_package_file, SignatureStatus};
 
 /// Default global KB collection name (must match frontend).
 const GLOBAL_KB_COLLECTION: &str = "dant_knowledge_global";
@@ -32,6 +40,9 @@ const DEFAULT_MODEL_URL: &str = "https://huggingface.co/bartowski/Llama-3.2-3B-I
 /// Relative path to bundled KB JSON in resources (same format as URL-loaded package: manifest, documents, embeddings).
 const BUNDLED_KB_FILENAME: &str = "default_kb.json";
 
+/// Binary (.ckb) variant of the bundled KB; preferred over the JSON file when both are shipped.
+const BUNDLED_KB_BINARY_FILENAME: &str = "default_kb.ckb";
+
 /// Phone book collection and seed file.
 const PHONEBOOK_COLLECTION: &str = "dant_phonebook";
 const PHONEBOOK_SEED_FILENAME: &str = "phonebook_seed.json";
@@ -46,6 +57,12 @@ pub struct BundledDefaultsStatus {
     /// When packaged and no model found: path where the default model should be saved (app data).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default_model_output_path: Option<String>,
+    /// Signature status of the bundled KB file, when one was found.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kb_signature: Option<SignatureStatus>,
+    /// Signature status of the bundled phone book seed, when one was found.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phonebook_signature: Option<SignatureStatus>,
 }
 
 /// Find project root (directory that contains "data" and ideally "desktop") for dev fallback.
@@ -150,8 +167,8 @@ fn resolve_bundled_model_path(app: &AppHandle) -> Option<PathBuf> {
     None
 }
 
-/// Resolve the path to the bundled default KB JSON file.
-/// Tries: (1) env, (2) resource dir, (3) dev: desktop/test_knowledge_base.json or project data/default_kb.json.
+/// Resolve the path to the bundled default KB file (binary .ckb or JSON).
+/// Tries: (1) env, (2) resource dir (default_kb.ckb before default_kb.json), (3) dev: desktop/test_knowledge_base.json or project data/default_kb.json.
 fn resolve_bundled_kb_path(app: &AppHandle) -> Option<PathBuf> {
     if let Ok(env_path) = std::env::var("CONFIDANT_BUNDLED_KB_PATH") {
         let p = PathBuf::from(&env_path);
@@ -161,7 +178,12 @@ fn resolve_bundled_kb_path(app: &AppHandle) -> Option<PathBuf> {
     }
     if let Some(resource_dir) = app.path().resource_dir().ok() {
         for base in [resource_dir.clone(), resource_dir.join("resources")] {
-            for rel in [BUNDLED_KB_FILENAME, &format!("kb/{}", BUNDLED_KB_FILENAME)] {
+            for rel in [
+                BUNDLED_KB_BINARY_FILENAME,
+                &format!("kb/{}", BUNDLED_KB_BINARY_FILENAME),
+                BUNDLED_KB_FILENAME,
+                &format!("kb/{}", BUNDLED_KB_FILENAME),
+            ] {
                 let p = base.join(rel);
                 if p.exists() {
                     return Some(p);
@@ -231,122 +253,738 @@ fn resolve_phonebook_seed_path(app: &AppHandle) -> Option<PathBuf> {
     None
 }
 
-/// Load phone book seed JSON and ingest into dant_phonebook (generates embeddings at load time).
-async fn ingest_phonebook_from_path(app: &AppHandle, path: &Path) -> Result<(), String> {
-    let content = fs::read_to_string(path)
-        .map_err(|e| format!("Failed to read phonebook seed: {}", e))?;
-    let entries: Vec<serde_json::Value> = serde_json::from_str(&content)
-        .map_err(|e| format!("Invalid phonebook seed JSON: {}", e))?;
+/// Phone book row; the fields of phonebook_seed.json and of the dant_phonebook metadata.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct PhonebookEntry {
+    #[serde(default)]
+    pub country: String,
+    #[serde(default)]
+    pub postal_code: String,
+    #[serde(default)]
+    pub profession: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub phone: String,
+    #[serde(default)]
+    pub address: String,
+    #[serde(default)]
+    pub city: String,
+    #[serde(default)]
+    pub state: String,
+}
 
-    if entries.is_empty() {
-        return Ok(());
+impl PhonebookEntry {
+    /// Text that is embedded and stored as the document.
+    fn document_text(&self) -> String {
+        format!("{} — {}, {}", self.name, self.profession, self.phone)
     }
 
-    let texts: Vec<String> = entries
-        .iter()
-        .map(|e| {
-            let name = e["name"].as_str().unwrap_or("");
-            let profession = e["profession"].as_str().unwrap_or("");
-            let phone = e["phone"].as_str().unwrap_or("");
-            format!("{} — {}, {}", name, profession, phone)
+    fn metadata(&self) -> serde_json::Value {
+        serde_json::json!({
+            "country": self.country,
+            "postal_code": self.postal_code,
+            "profession": self.profession,
+            "name": self.name,
+            "phone": self.phone,
+            "address": self.address,
+            "city": self.city,
+            "state": self.state,
         })
-        .collect();
+    }
+}
+
+/// Embed phone book entries and upsert them into dant_phonebook in batches. `source`, when set, is recorded
+/// in the metadata (e.g. "import").
+async fn ingest_phonebook_entries(
+    app: &AppHandle,
+    entries: &[(String, PhonebookEntry)],
+    source: Option<&str>,
+) -> Result<(), ConfidantError> {
+    if entries.is_empty() {
+        return Ok(());
+    }
 
-    let embeddings = generate_embeddings_batch(app.clone(), texts.clone()).await?;
+    let texts: Vec<String> = entries.iter().map(|(_, e)| e.document_text()).collect();
+    let embeddings = embed_passages(app.clone(), texts.clone()).await?;
 
     const BATCH_SIZE: usize = 20;
     for chunk_start in (0..entries.len()).step_by(BATCH_SIZE) {
         let chunk_end = (chunk_start + BATCH_SIZE).min(entries.len());
-        let batch_entries = &entries[chunk_start..chunk_end];
-        let batch_texts = &texts[chunk_start..chunk_end];
-        let batch_embeddings = &embeddings[chunk_start..chunk_end];
-
-        let batch_docs: Vec<VectorDocument> = batch_entries
+        let batch_docs: Vec<VectorDocument> = entries[chunk_start..chunk_end]
             .iter()
-            .zip(batch_texts.iter())
-            .zip(batch_embeddings.iter())
-            .enumerate()
-            .map(|(i, ((e, text), emb))| {
-                let id = format!("phonebook_{}_{}", chunk_start + i, e["name"].as_str().unwrap_or("").replace(' ', "_"));
-                let metadata = serde_json::json!({
-                    "country": e["country"].as_str().unwrap_or(""),
-                    "postal_code": e["postal_code"].as_str().unwrap_or(""),
-                    "profession": e["profession"].as_str().unwrap_or(""),
-                    "name": e["name"].as_str().unwrap_or(""),
-                    "phone": e["phone"].as_str().unwrap_or(""),
-                    "address": e.get("address").and_then(|v| v.as_str()).unwrap_or(""),
-                    "city": e.get("city").and_then(|v| v.as_str()).unwrap_or(""),
-                    "state": e.get("state").and_then(|v| v.as_str()).unwrap_or(""),
-                });
+            .zip(&texts[chunk_start..chunk_end])
+            .zip(&embeddings[chunk_start..chunk_end])
+            .map(|(((id, e), text), emb)| {
+                let mut metadata = e.metadata();
+                if let Some(source) = source {
+                    metadata["source"] = serde_json::json!(source);
+                }
                 VectorDocument {
-                    id,
+                    id: id.clone(),
                     text: text.clone(),
                     embedding: emb.clone(),
                     metadata,
                 }
             })
             .collect();
-        add_documents_to_collection(app.clone(), PHONEBOOK_COLLECTION.to_string(), batch_docs).await?;
+        upsert_documents(app.clone(), PHONEBOOK_COLLECTION.to_string(), batch_docs).await?;
     }
 
     Ok(())
 }
 
-/// Load bundled KB JSON and ingest into global collection.
-/// Expects same format as frontend KnowledgeBasePackage: { manifest, documents: [{ id, text, metadata }], embeddings: number[][] }.
-async fn ingest_kb_from_path(app: &AppHandle, path: &Path) -> Result<(), String> {
-    let content = fs::read_to_string(path)
-        .map_err(|e| format!("Failed to read bundled KB file: {}", e))?;
-    let data: serde_json::Value = serde_json::from_str(&content)
-        .map_err(|e| format!("Invalid bundled KB JSON: {}", e))?;
-
-    let documents = data["documents"]
-        .as_array()
-        .ok_or("Bundled KB: missing 'documents' array")?;
-    let embeddings = data["embeddings"]
-        .as_array()
-        .ok_or("Bundled KB: missing 'embeddings' array")?;
-    if documents.len() != embeddings.len() {
-        return Err(format!(
-            "Bundled KB: documents length {} != embeddings length {}",
-            documents.len(),
-            embeddings.len()
+/// Marker of what was last seeded from bundled files (data/bundled_seeds.json), so a new app release can
+/// apply only what changed in phonebook_seed.json and the default KB.
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct BundledSeedMarker {
+    #[serde(default)]
+    phonebook: Option<SeedRecord>,
+    #[serde(default)]
+    kb: Option<SeedRecord>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SeedRecord {
+    /// SHA-256 of the bundled file.
+    file_hash: String,
+    /// Phone book only: document id -> content hash of the entry.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    entries: BTreeMap<String, String>,
+}
+
+/// Get bundled seed marker path (data/bundled_seeds.json).
+fn get_seed_marker_path(app: &AppHandle) -> Result<PathBuf, ConfidantError> {
+    let base_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| ConfidantError::io(format!("Failed to get app data dir: {}", e)))?;
+    let data_dir = base_dir.join("data");
+    fs::create_dir_all(&data_dir)
+        .map_err(|e| ConfidantError::io(format!("Failed to create data directory: {}", e)))?;
+    Ok(data_dir.join("bundled_seeds.json"))
+}
+
+fn load_seed_marker(app: &AppHandle) -> Result<BundledSeedMarker, ConfidantError> {
+    let path = get_seed_marker_path(app)?;
+    if !path.exists() {
+        return Ok(BundledSeedMarker::default());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| ConfidantError::io(format!("Failed to read bundled seed marker: {}", e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| ConfidantError::internal(format!("Failed to parse bundled seed marker: {}", e)))
+}
+
+fn save_seed_marker(app: &AppHandle, marker: &BundledSeedMarker) -> Result<(), ConfidantError> {
+    let path = get_seed_marker_path(app)?;
+    let content = serde_json::to_string_pretty(marker)
+        .map_err(|e| ConfidantError::internal(format!("Failed to serialize bundled seed marker: {}", e)))?;
+    fs::write(&path, content)
+        .map_err(|e| ConfidantError::io(format!("Failed to write bundled seed marker: {}", e)))
+}
+
+/// SHA-256 of a file, streamed (the KB can be large).
+fn file_sha256(path: &Path) -> Result<String, ConfidantError> {
+    let mut file = fs::File::open(path)
+        .map_err(|e| ConfidantError::io(format!("Failed to open {}: {}", path.display(), e)))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)
+        .map_err(|e| ConfidantError::io(format!("Failed to read {}: {}", path.display(), e)))?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Stable id of a seed entry: country, postal code and name, so a corrected phone number or address updates
+/// the entry in place. A repeated key gets a numeric suffix in file order.
+fn seed_entry_ids(entries: &[PhonebookEntry]) -> Vec<String> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    entries
+        .iter()
+        .map(|e| {
+            let key = format!("{}|{}|{}", e.country.trim(), e.postal_code.trim(), e.name.trim().to_lowercase());
+            let id = format!("phonebook_{}", &hex::encode(Sha256::digest(key.as_bytes()))[..16]);
+            let count = seen.entry(id.clone()).or_insert(0);
+            *count += 1;
+            if *count == 1 { id } else { format!("{}_{}", id, count) }
+        })
+        .collect()
+}
+
+fn seed_entry_hash(entry: &PhonebookEntry) -> String {
+    hex::encode(Sha256::digest(entry.metadata().to_string().as_bytes()))
+}
+
+/// Ids of the old positional scheme (`phonebook_{index}_{name}`).
+fn is_legacy_seed_id(id: &str) -> bool {
+    id.strip_prefix("phonebook_")
+        .and_then(|rest| rest.split_once('_'))
+        .is_some_and(|(index, _)| !index.is_empty() && index.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Bring dant_phonebook in line with the bundled seed: embed and upsert new and changed entries, delete entries
+/// the seed no longer has. `previous` is what was seeded last time (None before markers existed, in which case
+/// entries under legacy positional ids are replaced). Imported entries and personal contacts are not touched.
+/// The seed must be signed (see package_signing.rs).
+async fn sync_phonebook_seed(
+    app: &AppHandle,
+    path: &Path,
+    previous: Option<&SeedRecord>,
+    collection_empty: bool,
+) -> Result<SeedRecord, ConfidantError> {
+    // Parse the same bytes whose signature was checked
+    let (content, _) = read_trusted_package(path, false)?;
+    let file_hash = hex::encode(Sha256::digest(&content));
+    let entries: Vec<PhonebookEntry> = serde_json::from_slice(&content)
+        .map_err(|e| ConfidantError::validation(format!("Invalid phonebook seed JSON: {}", e)))?;
+
+    let rows: Vec<(String, PhonebookEntry)> = seed_entry_ids(&entries).into_iter().zip(entries).collect();
+    let hashes: BTreeMap<String, String> = rows.iter().map(|(id, e)| (id.clone(), seed_entry_hash(e))).collect();
+    let seeded = if collection_empty {
+        BTreeMap::new()
+    } else {
+        previous.map(|p| p.entries.clone()).unwrap_or_default()
+    };
+
+    let mut removed: Vec<String> = seeded.keys().filter(|id| !hashes.contains_key(*id)).cloned().collect();
+    if previous.is_none() && !collection_empty {
+        let existing = get_all_documents(app, PHONEBOOK_COLLECTION).await?;
+        removed.extend(existing.into_iter().map(|d| d.id).filter(|id| is_legacy_seed_id(id)));
+    }
+    let changed: Vec<(String, PhonebookEntry)> = rows
+        .into_iter()
+        .filter(|(id, _)| seeded.get(id) != hashes.get(id))
+        .collect();
+
+    #[cfg(debug_assertions)]
+    eprintln!(
+        "[Bundled] Phone book seed: {} new or changed, {} removed, {} unchanged",
+        changed.len(),
+        removed.len(),
+        hashes.len() - changed.len()
+    );
+
+    ingest_phonebook_entries(app, &changed, Some("seed")).await?;
+    if !removed.is_empty() {
+        delete_documents(app.clone(), PHONEBOOK_COLLECTION.to_string(), removed).await?;
+    }
+
+    Ok(SeedRecord { file_hash, entries: hashes })
+}
+
+/// File format of a phone book import.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PhonebookImportFormat {
+    Vcard,
+    Csv,
+}
+
+/// Values for fields a file leaves empty (contact exports rarely carry a country or profession).
+#[derive(Debug, Deserialize, Default)]
+pub struct PhonebookImportDefaults {
+    #[serde(default)]
+    pub country: Option<String>,
+    #[serde(default)]
+    pub profession: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedPhonebookRow {
+    /// CSV row (the header is row 1) or vCard position, 1-based.
+    pub row: usize,
+    pub name: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhonebookImportReport {
+    pub format: PhonebookImportFormat,
+    pub dry_run: bool,
+    /// Entries that passed validation, as they are (or on a dry run, would be) stored.
+    pub entries: Vec<PhonebookEntry>,
+    pub rejected: Vec<RejectedPhonebookRow>,
+    /// Entries written to dant_phonebook; 0 on a dry run.
+    pub imported: usize,
+}
+
+/// Header aliases per phone book field, in order of preference (compared after normalize_header).
+const CSV_COLUMNS: &[(&str, &[&str])] = &[
+    ("name", &["name", "full name", "display name", "organization", "organisation", "organization name", "company"]),
+    ("profession", &["profession", "job title", "title", "role", "service", "category", "type"]),
+    ("phone", &["phone", "phone number", "telephone", "tel", "business phone", "primary phone", "phone 1 value", "mobile phone", "mobile"]),
+    ("address", &["address", "street", "street address", "address 1", "address line 1", "business street", "address 1 street"]),
+    ("city", &["city", "town", "locality", "business city", "address 1 city"]),
+    ("state", &["state", "province", "region", "county", "business state", "address 1 region"]),
+    ("postal_code", &["postal code", "postcode", "zip", "zip code", "zipcode", "business postal code", "address 1 postal code"]),
+    ("country", &["country", "country code", "business country/region", "address 1 country"]),
+];
+
+/// Lower case, `_`/`-` as spaces, single spaces: "Postal_Code" and "postal code" match.
+fn normalize_header(header: &str) -> String {
+    header
+        .trim()
+        .to_lowercase()
+        .replace(['_', '-'], " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Split CSV text into records (RFC 4180 quoting). The delimiter is whichever of `,` `;` or tab the header
+/// line uses most, since spreadsheet exports differ by locale.
+fn parse_csv(content: &str) -> Vec<Vec<String>> {
+    let content = content.trim_start_matches('\u{feff}');
+    let header_line = content.lines().next().unwrap_or("");
+    let delimiter = [',', ';', '\t']
+        .into_iter()
+        .rev()
+        .max_by_key(|d| header_line.matches(*d).count())
+        .unwrap_or(',');
+
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c == '"' {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            } else {
+                field.push(c);
+            }
+        } else if c == '"' {
+            in_quotes = true;
+        } else if c == delimiter {
+            record.push(std::mem::take(&mut field));
+        } else if c == '\n' || c == '\r' {
+            if c == '\r' && chars.peek() == Some(&'\n') {
+                chars.next();
+            }
+            record.push(std::mem::take(&mut field));
+            records.push(std::mem::take(&mut record));
+        } else {
+            field.push(c);
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+/// Map CSV records onto phone book entries. Returns (row number, entry) pairs; blank rows are skipped.
+fn phonebook_entries_from_csv(content: &str) -> Result<Vec<(usize, PhonebookEntry)>, ConfidantError> {
+    let records = parse_csv(content);
+    let header: Vec<String> = records
+        .first()
+        .ok_or_else(|| ConfidantError::validation("CSV file is empty"))?
+        .iter()
+        .map(|h| normalize_header(h))
+        .collect();
+    let column = |aliases: &[&str]| aliases.iter().find_map(|a| header.iter().position(|h| h == a));
+    let columns: Vec<(&str, Option<usize>)> = CSV_COLUMNS
+        .iter()
+        .map(|(field, aliases)| (*field, column(aliases)))
+        .collect();
+    let first_name = column(&["first name", "given name"]);
+    let last_name = column(&["last name", "family name", "surname"]);
+
+    let has = |field: &str| columns.iter().any(|(f, c)| *f == field && c.is_some());
+    if !has("name") && first_name.is_none() && last_name.is_none() {
+        return Err(ConfidantError::validation(
+            "CSV has no name column (expected e.g. name, organization or first name/last name)",
         ));
     }
+    if !has("phone") {
+        return Err(ConfidantError::validation("CSV has no phone column (expected e.g. phone or telephone)"));
+    }
 
-    const BATCH_SIZE: usize = 50;
-    for chunk_start in (0..documents.len()).step_by(BATCH_SIZE) {
-        let chunk_end = (chunk_start + BATCH_SIZE).min(documents.len());
-        let batch_docs: Vec<VectorDocument> = documents[chunk_start..chunk_end]
-            .iter()
-            .zip(embeddings[chunk_start..chunk_end].iter())
-            .map(|(doc, emb)| {
-                let id = doc["id"].as_str().unwrap_or("").to_string();
-                let text = doc["text"].as_str().unwrap_or("").to_string();
-                let metadata = doc.get("metadata").cloned().unwrap_or(serde_json::json!({}));
-                let embedding: Vec<f32> = emb
-                    .as_array()
-                    .map(|arr| {
-                        arr.iter()
-                            .filter_map(|v| v.as_f64().map(|f| f as f32))
-                            .collect()
-                    })
+    let mut entries = Vec::new();
+    for (i, record) in records.iter().enumerate().skip(1) {
+        if record.iter().all(|f| f.trim().is_empty()) {
+            continue;
+        }
+        let cell = |idx: Option<usize>| {
+            idx.and_then(|i| record.get(i)).map(|v| v.trim().to_string()).unwrap_or_default()
+        };
+        let mut entry = PhonebookEntry::default();
+        for (field, idx) in &columns {
+            let value = cell(*idx);
+            match *field {
+                "name" => entry.name = value,
+                "profession" => entry.profession = value,
+                "phone" => entry.phone = value,
+                "address" => entry.address = value,
+                "city" => entry.city = value,
+                "state" => entry.state = value,
+                "postal_code" => entry.postal_code = value,
+                "country" => entry.country = value,
+                _ => {}
+            }
+        }
+        if entry.name.is_empty() {
+            entry.name = [cell(first_name), cell(last_name)]
+                .into_iter()
+                .filter(|p| !p.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ");
+        }
+        entries.push((i + 1, entry));
+    }
+    Ok(entries)
+}
+
+/// Split a vCard value on unescaped separators (none: a single text value) and unescape each part.
+fn split_vcard_value(value: &str, separators: &[char]) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut part = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') | Some('N') => part.push(' '),
+                Some(other) => part.push(other),
+                None => {}
+            }
+        } else if separators.contains(&c) {
+            parts.push(std::mem::take(&mut part).trim().to_string());
+        } else {
+            part.push(c);
+        }
+    }
+    parts.push(part.trim().to_string());
+    parts
+}
+
+/// Map vCard 3.0/4.0 cards onto phone book entries. Returns (card number, entry) pairs.
+///
+/// FN (or N, or ORG) becomes the name, with ORG appended when both are present; TITLE, ROLE or the first
+/// CATEGORIES value the profession; the preferred non-fax TEL the phone; the preferred ADR the address.
+fn phonebook_entries_from_vcard(content: &str) -> Vec<(usize, PhonebookEntry)> {
+    // Unfold continuation lines (leading space or tab)
+    let mut lines: Vec<String> = Vec::new();
+    for raw in content.trim_start_matches('\u{feff}').split('\n') {
+        let raw = raw.trim_end_matches('\r');
+        match (raw.strip_prefix(' ').or_else(|| raw.strip_prefix('\t')), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(raw.to_string()),
+        }
+    }
+
+    #[derive(Default)]
+    struct Card {
+        formatted_name: String,
+        structured_name: String,
+        org: String,
+        profession: String,
+        phones: Vec<(bool, String)>,
+        address: Option<(bool, Vec<String>)>,
+    }
+
+    let mut entries = Vec::new();
+    let mut card: Option<Card> = None;
+    for line in &lines {
+        // Property name and parameters end at the first ':' outside quotes
+        let mut in_quotes = false;
+        let Some(colon) = line.char_indices().find_map(|(i, c)| {
+            if c == '"' {
+                in_quotes = !in_quotes;
+            }
+            (c == ':' && !in_quotes).then_some(i)
+        }) else {
+            continue;
+        };
+        let (head, value) = (&line[..colon], line[colon + 1..].trim());
+        let mut head_parts = head.split(';');
+        let name = head_parts.next().unwrap_or("");
+        let name = name.rsplit('.').next().unwrap_or(name).trim().to_uppercase();
+        let params = head_parts.collect::<Vec<_>>().join(";").to_uppercase();
+        let preferred = params.contains("PREF");
+
+        if name == "BEGIN" && value.eq_ignore_ascii_case("VCARD") {
+            card = Some(Card::default());
+            continue;
+        }
+        if name == "END" && value.eq_ignore_ascii_case("VCARD") {
+            if let Some(c) = card.take() {
+                let mut entry = PhonebookEntry::default();
+                let person = if c.formatted_name.is_empty() { c.structured_name } else { c.formatted_name };
+                entry.name = match (person.is_empty(), c.org.is_empty()) {
+                    (false, false) if person != c.org => format!("{} ({})", person, c.org),
+                    (true, _) => c.org,
+                    _ => person,
+                };
+                entry.profession = c.profession;
+                entry.phone = c.phones
+                    .iter()
+                    .find(|(pref, _)| *pref)
+                    .or_else(|| c.phones.first())
+                    .map(|(_, phone)| phone.clone())
                     .unwrap_or_default();
-                VectorDocument {
-                    id,
-                    text,
-                    embedding,
-                    metadata,
+                if let Some((_, adr)) = c.address {
+                    let part = |i: usize| adr.get(i).cloned().unwrap_or_default();
+                    entry.address = [part(0), part(1), part(2)]
+                        .into_iter()
+                        .filter(|p| !p.is_empty())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    entry.city = part(3);
+                    entry.state = part(4);
+                    entry.postal_code = part(5);
+                    entry.country = part(6);
                 }
-            })
-            .collect();
-        add_documents_to_collection(app.clone(), GLOBAL_KB_COLLECTION.to_string(), batch_docs)
-            .await?;
+                entries.push((entries.len() + 1, entry));
+            }
+            continue;
+        }
+        let Some(c) = card.as_mut() else { continue };
+        match name.as_str() {
+            "FN" => c.formatted_name = split_vcard_value(value, &[]).concat(),
+            "N" => {
+                // family;given;additional;prefix;suffix
+                let parts = split_vcard_value(value, &[';']);
+                let part = |i: usize| parts.get(i).cloned().unwrap_or_default();
+                c.structured_name = [part(3), part(1), part(2), part(0), part(4)]
+                    .into_iter()
+                    .filter(|p| !p.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+            }
+            "ORG" => c.org = split_vcard_value(value, &[';']).into_iter().find(|p| !p.is_empty()).unwrap_or_default(),
+            "TITLE" | "ROLE" if c.profession.is_empty() => c.profession = split_vcard_value(value, &[]).concat(),
+            "CATEGORIES" if c.profession.is_empty() => {
+                c.profession = split_vcard_value(value, &[',']).into_iter().next().unwrap_or_default();
+            }
+            "TEL" if !params.contains("FAX") && !params.contains("PAGER") => {
+                let phone = value.strip_prefix("tel:").unwrap_or(value).replace(";ext=", " ext ");
+                c.phones.push((preferred, phone));
+            }
+            "ADR" if c.address.as_ref().is_none_or(|(pref, _)| !pref && preferred) => {
+                c.address = Some((preferred, split_vcard_value(value, &[';'])));
+            }
+            _ => {}
+        }
+    }
+    entries
+}
+
+/// ISO 3166-1 alpha-2 code for a country as written in exports ("USA", "United Kingdom", "ca").
+fn country_code(value: &str) -> String {
+    let value = value.trim();
+    match value.to_lowercase().trim_end_matches('.') {
+        "usa" | "u.s.a" | "u.s" | "united states" | "united states of america" => "US".to_string(),
+        "canada" => "CA".to_string(),
+        "uk" | "u.k" | "united kingdom" | "great britain" | "england" | "scotland" | "wales" | "northern ireland" => {
+            "GB".to_string()
+        }
+        _ => value.to_uppercase(),
+    }
+}
+
+/// Check a phone number and tidy its spacing: digits with optional leading `+`, separators ` -().`/, 3 to 15
+/// digits (short codes such as 988 up to E.164), optional numeric extension ("ext 12", "x12", "#12").
+fn normalize_phone(raw: &str) -> Result<String, String> {
+    let raw = raw.trim();
+    let lower = raw.to_ascii_lowercase();
+    let (number, extension) = match ["ext", "x", "#"].iter().filter_map(|m| lower.find(m)).min() {
+        Some(i) => (&raw[..i], raw[i..].trim_start_matches(|c: char| !c.is_ascii_digit()).trim()),
+        None => (raw, ""),
+    };
+    if let Some(bad) = number.chars().find(|c| !(c.is_ascii_digit() || " +-().".contains(*c) || *c == '/')) {
+        return Err(format!("phone number contains '{}'", bad));
+    }
+    if number.trim_start().char_indices().any(|(i, c)| c == '+' && i > 0) {
+        return Err("'+' may only start a phone number".to_string());
+    }
+    let digits = number.chars().filter(|c| c.is_ascii_digit()).count();
+    if !(3..=15).contains(&digits) {
+        return Err(format!("phone number has {} digits (expected 3 to 15)", digits));
+    }
+    if !extension.chars().all(|c| c.is_ascii_digit()) {
+        return Err("phone extension must be digits".to_string());
+    }
+    let number = number.split_whitespace().collect::<Vec<_>>().join(" ");
+    Ok(if extension.is_empty() { number } else { format!("{} ext {}", number, extension) })
+}
+
+/// Trim an imported entry, fill defaults and check it; the error is the rejection reason.
+fn validate_phonebook_entry(
+    entry: PhonebookEntry,
+    defaults: &PhonebookImportDefaults,
+) -> Result<PhonebookEntry, String> {
+    let default = |value: String, fallback: &Option<String>| {
+        let value = value.trim().to_string();
+        if value.is_empty() {
+            fallback.as_deref().unwrap_or("").trim().to_string()
+        } else {
+            value
+        }
+    };
+    let mut entry = PhonebookEntry {
+        country: country_code(&default(entry.country, &defaults.country)),
+        postal_code: entry.postal_code.trim().to_string(),
+        profession: default(entry.profession, &defaults.profession),
+        name: entry.name.trim().to_string(),
+        phone: entry.phone.trim().to_string(),
+        address: entry.address.trim().to_string(),
+        city: entry.city.trim().to_string(),
+        state: entry.state.trim().to_string(),
+    };
+    if entry.name.is_empty() {
+        return Err("missing name".to_string());
+    }
+    if entry.phone.is_empty() {
+        return Err("missing phone number".to_string());
+    }
+    entry.phone = normalize_phone(&entry.phone)?;
+    if entry.country.is_empty() {
+        return Err("missing country (set a default country for the import)".to_string());
+    }
+    if entry.country.len() != 2 || !entry.country.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(format!("country '{}' is not a two-letter code", entry.country));
+    }
+    if entry.postal_code.eq_ignore_ascii_case("NATIONAL") {
+        entry.postal_code = "NATIONAL".to_string();
+    } else if entry.postal_code.is_empty() && entry.city.is_empty() {
+        return Err("missing postal code or city (use NATIONAL for country-wide lines)".to_string());
+    }
+    Ok(entry)
+}
+
+/// Stable id for an imported entry, so importing an updated list replaces entries instead of duplicating them.
+fn imported_phonebook_id(entry: &PhonebookEntry) -> String {
+    let key = format!("{}|{}|{}|{}", entry.country, entry.postal_code, entry.name.to_lowercase(), entry.phone);
+    format!("phonebook_import_{}", &hex::encode(Sha256::digest(key.as_bytes()))[..16])
+}
+
+/// Validated entries of an import, plus the rows that were rejected and why.
+#[derive(Debug, Default)]
+struct ReviewedPhonebookRows {
+    rows: Vec<(String, PhonebookEntry)>,
+    rejected: Vec<RejectedPhonebookRow>,
+}
+
+/// Validate parsed (row, entry) pairs and drop rows that repeat an earlier entry's id.
+fn review_phonebook_entries(
+    parsed: Vec<(usize, PhonebookEntry)>,
+    defaults: &PhonebookImportDefaults,
+) -> ReviewedPhonebookRows {
+    let mut reviewed = ReviewedPhonebookRows::default();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for (row, entry) in parsed {
+        let name = entry.name.trim().to_string();
+        match validate_phonebook_entry(entry, defaults) {
+            Ok(entry) => {
+                let id = imported_phonebook_id(&entry);
+                if let Some(first) = seen.get(&id) {
+                    let reason = format!("duplicate of row {}", first);
+                    reviewed.rejected.push(RejectedPhonebookRow { row, name, reason });
+                    continue;
+                }
+                seen.insert(id.clone(), row);
+                reviewed.rows.push((id, entry));
+            }
+            Err(reason) => reviewed.rejected.push(RejectedPhonebookRow { row, name, reason }),
+        }
+    }
+    reviewed
+}
+
+/// Import phone book entries from a vCard (.vcf/.vcard) or CSV file into dant_phonebook.
+/// With `dry_run`, nothing is written and the report previews the mapped entries and rejected rows.
+#[tauri::command]
+pub async fn import_phonebook_file(
+    app: AppHandle,
+    path: String,
+    dry_run: bool,
+    defaults: Option<PhonebookImportDefaults>,
+) -> Result<PhonebookImportReport, ConfidantError> {
+    let defaults = defaults.unwrap_or_default();
+    let path = PathBuf::from(path);
+    let content = fs::read_to_string(&path)
+        .map_err(|e| ConfidantError::io(format!("Failed to read {}: {}", path.display(), e)))?;
+
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    let format = match extension.as_str() {
+        "vcf" | "vcard" => PhonebookImportFormat::Vcard,
+        "csv" | "tsv" | "txt" => PhonebookImportFormat::Csv,
+        _ if content.trim_start_matches('\u{feff}').trim_start().to_uppercase().starts_with("BEGIN:VCARD") => {
+            PhonebookImportFormat::Vcard
+        }
+        _ => {
+            return Err(ConfidantError::validation(format!(
+                "Unsupported phone book file: {} (expected .vcf or .csv)",
+                path.display()
+            )))
+        }
+    };
+    let parsed = match format {
+        PhonebookImportFormat::Vcard => phonebook_entries_from_vcard(&content),
+        PhonebookImportFormat::Csv => phonebook_entries_from_csv(&content)?,
+    };
+
+    let ReviewedPhonebookRows { rows, rejected } = review_phonebook_entries(parsed, &defaults);
+    let entries = rows.iter().map(|(_, entry)| entry.clone()).collect();
+
+    let mut imported = 0;
+    if !dry_run && !rows.is_empty() {
+        initialize_vector_store(app.clone(), PHONEBOOK_COLLECTION.to_string(), None).await?;
+        ingest_phonebook_entries(&app, &rows, Some("import")).await?;
+        imported = rows.len();
     }
 
+    Ok(PhonebookImportReport { format, dry_run, entries, rejected, imported })
+}
+
+/// Load bundled KB package and ingest into global collection.
+/// Expects same format as frontend KnowledgeBasePackage: { manifest, documents: [{ id, text, metadata }], embeddings: number[][] },
+/// or its binary .ckb encoding (see kb_binary.rs).
+/// Goes through the validated package importer, so unsigned or tampered files and malformed rows are rejected
+/// and the installed version is recorded. The bundled KB is built with the default model, so after a model switch
+/// its text is embedded with the active model.
+async fn ingest_kb_from_path(app: &AppHandle, path: &Path) -> Result<(), ConfidantError> {
+    import_package_file(app, path, GLOBAL_KB_COLLECTION, false, true).await?;
     Ok(())
 }
 
+/// Name in the manifest of a KB package file of either format.
+fn package_name(path: &Path) -> Result<String, ConfidantError> {
+    if is_binary_package(path)? {
+        return Ok(BinaryPackageReader::open(path)?.manifest.name);
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| ConfidantError::io(format!("Failed to read knowledge base package: {}", e)))?;
+    let data: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| ConfidantError::validation(format!("Invalid knowledge base JSON: {}", e)))?;
+    Ok(data["manifest"]["name"].as_str().unwrap_or("").to_string())
+}
+
+/// Re-import the bundled KB after it changed, unless the user installed another package into the global
+/// collection. The package importer diffs by document content hash, so only changed documents are written.
+async fn reseed_kb_if_owned(app: &AppHandle, path: &Path) -> Result<(), ConfidantError> {
+    let installed = load_installed_packages(app)?;
+    if let Some(current) = installed.get(GLOBAL_KB_COLLECTION) {
+        let bundled_name = package_name(path)?;
+        if current.name != bundled_name {
+            #[cfg(debug_assertions)]
+            eprintln!("[Bundled] Global KB is {} (not the bundled {}); leaving it", current.name, bundled_name);
+            return Ok(());
+        }
+    }
+    ingest_kb_from_path(app, path).await
+}
+
 /// When packaged (resource dir present) and no model exists, return (url, output_path) for first-run auto-download.
 fn default_model_download_info(app: &AppHandle) -> Option<(String, PathBuf)> {
     if resolve_bundled_model_path(app).is_some() {
@@ -367,7 +1005,7 @@ fn default_model_download_info(app: &AppHandle) -> Option<(String, PathBuf)> {
 /// Call this once after loading; then check setup status (model_ready, kb_ready).
 /// When no model is found in a packaged build, returns default_model_download_url/output_path for first-run auto-download.
 #[tauri::command]
-pub async fn ensure_bundled_defaults_initialized(app: AppHandle) -> Result<BundledDefaultsStatus, String> {
+pub async fn ensure_bundled_defaults_initialized(app: AppHandle) -> Result<BundledDefaultsStatus, ConfidantError> {
     // 1. Model: if not loaded, try bundled model path
     if !is_model_loaded().await? {
         match resolve_bundled_model_path(&app) {
@@ -401,52 +1039,82 @@ pub async fn ensure_bundled_defaults_initialized(app: AppHandle) -> Result<Bundl
             kb_ready: false,
             default_model_download_url: None,
             default_model_output_path: None,
+            kb_signature: None,
+            phonebook_signature: None,
         });
     }
 
-    // 3. If collection is empty and we have bundled KB, ingest
+    // 3. Bundled KB (only if its signature verifies): seed an empty collection, or re-import it when the file
+    // changed since it was last applied (data/bundled_seeds.json)
+    let kb_path = resolve_bundled_kb_path(&app);
+    let kb_signature = kb_path.as_deref().and_then(|p| verify_package_file(p).ok());
     let stats = get_collection_stats_by_name(app.clone(), GLOBAL_KB_COLLECTION.to_string()).await;
     let doc_count: u64 = stats
         .ok()
         .and_then(|v| v["document_count"].as_u64())
         .unwrap_or(0);
-    if doc_count == 0 {
-        match resolve_bundled_kb_path(&app) {
-            Some(kb_path) => {
+    let mut marker = load_seed_marker(&app).unwrap_or_else(|e| {
+        eprintln!("[Confidant] {}; re-checking bundled seeds", e);
+        BundledSeedMarker::default()
+    });
+    match &kb_path {
+        Some(kb_path) => {
+            let kb_hash = file_sha256(kb_path).ok();
+            let applied = marker.kb.as_ref().map(|r| &r.file_hash) == kb_hash.as_ref();
+            if doc_count == 0 || !applied {
                 #[cfg(debug_assertions)]
                 eprintln!("[Bundled] Ingesting KB from: {:?}", kb_path);
-                if let Err(e) = ingest_kb_from_path(&app, &kb_path).await {
-                    eprintln!("[Confidant] Bundled KB ingest failed: {}", e);
+                let result = if doc_count == 0 {
+                    ingest_kb_from_path(&app, kb_path).await
+                } else {
+                    reseed_kb_if_owned(&app, kb_path).await
+                };
+                match result {
+                    Ok(()) => {
+                        if let Some(file_hash) = kb_hash {
+                            marker.kb = Some(SeedRecord { file_hash, entries: BTreeMap::new() });
+                        }
+                    }
+                    Err(e) => eprintln!("[Confidant] Bundled KB ingest failed: {}", e),
                 }
             }
-            None => {
-                if let Ok(rd) = app.path().resource_dir() {
-                    eprintln!(
-                        "[Confidant] No bundled KB found. Expected {} or {} (add resources/default_kb.json and rebuild)",
-                        rd.join(BUNDLED_KB_FILENAME).display(),
-                        rd.join("resources").join(BUNDLED_KB_FILENAME).display()
-                    );
-                }
+        }
+        None if doc_count == 0 => {
+            if let Ok(rd) = app.path().resource_dir() {
+                eprintln!(
+                    "[Confidant] No bundled KB found. Expected {} or {} (add resources/default_kb.json and rebuild)",
+                    rd.join(BUNDLED_KB_FILENAME).display(),
+                    rd.join("resources").join(BUNDLED_KB_FILENAME).display()
+                );
             }
         }
+        None => {}
     }
 
-    // 4. Phone book: ensure dant_phonebook collection exists; if empty, ingest from seed
+    // 4. Phone book: ensure dant_phonebook collection exists; seed it, or apply seed changes since the last run
+    let seed_path = resolve_phonebook_seed_path(&app);
+    let phonebook_signature = seed_path.as_deref().and_then(|p| verify_package_file(p).ok());
     if initialize_vector_store(app.clone(), PHONEBOOK_COLLECTION.to_string(), None).await.is_ok() {
         let pb_stats = get_collection_stats_by_name(app.clone(), PHONEBOOK_COLLECTION.to_string()).await;
         let pb_count: u64 = pb_stats.ok().and_then(|v| v["document_count"].as_u64()).unwrap_or(0);
-        if pb_count == 0 {
-            if let Some(seed_path) = resolve_phonebook_seed_path(&app) {
+        if let Some(seed_path) = &seed_path {
+            let seed_hash = file_sha256(seed_path).ok();
+            let applied = marker.phonebook.as_ref().map(|r| &r.file_hash) == seed_hash.as_ref();
+            if pb_count == 0 || !applied {
                 #[cfg(debug_assertions)]
-                eprintln!("[Bundled] Ingesting phone book from: {:?}", seed_path);
-                if let Err(e) = ingest_phonebook_from_path(&app, &seed_path).await {
-                    eprintln!("[Confidant] Phone book ingest failed: {}", e);
+                eprintln!("[Bundled] Syncing phone book from: {:?}", seed_path);
+                match sync_phonebook_seed(&app, seed_path, marker.phonebook.as_ref(), pb_count == 0).await {
+                    Ok(record) => marker.phonebook = Some(record),
+                    Err(e) => eprintln!("[Confidant] Phone book ingest failed: {}", e),
                 }
             }
         }
         // Restore global KB as current collection
         let _ = initialize_vector_store(app.clone(), GLOBAL_KB_COLLECTION.to_string(), None).await;
     }
+    if let Err(e) = save_seed_marker(&app, &marker) {
+        eprintln!("[Confidant] {}", e);
+    }
 
     let model_ready = is_model_loaded().await?;
     let stats_after = get_collection_stats_by_name(app.clone(), GLOBAL_KB_COLLECTION.to_string()).await;
@@ -469,5 +1137,130 @@ pub async fn ensure_bundled_defaults_initialized(app: AppHandle) -> Result<Bundl
         kb_ready,
         default_model_download_url,
         default_model_output_path,
+        kb_signature,
+        phonebook_signature,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn us_defaults() -> PhonebookImportDefaults {
+        PhonebookImportDefaults { country: Some("US".to_string()), profession: None }
+    }
+
+    #[test]
+    fn parse_csv_handles_quoted_delimiters_and_newlines() {
+        let csv = "\u{feff}name,address,phone\r\n\"Smith, Jane\",\"12 Main St\nSuite 4\",\"555 \"\"0100\"\"\"\r\nBob,,555-0101";
+        assert_eq!(
+            parse_csv(csv),
+            vec![
+                vec!["name", "address", "phone"],
+                vec!["Smith, Jane", "12 Main St\nSuite 4", "555 \"0100\""],
+                vec!["Bob", "", "555-0101"],
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_csv_detects_semicolon_and_tab_delimiters() {
+        let semicolon = "Name;Phone;City\n\"Dupont, J.\";01 23 45 67 89;Paris\n";
+        assert_eq!(parse_csv(semicolon)[1], vec!["Dupont, J.", "01 23 45 67 89", "Paris"]);
+
+        let tab = "Name\tPhone\tCity\nMüller; Hans\t030 1234567\tBerlin\n";
+        assert_eq!(parse_csv(tab)[1], vec!["Müller; Hans", "030 1234567", "Berlin"]);
+    }
+
+    #[test]
+    fn csv_entries_map_aliases_and_require_name_and_phone() {
+        let csv = "First Name,Last_Name,Telephone,Zip Code,Country\nJane,Smith,555-0100,12345,USA\n,,,,\n";
+        let entries = phonebook_entries_from_csv(csv).unwrap();
+        assert_eq!(entries.len(), 1);
+        let (row, entry) = &entries[0];
+        assert_eq!(*row, 2);
+        assert_eq!((entry.name.as_str(), entry.phone.as_str()), ("Jane Smith", "555-0100"));
+        assert_eq!((entry.postal_code.as_str(), entry.country.as_str()), ("12345", "USA"));
+
+        let err = phonebook_entries_from_csv("name,city\nJane,Boston\n").unwrap_err();
+        assert_eq!(err.code(), "validation");
+        assert!(err.to_string().contains("no phone column"));
+        assert_eq!(phonebook_entries_from_csv("phone\n555-0100\n").unwrap_err().code(), "validation");
+    }
+
+    #[test]
+    fn vcard_unfolds_lines_and_prefers_non_fax_phones() {
+        let vcf = "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Dr. Jane\r\n  Smith\r\nORG:Smith Clinic;Cardiology\r\nTITLE:Cardio\r\n\tlogist\r\n\
+            TEL;TYPE=WORK,FAX:555-0199\r\nTEL;TYPE=WORK:555-0100\r\nTEL;TYPE=CELL,PREF:555-0101\r\n\
+            ADR;TYPE=HOME:;;1 Home Rd;Springfield;IL;62701;USA\r\nADR;TYPE=WORK;PREF=1:;;2 Work St;Chicago;IL;60601;USA\r\n\
+            END:VCARD\r\nBEGIN:VCARD\r\nVERSION:4.0\r\nN:Doe;John;;Mr.;\r\nTEL;TYPE=fax:555-0200\r\nTEL;VALUE=uri:tel:+1-555-0201\r\n\
+            END:VCARD\r\n";
+        let entries = phonebook_entries_from_vcard(vcf);
+        assert_eq!(entries.len(), 2);
+
+        let (row, jane) = &entries[0];
+        assert_eq!(*row, 1);
+        assert_eq!(jane.name, "Dr. Jane Smith (Smith Clinic)");
+        assert_eq!(jane.profession, "Cardiologist");
+        assert_eq!(jane.phone, "555-0101");
+        assert_eq!((jane.address.as_str(), jane.city.as_str()), ("2 Work St", "Chicago"));
+        assert_eq!((jane.postal_code.as_str(), jane.country.as_str()), ("60601", "USA"));
+
+        let (_, john) = &entries[1];
+        assert_eq!(john.name, "Mr. John Doe");
+        assert_eq!(john.phone, "+1-555-0201");
+    }
+
+    #[test]
+    fn normalize_phone_parses_extensions() {
+        assert_eq!(normalize_phone(" +1 (555)  010-0199 x12 ").unwrap(), "+1 (555) 010-0199 ext 12");
+        assert_eq!(normalize_phone("555 0100 ext. 7").unwrap(), "555 0100 ext 7");
+        assert_eq!(normalize_phone("555-0100#9").unwrap(), "555-0100 ext 9");
+        assert_eq!(normalize_phone("988").unwrap(), "988");
+        assert!(normalize_phone("555 0100 ext 1a").unwrap_err().contains("extension"));
+        assert!(normalize_phone("555+0100").is_err());
+        assert!(normalize_phone("12").is_err());
+        assert!(normalize_phone("call 555-0100").is_err());
+    }
+
+    #[test]
+    fn vcard_extension_survives_validation() {
+        let vcf = "BEGIN:VCARD\nFN:Front Desk\nTEL;VALUE=uri:tel:+1-555-0100;ext=42\nADR:;;;Boston;;;\nEND:VCARD\n";
+        let reviewed = review_phonebook_entries(phonebook_entries_from_vcard(vcf), &us_defaults());
+        assert!(reviewed.rejected.is_empty());
+        assert_eq!(reviewed.rows[0].1.phone, "+1-555-0100 ext 42");
+    }
+
+    #[test]
+    fn review_rejects_invalid_and_duplicate_rows() {
+        let csv = "name,phone,city,postal code,country\n\
+            Jane Smith,555-0100,Boston,,\n\
+            ,555-0101,Boston,,\n\
+            No Phone,,Boston,,\n\
+            Bad Phone,555-01OO,Boston,,\n\
+            No Place,555-0102,,,\n\
+            Bad Country,555-0103,Paris,,France\n\
+            jane smith,555-0100,Boston,,us\n\
+            Hotline,988,,national,\n";
+        let reviewed = review_phonebook_entries(phonebook_entries_from_csv(csv).unwrap(), &us_defaults());
+
+        let kept: Vec<&str> = reviewed.rows.iter().map(|(_, e)| e.name.as_str()).collect();
+        assert_eq!(kept, vec!["Jane Smith", "Hotline"]);
+        assert_eq!(reviewed.rows[1].1.postal_code, "NATIONAL");
+        assert!(reviewed.rows.iter().all(|(id, e)| *id == imported_phonebook_id(e) && e.country == "US"));
+
+        let rejected: Vec<(usize, &str)> = reviewed.rejected.iter().map(|r| (r.row, r.reason.as_str())).collect();
+        assert_eq!(
+            rejected,
+            vec![
+                (3, "missing name"),
+                (4, "missing phone number"),
+                (5, "phone number contains 'O'"),
+                (6, "missing postal code or city (use NATIONAL for country-wide lines)"),
+                (7, "country 'FRANCE' is not a two-letter code"),
+                (8, "duplicate of row 2"),
+            ]
+        );
+        assert_eq!(reviewed.rejected[5].name, "jane smith");
+    }
+}
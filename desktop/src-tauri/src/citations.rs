@@ -0,0 +1,164 @@
+// Citations - Typed provenance for search hits and neighbor-chunk expansion
+
+use serde::{Deserialize, Serialize};
+
+/// Metadata keys that identify the document a chunk belongs to, in order of preference.
+/// `document_id` is set by packagers; `source_path` by ingest_files / watched folders.
+const DOCUMENT_KEYS: [&str; 2] = ["document_id", "source_path"];
+
+/// Where a search hit came from, for citations in the UI.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct Provenance {
+    /// Human-readable source: metadata `title`, else `source`, else the file name, else the chunk id.
+    pub source_title: String,
+    /// Document the chunk belongs to (metadata `document_id` / `source_path`, else the chunk id).
+    pub document_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunk_index: Option<u64>,
+    /// 1-based PDF page, when the source was paged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<u64>,
+    /// Character offsets of the chunk within its source text (or page).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_offset: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_offset: Option<u64>,
+}
+
+/// Chunk adjacent to a hit in the same document.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NeighborChunk {
+    pub id: String,
+    pub text: String,
+    pub chunk_index: u64,
+}
+
+impl Provenance {
+    pub fn from_metadata(id: &str, metadata: &serde_json::Value) -> Self {
+        let text = |key: &str| {
+            metadata
+                .get(key)
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.trim().is_empty())
+                .map(|s| s.to_string())
+        };
+        let number = |key: &str| metadata.get(key).and_then(|v| v.as_u64());
+        let file_name = text("source_path").map(|p| {
+            std::path::Path::new(&p)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or(p)
+        });
+
+        Provenance {
+            source_title: text("title")
+                .or_else(|| text("source"))
+                .or(file_name)
+                .unwrap_or_else(|| id.to_string()),
+            document_id: DOCUMENT_KEYS
+                .iter()
+                .find_map(|key| text(key))
+                .unwrap_or_else(|| id.to_string()),
+            chunk_index: number("chunk_index"),
+            page: number("page"),
+            start_offset: number("start_offset"),
+            end_offset: number("end_offset"),
+        }
+    }
+}
+
+/// Metadata filter selecting the chunks within `radius` of a hit in the same document, or None if the
+/// hit has no document key or chunk index (e.g. a whole-document KB entry).
+pub fn neighbor_filter(metadata: &serde_json::Value, radius: u32) -> Option<serde_json::Value> {
+    let (key, value) = DOCUMENT_KEYS
+        .iter()
+        .find_map(|key| metadata.get(*key).filter(|v| v.is_string()).map(|v| (*key, v.clone())))?;
+    let index = metadata.get("chunk_index")?.as_u64()?;
+    let low = index.saturating_sub(radius as u64);
+    let high = index + radius as u64;
+    Some(serde_json::json!({
+        "$and": [
+            { key: value },
+            { "chunk_index": { "$gte": low } },
+            { "chunk_index": { "$lte": high } },
+        ]
+    }))
+}
+
+/// Join a hit with its neighbors in chunk order. Overlap between consecutive chunks (known from their
+/// offsets on the same page) is only kept once; otherwise chunks are separated by a blank line.
+pub fn merge_chunks(chunks: &[(&Provenance, &str)]) -> String {
+    let mut merged = String::new();
+    let mut previous: Option<&Provenance> = None;
+    for (provenance, text) in chunks {
+        let overlap = match (previous, provenance.start_offset) {
+            (Some(prev), Some(start)) if prev.page == provenance.page => {
+                prev.end_offset.map_or(0, |end| end.saturating_sub(start) as usize)
+            }
+            _ => 0,
+        };
+        if overlap > 0 {
+            merged.extend(text.chars().skip(overlap));
+        } else {
+            if !merged.is_empty() {
+                merged.push_str("\n\n");
+            }
+            merged.push_str(text);
+        }
+        previous = Some(*provenance);
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(page: Option<u64>, start: u64, end: u64) -> Provenance {
+        Provenance {
+            page,
+            start_offset: Some(start),
+            end_offset: Some(end),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn merge_keeps_overlap_once_on_the_same_page() {
+        let a = chunk(Some(1), 0, 10);
+        let b = chunk(Some(1), 6, 16);
+        assert_eq!(merge_chunks(&[(&a, "abcdefghij"), (&b, "ghijklmnop")]), "abcdefghijklmnop");
+    }
+
+    #[test]
+    fn merge_separates_chunks_across_pages_or_gaps() {
+        let a = chunk(Some(1), 0, 10);
+        let b = chunk(Some(2), 6, 16);
+        assert_eq!(merge_chunks(&[(&a, "first"), (&b, "second")]), "first\n\nsecond");
+
+        let c = chunk(Some(1), 20, 30);
+        assert_eq!(merge_chunks(&[(&a, "first"), (&c, "later")]), "first\n\nlater");
+    }
+
+    #[test]
+    fn neighbor_filter_needs_a_chunk_index() {
+        let metadata = serde_json::json!({ "source_path": "/docs/a.md" });
+        assert_eq!(neighbor_filter(&metadata, 1), None);
+    }
+
+    #[test]
+    fn neighbor_filter_saturates_at_the_first_chunk() {
+        let metadata = serde_json::json!({ "document_id": "doc", "chunk_index": 0 });
+        let filter = neighbor_filter(&metadata, 2).unwrap();
+        assert_eq!(
+            filter,
+            serde_json::json!({
+                "$and": [
+                    { "document_id": "doc" },
+                    { "chunk_index": { "$gte": 0 } },
+                    { "chunk_index": { "$lte": 2 } },
+                ]
+            })
+        );
+    }
+}
@@ -0,0 +1,218 @@
+// Collection GC - Reconcile per-user collections with users.json
+//
+// Per-user knowledge bases live in dant_knowledge_user_{id}. A profile deleted while its collection could
+// not be removed leaves an orphan behind. The reconciliation pass lists collections, matches them against
+// the users in users.json and quarantines (exports to data/quarantine as an importable KB package, then
+// deletes) or removes the orphans. It runs once at startup in quarantine mode and on demand via
+// gc_user_collections.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::embeddings::EMBEDDING_MODEL_ID;
+use crate::error::ConfidantError;
+use crate::kb_package::{package_from_documents, write_package_json};
+use crate::reembed::SHADOW_SUFFIX;
+use crate::vector_store::{call_python_helper, export_documents, lock_collection_write, open_store_or_default};
+
+const USER_COLLECTION_PREFIX: &str = "dant_knowledge_user_";
+
+/// What to do with an orphaned collection.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OrphanAction {
+    /// Export to data/quarantine, then delete (restorable with import_kb_package).
+    #[default]
+    Quarantine,
+    /// Delete without keeping a copy.
+    Remove,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanCollection {
+    pub collection: String,
+    pub user_id: String,
+    pub documents: Option<u64>,
+    /// None on a dry run, or when handling the orphan failed (see `error`).
+    pub action: Option<OrphanAction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quarantine_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionGcReport {
+    /// Per-user collections found.
+    pub checked: usize,
+    /// Per-user collections that belong to an existing user.
+    pub kept: usize,
+    pub orphans: Vec<OrphanCollection>,
+    pub dry_run: bool,
+}
+
+/// User id of a per-user collection (or its re-embed shadow), None for other collections.
+fn collection_user_id(collection_name: &str) -> Option<&str> {
+    let rest = collection_name.strip_prefix(USER_COLLECTION_PREFIX)?;
+    Some(rest.strip_suffix(SHADOW_SUFFIX).unwrap_or(rest)).filter(|id| !id.is_empty())
+}
+
+/// Get app data dir for quarantined collections (data/quarantine/).
+fn get_quarantine_dir(app: &AppHandle) -> Result<PathBuf, ConfidantError> {
+    let base_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| ConfidantError::io(format!("Failed to get app data dir: {}", e)))?;
+    let dir = base_dir.join("data").join("quarantine");
+    fs::create_dir_all(&dir)
+        .map_err(|e| ConfidantError::io(format!("Failed to create quarantine directory: {}", e)))?;
+    Ok(dir)
+}
+
+async fn delete_collection(
+    bundled: Option<(PathBuf, PathBuf)>,
+    db_path: &str,
+    collection_name: &str,
+) -> Result<(), ConfidantError> {
+    let result_json = call_python_helper(bundled, "delete_collection", &[db_path, collection_name], None).await?;
+    let result: serde_json::Value = serde_json::from_str(&result_json)
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?;
+    if result["status"].as_str() != Some("success") {
+        return Err(ConfidantError::helper("Failed to delete collection").with_details(result.to_string()));
+    }
+    Ok(())
+}
+
+/// Export the collection (built with `embedding_model`) as a KB package into `dir`; returns the file path.
+async fn quarantine_collection(
+    bundled: Option<(PathBuf, PathBuf)>,
+    db_path: &str,
+    collection_name: &str,
+    embedding_model: &str,
+    dir: &Path,
+) -> Result<String, ConfidantError> {
+    let docs = export_documents(bundled, db_path, collection_name).await?;
+    let package = package_from_documents(
+        collection_name,
+        embedding_model,
+        docs,
+        None,
+        Some(format!("Quarantined orphan collection {}", collection_name)),
+        None,
+    )?;
+    let path = dir.join(format!("{}-{}.json", collection_name, chrono::Utc::now().format("%Y%m%d-%H%M%S")));
+    write_package_json(&package, &path)?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Find per-user collections without a user and quarantine or remove them.
+async fn reconcile(app: &AppHandle, action: OrphanAction, dry_run: bool) -> Result<CollectionGcReport, ConfidantError> {
+    // A users.json that cannot be read must not make every collection look orphaned
+    let user_ids: HashSet<String> = crate::user_management::load_users(app)?
+        .into_iter()
+        .map(|u| u.id)
+        .collect();
+    let quarantine_dir = match (action, dry_run) {
+        (OrphanAction::Quarantine, false) => Some(get_quarantine_dir(app)?),
+        _ => None,
+    };
+    let bundled = crate::python_bundle::resolve_bundled_python(app);
+
+    let store = open_store_or_default(app).await?;
+    let db_path = store.db_path.as_str();
+    let result_json = call_python_helper(bundled.clone(), "list_collections", &[db_path], None).await?;
+    let result: serde_json::Value = serde_json::from_str(&result_json)
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?;
+    if result["status"].as_str() != Some("success") {
+        return Err(ConfidantError::helper("Failed to list collections").with_details(result.to_string()));
+    }
+
+    let mut report = CollectionGcReport { checked: 0, kept: 0, orphans: Vec::new(), dry_run };
+    for entry in result["collections"].as_array().cloned().unwrap_or_default() {
+        let name = entry["name"].as_str().unwrap_or("").to_string();
+        let embedding_model = entry["embedding_model"].as_str().unwrap_or(EMBEDDING_MODEL_ID);
+        let Some(user_id) = collection_user_id(&name) else {
+            continue;
+        };
+        report.checked += 1;
+        if user_ids.contains(user_id) || crate::reembed::is_reembedding(&name) {
+            report.kept += 1;
+            continue;
+        }
+
+        let mut orphan = OrphanCollection {
+            collection: name.clone(),
+            user_id: user_id.to_string(),
+            documents: entry["count"].as_u64(),
+            action: None,
+            quarantine_path: None,
+            error: None,
+        };
+        if !dry_run {
+            let _lock = lock_collection_write(&name).await;
+            let handled: Result<Option<String>, ConfidantError> = async {
+                let path = match &quarantine_dir {
+                    Some(dir) => Some(quarantine_collection(bundled.clone(), db_path, &name, embedding_model, dir).await?),
+                    None => None,
+                };
+                delete_collection(bundled.clone(), db_path, &name).await?;
+                Ok(path)
+            }
+            .await;
+            match handled {
+                Ok(path) => {
+                    orphan.action = Some(action);
+                    orphan.quarantine_path = path;
+                }
+                Err(e) => orphan.error = Some(e.to_string()),
+            }
+        }
+        report.orphans.push(orphan);
+    }
+    drop(store);
+
+    if !dry_run {
+        for orphan in report.orphans.iter_mut().filter(|o| o.action.is_some()) {
+            if let Err(e) = crate::collection_settings::remove_collection_settings(app, &orphan.collection) {
+                orphan.error = Some(e.to_string());
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Quarantine orphaned per-user collections in the background. Called once at startup.
+pub fn reconcile_user_collections_on_startup(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match reconcile(&app, OrphanAction::Quarantine, false).await {
+        Ok(report) if !report.orphans.is_empty() => {
+            for orphan in &report.orphans {
+                match (&orphan.quarantine_path, &orphan.error) {
+                    (_, Some(e)) => eprintln!("[Confidant] Could not quarantine orphaned collection {}: {}", orphan.collection, e),
+                    (Some(path), None) => eprintln!("[Confidant] Quarantined orphaned collection {} to {}", orphan.collection, path),
+                    (None, None) => {}
+                }
+            }
+        }
+        Ok(_) => {}
+            Err(e) => eprintln!("[Confidant] Collection reconciliation skipped: {}", e),
+        }
+    });
+}
+
+/// Maintenance: list per-user collections whose user no longer exists and quarantine (default) or remove
+/// them. With `dry_run`, only reports what would be done.
+#[tauri::command]
+pub async fn gc_user_collections(
+    app: AppHandle,
+    action: Option<OrphanAction>,
+    dry_run: Option<bool>,
+) -> Result<CollectionGcReport, ConfidantError> {
+    reconcile(&app, action.unwrap_or_default(), dry_run.unwrap_or(false)).await
+}
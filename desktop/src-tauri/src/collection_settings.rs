@@ -0,0 +1,123 @@
+// Collection Settings - Per-collection retrieval options, persisted as JSON in app data
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+use crate::error::ConfidantError;
+use crate::ranking::SearchOptions;
+
+/// Default cross-encoder used when reranking is enabled without an explicit model.
+pub const DEFAULT_RERANK_MODEL: &str = "cross-encoder/ms-marco-MiniLM-L-6-v2";
+
+/// Default number of vector-search candidates passed to the cross-encoder.
+const DEFAULT_RERANK_CANDIDATES: u32 = 20;
+
+fn default_rerank_model() -> String {
+    DEFAULT_RERANK_MODEL.to_string()
+}
+
+fn default_rerank_candidates() -> u32 {
+    DEFAULT_RERANK_CANDIDATES
+}
+
+/// Second-stage reranking: re-score the top `candidates` vector hits with a cross-encoder.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RerankConfig {
+    #[serde(default = "default_rerank_model")]
+    pub model: String,
+    #[serde(default = "default_rerank_candidates")]
+    pub candidates: u32,
+}
+
+/// Retrieval settings for one collection. Missing fields mean "off" / defaults.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CollectionSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rerank: Option<RerankConfig>,
+    /// Default search options used when a search does not pass its own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search: Option<SearchOptions>,
+}
+
+/// Get collection settings file path (data/collection_settings.json).
+fn get_settings_file_path(app: &AppHandle) -> Result<PathBuf, ConfidantError> {
+    let base_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| ConfidantError::io(format!("Failed to get app data dir: {}", e)))?;
+    let data_dir = base_dir.join("data");
+    fs::create_dir_all(&data_dir)
+        .map_err(|e| ConfidantError::io(format!("Failed to create data directory: {}", e)))?;
+    Ok(data_dir.join("collection_settings.json"))
+}
+
+/// Load settings for all collections (empty map if the file does not exist yet).
+fn load_all_settings(app: &AppHandle) -> Result<HashMap<String, CollectionSettings>, ConfidantError> {
+    let path = get_settings_file_path(app)?;
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| ConfidantError::io(format!("Failed to read collection settings: {}", e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| ConfidantError::internal(format!("Failed to parse collection settings: {}", e)))
+}
+
+fn save_all_settings(app: &AppHandle, settings: &HashMap<String, CollectionSettings>) -> Result<(), ConfidantError> {
+    let path = get_settings_file_path(app)?;
+    let content = serde_json::to_string_pretty(settings)
+        .map_err(|e| ConfidantError::internal(format!("Failed to serialize collection settings: {}", e)))?;
+    fs::write(&path, content)
+        .map_err(|e| ConfidantError::io(format!("Failed to write collection settings: {}", e)))
+}
+
+/// Settings for one collection, or defaults if none were saved.
+pub fn load_collection_settings(app: &AppHandle, collection_name: &str) -> Result<CollectionSettings, ConfidantError> {
+    Ok(load_all_settings(app)?
+        .remove(collection_name)
+        .unwrap_or_default())
+}
+
+/// Forget the settings of a deleted collection.
+pub fn remove_collection_settings(app: &AppHandle, collection_name: &str) -> Result<(), ConfidantError> {
+    let mut all = load_all_settings(app)?;
+    if all.remove(collection_name).is_some() {
+        save_all_settings(app, &all)?;
+    }
+    Ok(())
+}
+
+/// Get retrieval settings for a collection
+#[tauri::command]
+pub async fn get_collection_settings(
+    app: AppHandle,
+    collection_name: String,
+) -> Result<CollectionSettings, ConfidantError> {
+    load_collection_settings(&app, &collection_name)
+}
+
+/// Set retrieval settings for a collection (replaces any previous settings)
+#[tauri::command]
+pub async fn set_collection_settings(
+    app: AppHandle,
+    collection_name: String,
+    settings: CollectionSettings,
+) -> Result<(), ConfidantError> {
+    if let Some(rerank) = &settings.rerank {
+        if rerank.model.trim().is_empty() {
+            return Err(ConfidantError::validation("Rerank model cannot be empty"));
+        }
+        if rerank.candidates == 0 {
+            return Err(ConfidantError::validation("Rerank candidates must be at least 1"));
+        }
+    }
+    if let Some(search) = &settings.search {
+        search.validate()?;
+    }
+    let mut all = load_all_settings(&app)?;
+    all.insert(collection_name, settings);
+    save_all_settings(&app, &all)
+}
@@ -0,0 +1,343 @@
+// Embedding Cache - On-disk, content-addressed cache in front of the embedding model
+//
+// Each embedding is stored as little-endian f32s in data/cache/embeddings/<ab>/<key>.f32, where key is the
+// SHA-256 of the model (id and settings) and the whitespace-normalized text. File mtimes record last use, so the LRU order
+// survives restarts. When the cache grows past its size limit the least recently used entries are evicted.
+// Cache failures are logged and treated as misses; they never fail an embedding request. Lookups and writes run
+// on the blocking thread pool, since they hit the disk under a global lock.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+
+/// Default size limit (~40k MiniLM embeddings). CONFIDANT_EMBEDDING_CACHE_MB overrides it; 0 disables the cache.
+const DEFAULT_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Eviction trims to this fraction of the limit so it does not run on every insert.
+const EVICT_TO_FRACTION: f64 = 0.9;
+
+struct CacheEntry {
+    bytes: u64,
+    last_used: SystemTime,
+}
+
+struct CacheIndex {
+    dir: PathBuf,
+    entries: HashMap<String, CacheEntry>,
+    total_bytes: u64,
+}
+
+lazy_static::lazy_static! {
+    /// Index of the cache directory, built on first use.
+    static ref CACHE: Mutex<Option<CacheIndex>> = Mutex::new(None);
+}
+
+fn max_bytes() -> u64 {
+    std::env::var("CONFIDANT_EMBEDDING_CACHE_MB")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(|mb| mb * 1024 * 1024)
+        .unwrap_or(DEFAULT_MAX_BYTES)
+}
+
+fn get_cache_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let base_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+    let dir = base_dir.join("data").join("cache").join("embeddings");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create embedding cache directory: {}", e))?;
+    Ok(dir)
+}
+
+/// Cache key: SHA-256 of the model id and the text with whitespace runs collapsed and trimmed (the tokenizers
+/// split on whitespace, so this does not change the embedding).
+fn cache_key(model: &str, text: &str) -> String {
+    let normalized = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut hasher = Sha256::new();
+    hasher.update(model.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(normalized.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Whether a file stem is a cache key (64 hex digits), so stray files in the cache directory are skipped.
+fn is_cache_key(stem: &str) -> bool {
+    stem.len() == 64 && stem.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+impl CacheIndex {
+    /// Scan the cache directory (two-level, sharded by the first two hex digits of the key). Temp files left by
+    /// an interrupted write are removed.
+    fn load(dir: PathBuf) -> Self {
+        let mut entries = HashMap::new();
+        let mut total_bytes = 0;
+        for shard in fs::read_dir(&dir).into_iter().flatten().flatten() {
+            for file in fs::read_dir(shard.path()).into_iter().flatten().flatten() {
+                let path = file.path();
+                let name = file.file_name();
+                let Some(name) = name.to_str() else { continue };
+                if name.ends_with(".f32.tmp") {
+                    let _ = fs::remove_file(&path);
+                    continue;
+                }
+                let Some(key) = name.strip_suffix(".f32").filter(|stem| is_cache_key(stem)) else {
+                    continue;
+                };
+                let Ok(meta) = file.metadata() else { continue };
+                let entry = CacheEntry {
+                    bytes: meta.len(),
+                    last_used: meta.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                };
+                total_bytes += entry.bytes;
+                entries.insert(key.to_string(), entry);
+            }
+        }
+        Self { dir, entries, total_bytes }
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(&key[..2]).join(format!("{}.f32", key))
+    }
+
+    fn get(&mut self, key: &str) -> Option<Vec<f32>> {
+        self.entries.get(key)?;
+        let path = self.entry_path(key);
+        let embedding = fs::read(&path).ok().and_then(|bytes| decode(&bytes));
+        let Some(embedding) = embedding else {
+            // Missing or corrupt file
+            self.remove(key);
+            return None;
+        };
+        let now = SystemTime::now();
+        if let Some(entry) = self.entries.get_mut(key) {
+            entry.last_used = now;
+        }
+        // Persist the LRU order; best effort
+        if let Ok(file) = fs::File::options().write(true).open(&path) {
+            let _ = file.set_modified(now);
+        }
+        Some(embedding)
+    }
+
+    fn put(&mut self, key: &str, embedding: &[f32]) -> Result<(), String> {
+        let path = self.entry_path(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let bytes = encode(embedding);
+        write_atomic(&path, &bytes)?;
+        let entry = CacheEntry { bytes: bytes.len() as u64, last_used: SystemTime::now() };
+        self.total_bytes += entry.bytes;
+        if let Some(old) = self.entries.insert(key.to_string(), entry) {
+            self.total_bytes -= old.bytes;
+        }
+        Ok(())
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.total_bytes -= entry.bytes;
+        }
+        let _ = fs::remove_file(self.entry_path(key));
+    }
+
+    /// Drop least recently used entries until the cache is under the limit (with headroom).
+    fn evict(&mut self, max_bytes: u64) {
+        if self.total_bytes <= max_bytes {
+            return;
+        }
+        let target = (max_bytes as f64 * EVICT_TO_FRACTION) as u64;
+        #[cfg(debug_assertions)]
+        let before = self.entries.len();
+        let mut by_age: Vec<(SystemTime, String)> = self
+            .entries
+            .iter()
+            .map(|(key, entry)| (entry.last_used, key.clone()))
+            .collect();
+        by_age.sort();
+        for (_, key) in by_age {
+            if self.total_bytes <= target {
+                break;
+            }
+            self.remove(&key);
+        }
+        #[cfg(debug_assertions)]
+        eprintln!("[Embedding Cache] Evicted {} entries ({} bytes left)", before - self.entries.len(), self.total_bytes);
+    }
+}
+
+fn encode(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn decode(bytes: &[u8]) -> Option<Vec<f32>> {
+    if bytes.is_empty() || !bytes.len().is_multiple_of(4) {
+        return None;
+    }
+    Some(
+        bytes
+            .chunks_exact(4)
+            .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect(),
+    )
+}
+
+/// Write to a temp file and rename, so a crash never leaves a truncated entry.
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    let tmp = path.with_extension("f32.tmp");
+    fs::write(&tmp, bytes).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+    fs::rename(&tmp, path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Run `f` on the loaded index. None when the cache is disabled or unavailable.
+fn with_index<T>(app: &AppHandle, f: impl FnOnce(&mut CacheIndex) -> T) -> Option<T> {
+    if max_bytes() == 0 {
+        return None;
+    }
+    let mut guard = match CACHE.lock() {
+        Ok(guard) => guard,
+        Err(_) => {
+            eprintln!("[Embedding Cache] Lock poisoned; cache disabled");
+            return None;
+        }
+    };
+    if guard.is_none() {
+        match get_cache_dir(app) {
+            Ok(dir) => *guard = Some(CacheIndex::load(dir)),
+            Err(e) => {
+                eprintln!("[Embedding Cache] {}", e);
+                return None;
+            }
+        }
+    }
+    guard.as_mut().map(f)
+}
+
+/// Cached embeddings for `texts` (same order); None for misses.
+pub async fn get_many(app: &AppHandle, model: &str, texts: &[String]) -> Vec<Option<Vec<f32>>> {
+    let (app, model, texts) = (app.clone(), model.to_string(), texts.to_vec());
+    let count = texts.len();
+    tauri::async_runtime::spawn_blocking(move || {
+        with_index(&app, |index| texts.iter().map(|text| index.get(&cache_key(&model, text))).collect())
+    })
+    .await
+    .unwrap_or_else(|e| {
+        eprintln!("[Embedding Cache] Lookup task failed: {}", e);
+        None
+    })
+    .unwrap_or_else(|| vec![None; count])
+}
+
+pub async fn get(app: &AppHandle, model: &str, text: &str) -> Option<Vec<f32>> {
+    get_many(app, model, &[text.to_string()]).await.pop().flatten()
+}
+
+/// Store embeddings for `texts`, then evict down to the size limit.
+pub async fn put_many(app: &AppHandle, model: &str, texts: &[String], embeddings: &[Vec<f32>]) {
+    let limit = max_bytes();
+    let (app, model, texts, embeddings) = (app.clone(), model.to_string(), texts.to_vec(), embeddings.to_vec());
+    let stored = tauri::async_runtime::spawn_blocking(move || {
+        with_index(&app, |index| {
+            for (text, embedding) in texts.iter().zip(&embeddings) {
+                if let Err(e) = index.put(&cache_key(&model, text), embedding) {
+                    eprintln!("[Embedding Cache] {}", e);
+                    break;
+                }
+            }
+            index.evict(limit);
+        });
+    })
+    .await;
+    if let Err(e) = stored {
+        eprintln!("[Embedding Cache] Store task failed: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("confidant-cache-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn cache_key_normalizes_whitespace_and_separates_models() {
+        let key = cache_key("minilm", "  hello \n\t world ");
+        assert!(is_cache_key(&key));
+        assert_eq!(key, cache_key("minilm", "hello world"));
+        assert_ne!(key, cache_key("minilm", "helloworld"));
+        assert_ne!(key, cache_key("bge", "hello world"));
+        // The separator keeps model and text from running together
+        assert_ne!(cache_key("ab", "c"), cache_key("a", "bc"));
+    }
+
+    #[test]
+    fn encode_decode_round_trip() {
+        let embedding = vec![0.0, -1.5, f32::MIN_POSITIVE, 3.25e7];
+        let bytes = encode(&embedding);
+        assert_eq!(bytes.len(), 16);
+        assert_eq!(decode(&bytes), Some(embedding));
+        assert_eq!(decode(&[]), None);
+        assert_eq!(decode(&bytes[..7]), None);
+    }
+
+    #[test]
+    fn load_skips_stray_files_and_removes_temp_files() {
+        let dir = temp_cache_dir("load");
+        let key = cache_key("m", "kept");
+        let mut index = CacheIndex::load(dir.clone());
+        index.put(&key, &[1.0, 2.0]).unwrap();
+
+        let shard = dir.join(&key[..2]);
+        let tmp = shard.join(format!("{}.f32.tmp", cache_key("m", "interrupted")));
+        fs::write(&tmp, b"partial").unwrap();
+        fs::write(shard.join("a.f32"), b"1234").unwrap();
+        fs::write(shard.join(format!("{}.f32", "g".repeat(64))), b"1234").unwrap();
+
+        let mut index = CacheIndex::load(dir.clone());
+        let keys: Vec<&String> = index.entries.keys().collect();
+        assert_eq!(keys, vec![&key]);
+        assert_eq!(index.total_bytes, 8);
+        assert!(!tmp.exists());
+        assert_eq!(index.get(&key), Some(vec![1.0, 2.0]));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn evict_drops_least_recently_used_entries() {
+        let dir = temp_cache_dir("evict");
+        let mut index = CacheIndex::load(dir.clone());
+        let keys: Vec<String> = (0..4).map(|i| cache_key("m", &format!("text {}", i))).collect();
+        for key in &keys {
+            index.put(key, &[0.5; 4]).unwrap();
+        }
+        // Oldest first: 0, 1, 2, 3; then using 0 makes 1 the least recently used
+        let start = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        for (i, key) in keys.iter().enumerate() {
+            index.entries.get_mut(key).unwrap().last_used = start + std::time::Duration::from_secs(i as u64);
+        }
+        index.entries.get_mut(&keys[0]).unwrap().last_used = start + std::time::Duration::from_secs(10);
+        assert_eq!(index.total_bytes, 64);
+
+        index.evict(64);
+        assert_eq!(index.entries.len(), 4, "under the limit nothing is evicted");
+
+        // Over 40 bytes: trim to 36 (90%), which keeps two 16-byte entries
+        index.evict(40);
+        assert_eq!(index.total_bytes, 32);
+        assert!(index.entries.contains_key(&keys[0]) && index.entries.contains_key(&keys[3]));
+        assert!(!index.entry_path(&keys[1]).exists() && !index.entry_path(&keys[2]).exists());
+        assert_eq!(CacheIndex::load(dir.clone()).total_bytes, 32);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
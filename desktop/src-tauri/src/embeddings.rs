@@ -1,154 +1,638 @@
 // Embeddings - Generate embeddings using Python sentence-transformers
+//
+// Models are described by EmbeddingModelSpec (dimension, token limit, normalization, query/passage prefixes).
+// Built-in specs can be extended or overridden, and the active model chosen, in data/embedding_models.json.
+// Retrieval code embeds with embed_query / embed_passages, which apply the active model's prefixes.
+//
+// Embedding requests go through a warm embeddings_helper.py worker (JSON lines over stdin/stdout) that is
+// started on first use and restarted if it dies; cross-encoder reranking runs on the same worker. Results
+// are cached on disk (embedding_cache.rs), so repeated texts are not re-embedded.
+// With the native-embeddings feature, the default model runs in-process (native_embeddings.rs) when its model
+// files are installed, and falls back to the Python helper otherwise.
 
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
 use std::path::PathBuf;
-use std::process::Command;
-use std::io::Write;
-use tauri::AppHandle;
+use std::process::Stdio;
+use std::sync::RwLock;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tauri::{AppHandle, Manager};
+
+use crate::error::ConfidantError;
+
+/// Default sentence-transformers model (384 dimensions), preloaded by embeddings_helper.py.
+pub const EMBEDDING_MODEL_ID: &str = "all-MiniLM-L6-v2";
+
+/// Model ids may be written with or without the "sentence-transformers/" namespace.
+pub fn same_embedding_model(a: &str, b: &str) -> bool {
+    let strip = |s: &str| s.trim().trim_start_matches("sentence-transformers/").to_lowercase();
+    strip(a) == strip(b)
+}
+
+/// Registry file with extra model specs and the active model, under app_data_dir/data.
+const MODELS_FILE: &str = "embedding_models.json";
+
+/// How to embed with one model: what to load, what it outputs and how inputs are prepared.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingModelSpec {
+    /// Model id recorded on collections and packages (sentence-transformers / Hugging Face id).
+    pub id: String,
+    /// Local model directory (or another hub id) to load instead of `id`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub dimension: usize,
+    /// Longer inputs are truncated to this many tokens.
+    pub max_tokens: usize,
+    /// L2-normalize the output (cosine search expects unit vectors).
+    #[serde(default = "default_normalize")]
+    pub normalize: bool,
+    /// Prepended to search queries (e5: "query: ").
+    #[serde(default)]
+    pub query_prefix: String,
+    /// Prepended to stored passages (e5: "passage: ").
+    #[serde(default)]
+    pub passage_prefix: String,
+}
+
+fn default_normalize() -> bool {
+    true
+}
+
+impl EmbeddingModelSpec {
+    fn builtin(id: &str, dimension: usize, max_tokens: usize, query_prefix: &str, passage_prefix: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            path: None,
+            dimension,
+            max_tokens,
+            normalize: true,
+            query_prefix: query_prefix.to_string(),
+            passage_prefix: passage_prefix.to_string(),
+        }
+    }
+
+    /// Name passed to SentenceTransformer().
+    fn load_name(&self) -> &str {
+        self.path.as_deref().unwrap_or(&self.id)
+    }
+
+    /// Cache namespace: everything besides the text that changes the output.
+    fn cache_id(&self) -> String {
+        format!("{}|{}|{}", self.load_name(), self.max_tokens, self.normalize)
+    }
+
+    /// The default model with its default settings, which the native embedder and the helper's preloaded model
+    /// serve directly.
+    fn is_default_model(&self) -> bool {
+        let default = &builtin_embedding_models()[0];
+        self.path.is_none()
+            && same_embedding_model(&self.id, &default.id)
+            && self.max_tokens == default.max_tokens
+            && self.normalize
+    }
+
+    fn validate(&self) -> Result<(), ConfidantError> {
+        if self.id.trim().is_empty() {
+            return Err(ConfidantError::validation("Embedding model spec needs an id"));
+        }
+        if self.dimension == 0 || self.max_tokens == 0 {
+            return Err(ConfidantError::validation(format!(
+                "Embedding model {}: dimension and maxTokens must be positive", self.id
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Built-in specs: the default model first, then multilingual and retrieval-tuned alternatives.
+fn builtin_embedding_models() -> Vec<EmbeddingModelSpec> {
+    vec![
+        EmbeddingModelSpec::builtin(EMBEDDING_MODEL_ID, 384, 256, "", ""),
+        EmbeddingModelSpec::builtin("paraphrase-multilingual-MiniLM-L12-v2", 384, 128, "", ""),
+        EmbeddingModelSpec::builtin("intfloat/multilingual-e5-small", 384, 512, "query: ", "passage: "),
+        EmbeddingModelSpec::builtin(
+            "BAAI/bge-small-en-v1.5",
+            384,
+            512,
+            "Represent this sentence for searching relevant passages: ",
+            "",
+        ),
+    ]
+}
+
+/// data/embedding_models.json: specs that add to (or replace) the built-in ones, and the active model.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EmbeddingModelsFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    active: Option<String>,
+    #[serde(default)]
+    models: Vec<EmbeddingModelSpec>,
+}
+
+/// Registered models and the one new collections, queries and passages are embedded with.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingModelsInfo {
+    pub active: String,
+    pub models: Vec<EmbeddingModelSpec>,
+}
+
+lazy_static::lazy_static! {
+    /// Built-in specs until load_embedding_models reads the registry file.
+    static ref EMBEDDING_MODELS: RwLock<EmbeddingModelsInfo> = RwLock::new(EmbeddingModelsInfo {
+        active: EMBEDDING_MODEL_ID.to_string(),
+        models: builtin_embedding_models(),
+    });
+}
+
+fn get_models_file_path(app: &AppHandle) -> Result<PathBuf, ConfidantError> {
+    let base_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| ConfidantError::io(format!("Failed to get app data dir: {}", e)))?;
+    let data_dir = base_dir.join("data");
+    fs::create_dir_all(&data_dir)
+        .map_err(|e| ConfidantError::io(format!("Failed to create data directory: {}", e)))?;
+    Ok(data_dir.join(MODELS_FILE))
+}
+
+fn read_models_file(app: &AppHandle) -> Result<EmbeddingModelsFile, ConfidantError> {
+    let path = get_models_file_path(app)?;
+    if !path.exists() {
+        return Ok(EmbeddingModelsFile::default());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| ConfidantError::io(format!("Failed to read {}: {}", MODELS_FILE, e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| ConfidantError::validation(format!("Invalid {}: {}", MODELS_FILE, e)))
+}
+
+/// Built-in specs with the file's specs merged in by id, and the file's active model (checked to be registered).
+fn merge_models_file(file: &EmbeddingModelsFile) -> Result<EmbeddingModelsInfo, ConfidantError> {
+    let mut models = builtin_embedding_models();
+    for spec in &file.models {
+        spec.validate()?;
+        match models.iter_mut().find(|m| same_embedding_model(&m.id, &spec.id)) {
+            Some(existing) => *existing = spec.clone(),
+            None => models.push(spec.clone()),
+        }
+    }
+    let active = match &file.active {
+        Some(id) => models
+            .iter()
+            .find(|m| same_embedding_model(&m.id, id))
+            .map(|m| m.id.clone())
+            .ok_or_else(|| ConfidantError::not_found(format!("Active embedding model {} is not registered", id)))?,
+        None => EMBEDDING_MODEL_ID.to_string(),
+    };
+    Ok(EmbeddingModelsInfo { active, models })
+}
+
+/// Load data/embedding_models.json into the registry (at startup). Without the file the built-ins are used.
+pub fn load_embedding_models(app: &AppHandle) -> Result<(), ConfidantError> {
+    let info = merge_models_file(&read_models_file(app)?)?;
+    let mut registry = EMBEDDING_MODELS.write()
+        .map_err(|_| ConfidantError::internal("Embedding model registry lock poisoned"))?;
+    *registry = info;
+    Ok(())
+}
+
+fn registry() -> EmbeddingModelsInfo {
+    EMBEDDING_MODELS.read().map(|r| r.clone()).unwrap_or_else(|e| e.into_inner().clone())
+}
+
+/// Registered spec for a model id (with or without the "sentence-transformers/" namespace).
+pub fn embedding_model_spec(model: &str) -> Option<EmbeddingModelSpec> {
+    registry().models.into_iter().find(|m| same_embedding_model(&m.id, model))
+}
+
+/// Spec of the model the app embeds with.
+pub fn active_embedding_model() -> EmbeddingModelSpec {
+    let info = registry();
+    info.models
+        .into_iter()
+        .find(|m| m.id == info.active)
+        .unwrap_or_else(|| builtin_embedding_models().remove(0))
+}
+
+pub fn active_embedding_model_id() -> String {
+    registry().active
+}
+
+/// Registered embedding models and the active one.
+#[tauri::command]
+pub async fn list_embedding_models() -> Result<EmbeddingModelsInfo, ConfidantError> {
+    Ok(registry())
+}
+
+/// Result of set_active_embedding_model: the registry, and the collections being re-embedded for the new model.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveModelChange {
+    #[serde(flatten)]
+    pub registry: EmbeddingModelsInfo,
+    #[serde(flatten)]
+    pub collections: crate::reembed::StaleCollections,
+}
+
+/// Make a registered model the one the app embeds with (saved in data/embedding_models.json), and start a
+/// re-embed of every collection built with another model. Those collections refuse queries and writes until their
+/// re-embed completes. Once the model is saved the call succeeds; collections whose re-embed could not start are
+/// listed in `failed`.
+#[tauri::command]
+pub async fn set_active_embedding_model(app: AppHandle, model_id: String) -> Result<ActiveModelChange, ConfidantError> {
+    let spec = embedding_model_spec(&model_id)
+        .ok_or_else(|| ConfidantError::not_found(format!("Embedding model {} is not registered", model_id)))?;
+    let mut file = read_models_file(&app)?;
+    file.active = Some(spec.id.clone());
+    let info = merge_models_file(&file)?;
+    // Everything that can fail before the switch happens first, so an error leaves the old model active
+    let stale = crate::reembed::list_stale_collections(&app, &info.active).await?;
+
+    let content = serde_json::to_string_pretty(&file)
+        .map_err(|e| ConfidantError::internal(format!("Failed to serialize {}: {}", MODELS_FILE, e)))?;
+    fs::write(get_models_file_path(&app)?, content)
+        .map_err(|e| ConfidantError::io(format!("Failed to write {}: {}", MODELS_FILE, e)))?;
+
+    *EMBEDDING_MODELS.write().unwrap_or_else(|e| e.into_inner()) = info.clone();
+
+    let collections = crate::reembed::start_reembeds(&app, stale, &info.active).await;
+    Ok(ActiveModelChange { registry: info, collections })
+}
 
 /// Get path to embeddings helper script
-fn get_embeddings_helper_path() -> Result<std::path::PathBuf, String> {
+fn get_embeddings_helper_path() -> Result<std::path::PathBuf, ConfidantError> {
     let exe_path = std::env::current_exe()
-        .map_err(|e| format!("Failed to get executable path: {}", e))?;
+        .map_err(|e| ConfidantError::io(format!("Failed to get executable path: {}", e)))?;
     
     let script_path = exe_path
         .parent()
         .and_then(|p| p.parent())
         .and_then(|p| p.parent())
-        .ok_or("Failed to find script directory")?
+        .ok_or_else(|| ConfidantError::not_found("Failed to find script directory"))?
         .join("scripts")
         .join("embeddings_helper.py");
     
     if !script_path.exists() {
         let current_dir = std::env::current_dir()
-            .map_err(|e| format!("Failed to get current directory: {}", e))?;
+            .map_err(|e| ConfidantError::io(format!("Failed to get current directory: {}", e)))?;
         let fallback_path = current_dir.join("src-tauri").join("scripts").join("embeddings_helper.py");
         if fallback_path.exists() {
             return Ok(fallback_path);
         }
-        return Err(format!("Helper script not found at: {:?}", script_path));
+        return Err(ConfidantError::not_found(format!("Helper script not found at: {:?}", script_path)));
     }
     
     Ok(script_path)
 }
 
-/// Call Python embeddings helper.
+/// Python interpreter and embeddings_helper.py path.
 /// If `bundled` is Some((python_exe, scripts_dir)), use that Python and scripts_dir/embeddings_helper.py.
-fn call_embeddings_helper(
-    bundled: Option<(PathBuf, PathBuf)>,
-    command: &str,
-    stdin_data: Option<&str>,
-) -> Result<String, String> {
-    let (python_cmd, script_path) = if let Some((python_exe, scripts_dir)) = bundled {
+async fn resolve_python_and_script(bundled: Option<(PathBuf, PathBuf)>) -> Result<(String, PathBuf), ConfidantError> {
+    if let Some((python_exe, scripts_dir)) = bundled {
         let script = scripts_dir.join("embeddings_helper.py");
         if !python_exe.exists() || !script.exists() {
-            return Err("Bundled Python or script not found.".to_string());
-        }
-        (python_exe.to_string_lossy().to_string(), script)
-    } else {
-        let script_path = get_embeddings_helper_path()?;
-        let python_cmd = {
-            let venv_python = crate::python_bundle::find_venv_python();
-            if let Some(venv_py) = venv_python {
-                if Command::new(&venv_py).arg("--version").output().is_ok() {
-                    venv_py.to_string_lossy().to_string()
-                } else if Command::new("python3").arg("--version").output().is_ok() {
-                    "python3".to_string()
-                } else if Command::new("python").arg("--version").output().is_ok() {
-                    "python".to_string()
-                } else {
-                    return Err("Python not found. Please install Python 3.".to_string());
-                }
-            } else if Command::new("python3").arg("--version").output().is_ok() {
+            return Err(ConfidantError::python_missing("Bundled Python or script not found."));
+        }
+        return Ok((python_exe.to_string_lossy().to_string(), script));
+    }
+    let script_path = get_embeddings_helper_path()?;
+    let python_cmd = {
+        let venv_python = crate::python_bundle::find_venv_python();
+        if let Some(venv_py) = venv_python {
+            if Command::new(&venv_py).arg("--version").output().await.is_ok() {
+                venv_py.to_string_lossy().to_string()
+            } else if Command::new("python3").arg("--version").output().await.is_ok() {
                 "python3".to_string()
-            } else if Command::new("python").arg("--version").output().is_ok() {
+            } else if Command::new("python").arg("--version").output().await.is_ok() {
                 "python".to_string()
             } else {
-                return Err("Python not found. Please install Python 3.".to_string());
+                return Err(ConfidantError::python_missing("Python not found. Please install Python 3."));
             }
-        };
-        (python_cmd, script_path)
+        } else if Command::new("python3").arg("--version").output().await.is_ok() {
+            "python3".to_string()
+        } else if Command::new("python").arg("--version").output().await.is_ok() {
+            "python".to_string()
+        } else {
+            return Err(ConfidantError::python_missing("Python not found. Please install Python 3."));
+        }
     };
+    Ok((python_cmd, script_path))
+}
 
-    let mut cmd = Command::new(&python_cmd);
-    cmd.arg(&script_path);
-    cmd.arg(command);
-    
-    if stdin_data.is_some() {
-        cmd.stdin(std::process::Stdio::piped());
+/// Longest wait for the worker's ready line (the first start may download the model).
+const WORKER_READY_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Longest wait for one response (batch_model and rerank may load their model on first use).
+const WORKER_REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Long-lived `embeddings_helper.py serve` process with the model loaded, so requests skip the Python and
+/// model start-up. It reads one JSON request per line and answers with one JSON line carrying the same id.
+struct EmbeddingsWorker {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: u64,
+}
+
+lazy_static::lazy_static! {
+    /// The worker answers one request at a time; the async lock queues concurrent callers.
+    static ref EMBEDDINGS_WORKER: tokio::sync::Mutex<Option<EmbeddingsWorker>> = tokio::sync::Mutex::new(None);
+}
+
+impl EmbeddingsWorker {
+    /// Spawn the worker and wait for its {"ready": true} line.
+    async fn start(bundled: Option<(PathBuf, PathBuf)>) -> Result<Self, ConfidantError> {
+        let (python_cmd, script_path) = resolve_python_and_script(bundled).await?;
+        let mut child = Command::new(&python_cmd)
+            .arg(&script_path)
+            .arg("serve")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| ConfidantError::helper(format!("Failed to spawn embeddings worker: {}", e)))?;
+
+        // Drain stderr so the worker never blocks on it; the last lines explain a failed start.
+        let stderr = child.stderr.take().ok_or_else(|| ConfidantError::helper("No stderr from embeddings worker"))?;
+        let stderr_task = tokio::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            let mut tail = VecDeque::new();
+            while let Ok(Some(line)) = lines.next_line().await {
+                #[cfg(debug_assertions)]
+                eprintln!("[Embeddings Worker] {}", line);
+                if tail.len() == 20 {
+                    tail.pop_front();
+                }
+                tail.push_back(line);
+            }
+            Vec::from(tail).join("\n")
+        });
+
+        let stdout = child.stdout.take().ok_or_else(|| ConfidantError::helper("No stdout from embeddings worker"))?;
+        let mut stdout = BufReader::new(stdout);
+        let failure = match tokio::time::timeout(WORKER_READY_TIMEOUT, wait_for_ready(&mut stdout)).await {
+            Ok(Ok(())) => None,
+            Ok(Err(message)) => Some(message),
+            Err(_) => Some("Embeddings worker did not become ready in time".to_string()),
+        };
+        if let Some(message) = failure {
+            let _ = child.kill().await;
+            let stderr = tokio::time::timeout(Duration::from_secs(2), stderr_task)
+                .await
+                .ok()
+                .and_then(|r| r.ok())
+                .unwrap_or_default();
+            return Err(ConfidantError::from_helper_stderr(message, &stderr));
+        }
+
+        let stdin = child.stdin.take().ok_or_else(|| ConfidantError::helper("No stdin from embeddings worker"))?;
+        Ok(Self { child, stdin, stdout, next_id: 0 })
     }
-    
-    let mut child = cmd.stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
-        .spawn()
-        .map_err(|e| format!("Failed to spawn Python process: {}", e))?;
-    
-    if let Some(ref data) = stdin_data {
-        if let Some(mut stdin) = child.stdin.take() {
-            stdin.write_all(data.as_bytes())
-                .map_err(|e| format!("Failed to write to Python stdin: {}", e))?;
+
+    fn is_running(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    /// Send one request and read lines until the response with its id (stray output is skipped).
+    async fn request(&mut self, mut request: serde_json::Value) -> Result<serde_json::Value, ConfidantError> {
+        self.next_id += 1;
+        let id = self.next_id;
+        request["id"] = id.into();
+        let line = serde_json::to_string(&request)
+            .map_err(|e| ConfidantError::internal(format!("Failed to serialize request: {}", e)))?;
+        self.stdin.write_all(format!("{}\n", line).as_bytes()).await
+            .map_err(|e| ConfidantError::helper(format!("Write to embeddings worker: {}", e)))?;
+        self.stdin.flush().await
+            .map_err(|e| ConfidantError::helper(format!("Flush embeddings worker: {}", e)))?;
+
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let n = self.stdout.read_line(&mut line).await
+                .map_err(|e| ConfidantError::helper(format!("Read from embeddings worker: {}", e)))?;
+            if n == 0 {
+                return Err(ConfidantError::helper("Embeddings worker exited during a request"));
+            }
+            if let Ok(response) = serde_json::from_str::<serde_json::Value>(line.trim()) {
+                if response["id"].as_u64() == Some(id) {
+                    return Ok(response);
+                }
+            }
         }
     }
-    
-    let output = child.wait_with_output()
-        .map_err(|e| format!("Failed to wait for Python process: {}", e))?;
-    
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Python script failed: {}", stderr));
+}
+
+/// Read worker stdout until {"ready": true}; Err(message) if it reports an error or exits first.
+async fn wait_for_ready(stdout: &mut BufReader<ChildStdout>) -> Result<(), String> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let n = stdout.read_line(&mut line).await
+            .map_err(|e| format!("Failed to read from embeddings worker: {}", e))?;
+        if n == 0 {
+            return Err("Embeddings worker exited before it was ready".to_string());
+        }
+        let Ok(v) = serde_json::from_str::<serde_json::Value>(line.trim()) else {
+            continue;
+        };
+        if v.get("ready").and_then(|r| r.as_bool()) == Some(true) {
+            return Ok(());
+        }
+        if let Some(err) = v.get("error").and_then(|e| e.as_str()) {
+            return Err(format!("Embeddings worker failed to load the model: {}", err));
+        }
     }
-    
-    let stdout = String::from_utf8(output.stdout)
-        .map_err(|e| format!("Failed to parse Python output: {}", e))?;
-    
-    Ok(stdout)
 }
 
-/// Generate embedding for a single text
+/// Run a request on the warm worker, starting it if needed. A worker that died is restarted and the request
+/// retried once; a request without an answer within WORKER_REQUEST_TIMEOUT stops the worker and fails.
+async fn call_embeddings_worker(app: &AppHandle, request: serde_json::Value) -> Result<serde_json::Value, ConfidantError> {
+    let mut guard = EMBEDDINGS_WORKER.lock().await;
+    let mut retried = false;
+    loop {
+        if !guard.as_mut().is_some_and(|worker| worker.is_running()) {
+            #[cfg(debug_assertions)]
+            if guard.is_some() {
+                eprintln!("[Embeddings] Worker exited; restarting");
+            }
+            *guard = Some(EmbeddingsWorker::start(crate::python_bundle::resolve_bundled_python(app)).await?);
+        }
+        let Some(worker) = guard.as_mut() else {
+            return Err(ConfidantError::internal("Embeddings worker missing after start"));
+        };
+        match tokio::time::timeout(WORKER_REQUEST_TIMEOUT, worker.request(request.clone())).await {
+            Ok(Ok(response)) => return Ok(response),
+            Ok(Err(e)) => {
+                // Dropping the worker kills it; the next attempt starts a fresh one
+                *guard = None;
+                if retried {
+                    return Err(e);
+                }
+                #[cfg(debug_assertions)]
+                eprintln!("[Embeddings] {}; restarting worker", e);
+                retried = true;
+            }
+            Err(_) => {
+                // A hung worker is not retried: the request would likely hang again
+                *guard = None;
+                return Err(ConfidantError::helper(format!(
+                    "Embeddings worker did not answer within {}s; it was stopped and will restart on the next request",
+                    WORKER_REQUEST_TIMEOUT.as_secs()
+                )));
+            }
+        }
+    }
+}
+
+/// Embed a search query with the active model (its query prefix is prepended).
+#[tauri::command]
+pub async fn embed_query(app: AppHandle, text: String) -> Result<Vec<f32>, ConfidantError> {
+    let spec = active_embedding_model();
+    let text = format!("{}{}", spec.query_prefix, text);
+    let mut embeddings = embed_cached(&app, &spec, std::slice::from_ref(&text)).await?;
+    embeddings.pop().ok_or_else(|| ConfidantError::helper("Embedding model returned no embedding"))
+}
+
+/// Embed passages for storage with the active model (its passage prefix is prepended).
+#[tauri::command]
+pub async fn embed_passages(app: AppHandle, texts: Vec<String>) -> Result<Vec<Vec<f32>>, ConfidantError> {
+    let spec = active_embedding_model();
+    let texts: Vec<String> = texts.iter().map(|t| format!("{}{}", spec.passage_prefix, t)).collect();
+    embed_cached(&app, &spec, &texts).await
+}
+
+/// Generate embedding for a single text with the active model, without prefixes (see embed_query).
 #[tauri::command]
-pub async fn generate_embedding(app: AppHandle, text: String) -> Result<Vec<f32>, String> {
-    let bundled = crate::python_bundle::resolve_bundled_python(&app);
-    let result_json = call_embeddings_helper(bundled, "embed", Some(&text))?;
-    let result: serde_json::Value = serde_json::from_str(&result_json)
-        .map_err(|e| format!("Failed to parse Python response: {}", e))?;
+pub async fn generate_embedding(app: AppHandle, text: String) -> Result<Vec<f32>, ConfidantError> {
+    let mut embeddings = embed_cached(&app, &active_embedding_model(), std::slice::from_ref(&text)).await?;
+    embeddings.pop().ok_or_else(|| ConfidantError::helper("Embedding model returned no embedding"))
+}
+
+/// Generate embeddings for multiple texts with the active model, without prefixes (see embed_passages).
+#[tauri::command]
+pub async fn generate_embeddings_batch(app: AppHandle, texts: Vec<String>) -> Result<Vec<Vec<f32>>, ConfidantError> {
+    embed_cached(&app, &active_embedding_model(), &texts).await
+}
+
+/// Embed texts as given. Cached texts are not re-embedded; only the misses (once each) go to the model.
+async fn embed_cached(app: &AppHandle, spec: &EmbeddingModelSpec, texts: &[String]) -> Result<Vec<Vec<f32>>, ConfidantError> {
+    let cache_id = spec.cache_id();
+    let mut embeddings = crate::embedding_cache::get_many(app, &cache_id, texts).await;
+    let mut misses: Vec<String> = Vec::new();
+    let mut miss_index: HashMap<&str, usize> = HashMap::new();
+    for (text, cached) in texts.iter().zip(&embeddings) {
+        if cached.is_none() && !miss_index.contains_key(text.as_str()) {
+            miss_index.insert(text, misses.len());
+            misses.push(text.clone());
+        }
+    }
+
+    if !misses.is_empty() {
+        let computed = embed_uncached(app, spec, misses.clone()).await?;
+        if computed.len() != misses.len() {
+            return Err(ConfidantError::helper(format!(
+                "Expected {} embeddings, got {}", misses.len(), computed.len()
+            )));
+        }
+        if let Some(wrong) = computed.iter().find(|e| e.len() != spec.dimension) {
+            return Err(ConfidantError::helper(format!(
+                "{} produced {}-dimensional vectors but its spec says {}", spec.id, wrong.len(), spec.dimension
+            )));
+        }
+        crate::embedding_cache::put_many(app, &cache_id, &misses, &computed).await;
+        for (text, slot) in texts.iter().zip(embeddings.iter_mut()) {
+            if slot.is_none() {
+                *slot = miss_index.get(text.as_str()).map(|&i| computed[i].clone());
+            }
+        }
+    }
+    Ok(embeddings.into_iter().flatten().collect())
+}
+
+/// Embed texts with the model (no cache).
+async fn embed_uncached(app: &AppHandle, spec: &EmbeddingModelSpec, texts: Vec<String>) -> Result<Vec<Vec<f32>>, ConfidantError> {
+    if !spec.is_default_model() {
+        let result = call_embeddings_worker(app, serde_json::json!({
+            "command": "batch_model",
+            "texts": texts,
+            "model": spec.load_name(),
+            "normalize": spec.normalize,
+            "max_seq_length": spec.max_tokens,
+        })).await?;
+        if result["status"].as_str() != Some("success") {
+            let error_msg = result["message"].as_str().unwrap_or("Unknown error");
+            return Err(ConfidantError::helper(format!("Failed to generate embeddings with {}: {}", spec.id, error_msg)));
+        }
+        return parse_embeddings(&result);
+    }
+
+    #[cfg(feature = "native-embeddings")]
+    if let Some(embeddings) = crate::native_embeddings::embed_texts(app, texts.clone()).await? {
+        return Ok(embeddings);
+    }
+
+    if let [text] = texts.as_slice() {
+        let result = call_embeddings_worker(app, serde_json::json!({ "command": "embed", "text": text })).await?;
+        if result["status"].as_str() != Some("success") {
+            let error_msg = result["message"].as_str().unwrap_or("Unknown error");
+            return Err(ConfidantError::helper(format!("Failed to generate embedding: {}", error_msg)));
+        }
+        let embedding = parse_embeddings(&serde_json::json!({ "embeddings": [result["embedding"]] }))?;
+        return Ok(embedding);
+    }
+
+    let result = call_embeddings_worker(app, serde_json::json!({ "command": "batch", "texts": texts })).await?;
     
     if result["status"].as_str() != Some("success") {
         let error_msg = result["message"].as_str().unwrap_or("Unknown error");
-        return Err(format!("Failed to generate embedding: {}", error_msg));
+        return Err(ConfidantError::helper(format!("Failed to generate embeddings: {}", error_msg)));
     }
     
-    let embedding_array = result["embedding"].as_array()
-        .ok_or("Invalid response format: missing embedding")?;
-    
-    let embedding: Vec<f32> = embedding_array.iter()
-        .map(|v| {
-            v.as_f64()
-                .ok_or_else(|| "Invalid number format".to_string())
-                .map(|f| f as f32)
-        })
-        .collect::<Result<Vec<f32>, _>>()
-        .map_err(|e| format!("Failed to parse embedding values: {}", e))?;
-    
-    Ok(embedding)
+    parse_embeddings(&result)
 }
 
-/// Generate embeddings for multiple texts
-#[tauri::command]
-pub async fn generate_embeddings_batch(app: AppHandle, texts: Vec<String>) -> Result<Vec<Vec<f32>>, String> {
-    let bundled = crate::python_bundle::resolve_bundled_python(&app);
-    let texts_json = serde_json::to_string(&texts)
-        .map_err(|e| format!("Failed to serialize texts: {}", e))?;
-    
-    let result_json = call_embeddings_helper(bundled, "batch", Some(&texts_json))?;
-    let result: serde_json::Value = serde_json::from_str(&result_json)
-        .map_err(|e| format!("Failed to parse Python response: {}", e))?;
-    
+/// Embed stored passages with a specific model, e.g. the target of a re-embed, on the warm worker. Registered
+/// models get their passage prefix, token limit and normalization; others are embedded as given, normalized.
+pub async fn embed_texts_with_model(
+    app: &AppHandle,
+    model: &str,
+    texts: &[String],
+) -> Result<Vec<Vec<f32>>, ConfidantError> {
+    let request = match embedding_model_spec(model) {
+        Some(spec) => serde_json::json!({
+            "command": "batch_model",
+            "texts": texts.iter().map(|t| format!("{}{}", spec.passage_prefix, t)).collect::<Vec<_>>(),
+            "model": spec.load_name(),
+            "normalize": spec.normalize,
+            "max_seq_length": spec.max_tokens,
+        }),
+        None => serde_json::json!({
+            "command": "batch_model",
+            "texts": texts,
+            "model": model,
+        }),
+    };
+    let result = call_embeddings_worker(app, request).await?;
+
     if result["status"].as_str() != Some("success") {
         let error_msg = result["message"].as_str().unwrap_or("Unknown error");
-        return Err(format!("Failed to generate embeddings: {}", error_msg));
+        return Err(ConfidantError::helper(format!("Failed to generate embeddings with {}: {}", model, error_msg)));
     }
-    
+
+    parse_embeddings(&result)
+}
+
+/// Parse the `embeddings` array of a helper batch response.
+fn parse_embeddings(result: &serde_json::Value) -> Result<Vec<Vec<f32>>, ConfidantError> {
     let embeddings_array = result["embeddings"].as_array()
-        .ok_or("Invalid response format: missing embeddings")?;
+        .ok_or_else(|| ConfidantError::helper("Invalid response format: missing embeddings"))?;
     
     let embeddings: Vec<Vec<f32>> = embeddings_array.iter().map(|emb| {
         emb.as_array()
@@ -163,7 +647,108 @@ pub async fn generate_embeddings_batch(app: AppHandle, texts: Vec<String>) -> Re
                     .collect::<Result<Vec<f32>, _>>()
             })
     }).collect::<Result<Vec<Vec<f32>>, _>>()
-        .map_err(|e| format!("Failed to parse embeddings: {}", e))?;
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse embeddings: {}", e)))?;
     
     Ok(embeddings)
 }
+
+/// Score each passage against the query with a cross-encoder model on the warm worker (higher is more relevant).
+/// Returns one score per passage, in input order.
+pub async fn rerank_passages(
+    app: &AppHandle,
+    model: &str,
+    query: &str,
+    passages: &[String],
+) -> Result<Vec<f32>, ConfidantError> {
+    let result = call_embeddings_worker(app, serde_json::json!({
+        "command": "rerank",
+        "query": query,
+        "passages": passages,
+        "model": model,
+    })).await?;
+
+    if result["status"].as_str() != Some("success") {
+        let error_msg = result["message"].as_str().unwrap_or("Unknown error");
+        return Err(ConfidantError::helper(format!("Failed to rerank passages: {}", error_msg)));
+    }
+
+    let scores: Vec<f32> = result["scores"].as_array()
+        .ok_or_else(|| ConfidantError::helper("Invalid response format: missing scores"))?
+        .iter()
+        .map(|v| {
+            v.as_f64()
+                .ok_or_else(|| "Invalid number format".to_string())
+                .map(|f| f as f32)
+        })
+        .collect::<Result<Vec<f32>, _>>()
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse rerank scores: {}", e)))?;
+
+    if scores.len() != passages.len() {
+        return Err(ConfidantError::helper(format!(
+            "Rerank returned {} scores for {} passages",
+            scores.len(),
+            passages.len()
+        )));
+    }
+
+    Ok(scores)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(id: &str, dimension: usize, max_tokens: usize) -> EmbeddingModelSpec {
+        EmbeddingModelSpec::builtin(id, dimension, max_tokens, "", "")
+    }
+
+    #[test]
+    fn merge_models_file_overrides_adds_and_resolves_active() {
+        let builtins = builtin_embedding_models();
+        let info = merge_models_file(&EmbeddingModelsFile::default()).unwrap();
+        assert_eq!(info.active, EMBEDDING_MODEL_ID);
+        assert_eq!(info.models, builtins);
+
+        let file = EmbeddingModelsFile {
+            active: Some("Local/Custom-Model".to_string()),
+            models: vec![
+                spec("sentence-transformers/all-MiniLM-L6-v2", 384, 128),
+                spec("local/custom-model", 768, 512),
+            ],
+        };
+        let info = merge_models_file(&file).unwrap();
+        // Overrides replace the built-in in place; new specs are appended
+        assert_eq!(info.models.len(), builtins.len() + 1);
+        assert_eq!(info.models[0], file.models[0]);
+        assert_eq!(info.models[1..builtins.len()], builtins[1..]);
+        assert_eq!(info.models.last(), Some(&file.models[1]));
+        // The active id is matched case-insensitively and reported as registered
+        assert_eq!(info.active, "local/custom-model");
+    }
+
+    #[test]
+    fn merge_models_file_rejects_bad_specs_and_unknown_active() {
+        let unknown = EmbeddingModelsFile { active: Some("missing-model".to_string()), models: Vec::new() };
+        assert_eq!(merge_models_file(&unknown).unwrap_err().code(), "not_found");
+
+        let invalid = EmbeddingModelsFile { active: None, models: vec![spec("broken", 0, 256)] };
+        assert_eq!(merge_models_file(&invalid).unwrap_err().code(), "validation");
+        let unnamed = EmbeddingModelsFile { active: None, models: vec![spec(" ", 384, 256)] };
+        assert_eq!(merge_models_file(&unnamed).unwrap_err().code(), "validation");
+    }
+
+    #[test]
+    fn is_default_model_requires_default_settings() {
+        let default = builtin_embedding_models().remove(0);
+        assert!(default.is_default_model());
+        assert!(spec("sentence-transformers/All-MiniLM-L6-v2", 384, 256).is_default_model());
+
+        let local = EmbeddingModelSpec { path: Some("/models/minilm".to_string()), ..default.clone() };
+        assert!(!local.is_default_model());
+        let truncated = EmbeddingModelSpec { max_tokens: 128, ..default.clone() };
+        assert!(!truncated.is_default_model());
+        let unnormalized = EmbeddingModelSpec { normalize: false, ..default };
+        assert!(!unnormalized.is_default_model());
+        assert!(!builtin_embedding_models()[1].is_default_model());
+    }
+}
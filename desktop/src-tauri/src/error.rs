@@ -0,0 +1,137 @@
+// Errors - Typed error returned by Tauri commands
+//
+// Commands return ConfidantError, serialized as { code, message, details }, so the frontend can branch on
+// `code` instead of matching message text. Modules that still use String errors convert it with `?`.
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfidantError {
+    /// The model or vector store has not been initialized yet.
+    NotInitialized(String),
+    /// No usable Python, or a required Python package is missing (details: the import error).
+    PythonMissing { message: String, details: Option<String> },
+    /// A helper process failed or reported an error (details: its stderr or response).
+    Helper { message: String, details: Option<String> },
+    /// Invalid input from the caller.
+    Validation(String),
+    NotFound(String),
+    /// Reading or writing app data (or downloading a file) failed.
+    Io(String),
+    /// Poisoned lock, serialization failure or other bug; not something the user can fix.
+    Internal(String),
+}
+
+impl ConfidantError {
+    pub fn not_initialized(message: impl Into<String>) -> Self {
+        Self::NotInitialized(message.into())
+    }
+
+    pub fn python_missing(message: impl Into<String>) -> Self {
+        Self::PythonMissing { message: message.into(), details: None }
+    }
+
+    pub fn helper(message: impl Into<String>) -> Self {
+        Self::Helper { message: message.into(), details: None }
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::Validation(message.into())
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::NotFound(message.into())
+    }
+
+    pub fn io(message: impl Into<String>) -> Self {
+        Self::Io(message.into())
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::Internal(message.into())
+    }
+
+    /// Helper process that exited with an error. An import error in its stderr means a Python package is
+    /// missing rather than a helper bug.
+    pub fn from_helper_stderr(message: impl Into<String>, stderr: &str) -> Self {
+        let last_line = stderr.lines().map(str::trim).rev().find(|l| !l.is_empty()).unwrap_or("");
+        if last_line.contains("ModuleNotFoundError") || last_line.contains("ImportError") {
+            return Self::PythonMissing {
+                message: message.into(),
+                details: Some(last_line.to_string()),
+            };
+        }
+        let stderr = stderr.trim();
+        Self::Helper {
+            message: message.into(),
+            details: (!stderr.is_empty()).then(|| stderr.to_string()),
+        }
+    }
+
+    /// Attach details (stderr, helper response) to a PythonMissing or Helper error; others are unchanged.
+    pub fn with_details(mut self, text: impl Into<String>) -> Self {
+        if let Self::PythonMissing { details, .. } | Self::Helper { details, .. } = &mut self {
+            *details = Some(text.into());
+        }
+        self
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NotInitialized(_) => "not_initialized",
+            Self::PythonMissing { .. } => "python_missing",
+            Self::Helper { .. } => "helper_failed",
+            Self::Validation(_) => "validation",
+            Self::NotFound(_) => "not_found",
+            Self::Io(_) => "io",
+            Self::Internal(_) => "internal",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Self::NotInitialized(m)
+            | Self::Validation(m)
+            | Self::NotFound(m)
+            | Self::Io(m)
+            | Self::Internal(m) => m,
+            Self::PythonMissing { message, .. } | Self::Helper { message, .. } => message,
+        }
+    }
+
+    pub fn details(&self) -> Option<&str> {
+        match self {
+            Self::PythonMissing { details, .. } | Self::Helper { details, .. } => details.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ConfidantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.details() {
+            Some(details) => write!(f, "{}: {}", self.message(), details),
+            None => f.write_str(self.message()),
+        }
+    }
+}
+
+impl std::error::Error for ConfidantError {}
+
+impl Serialize for ConfidantError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ConfidantError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", self.message())?;
+        state.serialize_field("details", &self.details())?;
+        state.end()
+    }
+}
+
+impl From<ConfidantError> for String {
+    fn from(error: ConfidantError) -> Self {
+        error.to_string()
+    }
+}
@@ -0,0 +1,596 @@
+// Ingest - Turn local documents (.txt, .md, .html, .pdf) into chunked, embedded KB entries
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+
+use crate::embeddings::embed_passages;
+use crate::error::ConfidantError;
+use crate::vector_store::{
+    add_documents_to_collection, delete_documents, delete_documents_by_filter, initialize_vector_store, VectorDocument,
+};
+
+/// Defaults match `rag.chunk_size` / `rag.chunk_overlap` in config.example.yaml (measured in words,
+/// as in the Python KnowledgeLoader).
+pub const DEFAULT_CHUNK_SIZE: usize = 512;
+pub const DEFAULT_CHUNK_OVERLAP: usize = 50;
+
+/// File extensions extract_sections understands.
+pub const SUPPORTED_EXTENSIONS: &[&str] = &["txt", "md", "markdown", "html", "htm", "pdf"];
+
+/// Chunks embedded and stored per helper call.
+const EMBED_BATCH_SIZE: usize = 32;
+
+/// Event emitted for each file as it moves through the pipeline.
+pub const INGEST_PROGRESS_EVENT: &str = "ingest-progress";
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestOptions {
+    /// Maximum words per chunk.
+    pub chunk_size: Option<usize>,
+    /// Words shared between consecutive chunks.
+    pub chunk_overlap: Option<usize>,
+}
+
+/// Outcome for one input file.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestedFile {
+    pub path: String,
+    pub chunks: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestReport {
+    pub collection: String,
+    pub files: Vec<IngestedFile>,
+    pub total_chunks: usize,
+}
+
+/// Payload of `ingest-progress`. `stage` is one of: reading, embedding, stored, error.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct IngestProgress<'a> {
+    path: &'a str,
+    file_index: usize,
+    total_files: usize,
+    stage: &'a str,
+    chunks_done: usize,
+    chunks_total: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'a str>,
+}
+
+/// Text of one extracted unit: the whole file, or one PDF page (1-based).
+pub struct Section {
+    pub page: Option<usize>,
+    pub text: String,
+}
+
+/// One chunk of a section. Offsets are character offsets into the section text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    pub text: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// True if the file has one of SUPPORTED_EXTENSIONS.
+pub fn is_supported_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| SUPPORTED_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+/// Read a supported file into sections of plain text.
+pub fn extract_sections(path: &Path) -> Result<Vec<Section>, ConfidantError> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "txt" | "md" | "markdown" => {
+            let bytes = fs::read(path)
+                .map_err(|e| ConfidantError::io(format!("Failed to read {}: {}", path.display(), e)))?;
+            Ok(vec![Section {
+                page: None,
+                text: String::from_utf8_lossy(&bytes).into_owned(),
+            }])
+        }
+        "html" | "htm" => {
+            let bytes = fs::read(path)
+                .map_err(|e| ConfidantError::io(format!("Failed to read {}: {}", path.display(), e)))?;
+            Ok(vec![Section {
+                page: None,
+                text: html_to_text(&String::from_utf8_lossy(&bytes)),
+            }])
+        }
+        "pdf" => {
+            let pages = pdf_extract::extract_text_by_pages(path).map_err(|e| {
+                ConfidantError::validation(format!("Failed to extract PDF text from {}: {}", path.display(), e))
+            })?;
+            Ok(pages
+                .into_iter()
+                .enumerate()
+                .map(|(i, text)| Section { page: Some(i + 1), text })
+                .collect())
+        }
+        _ => Err(ConfidantError::validation(format!(
+            "Unsupported file type '{}' for {} (expected .txt, .md, .html or .pdf)",
+            ext,
+            path.display()
+        ))),
+    }
+}
+
+/// Strip tags, scripts and styles from HTML; block elements become line breaks.
+pub fn html_to_text(html: &str) -> String {
+    let mut out = String::with_capacity(html.len() / 2);
+    let mut rest = html;
+    while let Some(lt) = rest.find('<') {
+        out.push_str(&decode_entities(&rest[..lt]));
+        let after = &rest[lt + 1..];
+        let Some(gt) = after.find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = after[..gt].trim_start_matches('/').to_lowercase();
+        let name: String = tag.chars().take_while(|c| c.is_ascii_alphanumeric()).collect();
+        rest = &after[gt + 1..];
+
+        if (name == "script" || name == "style") && !after.starts_with('/') {
+            // Skip everything up to the matching close tag
+            let close = format!("</{}", name);
+            match rest.to_ascii_lowercase().find(&close) {
+                Some(pos) => {
+                    rest = &rest[pos..];
+                    if let Some(end) = rest.find('>') {
+                        rest = &rest[end + 1..];
+                    }
+                }
+                None => rest = "",
+            }
+            continue;
+        }
+        if matches!(
+            name.as_str(),
+            "p" | "br" | "div" | "li" | "tr" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "section" | "article"
+                | "blockquote" | "pre" | "table" | "ul" | "ol"
+        ) {
+            out.push('\n');
+        }
+    }
+    out.push_str(&decode_entities(rest));
+
+    // Collapse runs of blank lines left by the markup
+    let mut text = String::with_capacity(out.len());
+    let mut blank = 0;
+    for line in out.lines() {
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if line.is_empty() {
+            blank += 1;
+            if blank == 1 && !text.is_empty() {
+                text.push('\n');
+            }
+        } else {
+            blank = 0;
+            text.push_str(&line);
+            text.push('\n');
+        }
+    }
+    text
+}
+
+fn decode_entities(s: &str) -> String {
+    s.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Split text into sentences as (start, end) byte ranges. A sentence ends after ., ! or ? followed by
+/// whitespace, or at a blank line (paragraph break).
+fn sentence_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if start.is_none() {
+            if c.is_whitespace() {
+                continue;
+            }
+            start = Some(i);
+        }
+        let next = chars.peek().map(|&(_, n)| n);
+        let end = i + c.len_utf8();
+        let terminal = matches!(c, '.' | '!' | '?') && next.is_none_or(|n| n.is_whitespace());
+        let paragraph = c == '\n' && text[end..].trim_start_matches([' ', '\t', '\r']).starts_with('\n');
+        if terminal || paragraph {
+            if let Some(s) = start.take() {
+                spans.push((s, text[..end].trim_end().len()));
+            }
+        }
+    }
+    if let Some(s) = start {
+        spans.push((s, text.trim_end().len()));
+    }
+    spans
+}
+
+/// Word ranges (start, end) in bytes within `text[from..to]`.
+fn word_spans(text: &str, from: usize, to: usize) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start: Option<usize> = None;
+    for (i, c) in text[from..to].char_indices() {
+        if c.is_whitespace() {
+            if let Some(s) = start.take() {
+                spans.push((from + s, from + i));
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    if let Some(s) = start {
+        spans.push((from + s, to));
+    }
+    spans
+}
+
+/// Sentence-aware chunking: whole sentences are packed into chunks of at most `chunk_size` words,
+/// and each chunk repeats the trailing sentences of the previous one covering at least `chunk_overlap`
+/// words. Sentences longer than `chunk_size` are split on word boundaries.
+pub fn chunk_text(text: &str, chunk_size: usize, chunk_overlap: usize) -> Vec<Chunk> {
+    let chunk_size = chunk_size.max(1);
+    let chunk_overlap = chunk_overlap.min(chunk_size.saturating_sub(1));
+
+    // Units are sentences, or word-windows of over-long sentences: (start, end, words)
+    let mut units: Vec<(usize, usize, usize)> = Vec::new();
+    for (s, e) in sentence_spans(text) {
+        let words = word_spans(text, s, e);
+        if words.len() <= chunk_size {
+            units.push((s, e, words.len()));
+        } else {
+            for piece in words.chunks(chunk_size) {
+                units.push((piece[0].0, piece[piece.len() - 1].1, piece.len()));
+            }
+        }
+    }
+
+    // Byte -> char offset, counting forward from the previous lookup (positions only move forward)
+    let char_offset = |cursor: &mut (usize, usize), byte: usize| {
+        if byte < cursor.0 {
+            *cursor = (0, 0);
+        }
+        cursor.1 += text[cursor.0..byte].chars().count();
+        cursor.0 = byte;
+        cursor.1
+    };
+    let (mut start_cursor, mut end_cursor) = ((0, 0), (0, 0));
+
+    let mut chunks = Vec::new();
+    let mut first = 0;
+    while first < units.len() {
+        let mut last = first;
+        let mut words = units[first].2;
+        while last + 1 < units.len() && words + units[last + 1].2 <= chunk_size {
+            last += 1;
+            words += units[last].2;
+        }
+        let (start, end) = (units[first].0, units[last].1);
+        chunks.push(Chunk {
+            text: text[start..end].to_string(),
+            start: char_offset(&mut start_cursor, start),
+            end: char_offset(&mut end_cursor, end),
+        });
+        if last + 1 >= units.len() {
+            break;
+        }
+        // Step back over trailing units until the overlap is covered, always making progress and
+        // leaving room for the next new unit (otherwise the chunk would only repeat the overlap)
+        let new_unit = last + 1;
+        let mut next = new_unit;
+        let mut overlap = 0;
+        while next > first + 1
+            && overlap < chunk_overlap
+            && overlap + units[next - 1].2 + units[new_unit].2 <= chunk_size
+        {
+            next -= 1;
+            overlap += units[next].2;
+        }
+        first = next;
+    }
+    chunks
+}
+
+/// Id prefix for a file's chunks (same path -> same prefix). Each ingest adds a run tag after it, so new
+/// chunks never overwrite the ones they replace.
+fn file_id(path: &str) -> String {
+    let digest = Sha256::digest(path.as_bytes());
+    format!("file_{}", &hex::encode(digest)[..16])
+}
+
+fn emit_progress(app: &AppHandle, progress: IngestProgress) {
+    let _ = app.emit(INGEST_PROGRESS_EVENT, progress);
+}
+
+/// Chunk, embed and store one file. Existing chunks from the same path are replaced: the new chunks are
+/// stored under fresh ids first and the old ones deleted only after that succeeded, so a failure leaves
+/// the previous version searchable.
+pub async fn ingest_file(
+    app: &AppHandle,
+    path: &Path,
+    collection_name: &str,
+    chunk_size: usize,
+    chunk_overlap: usize,
+    mut on_progress: impl FnMut(&str, usize, usize),
+) -> Result<usize, ConfidantError> {
+    let path_str = path.to_string_lossy().to_string();
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path_str.clone());
+    let file_type = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    on_progress("reading", 0, 0);
+    // File reads and PDF parsing block; a PDF that makes the parser panic only fails this file
+    let owned_path = path.to_path_buf();
+    let sections = tauri::async_runtime::spawn_blocking(move || extract_sections(&owned_path))
+        .await
+        .map_err(|e| ConfidantError::validation(format!("Failed to extract text from {}: {}", path.display(), e)))??;
+    let now = chrono::Utc::now();
+    let id_prefix = format!("{}_{:x}", file_id(&path_str), now.timestamp_nanos_opt().unwrap_or_default());
+    let ingested_at = now.to_rfc3339();
+
+    let mut pending: Vec<(String, String, serde_json::Value)> = Vec::new();
+    for section in &sections {
+        for chunk in chunk_text(&section.text, chunk_size, chunk_overlap) {
+            let index = pending.len();
+            let mut metadata = serde_json::json!({
+                "source": file_name,
+                "source_path": path_str,
+                "file_type": file_type,
+                "chunk_index": index as u64,
+                "start_offset": chunk.start as u64,
+                "end_offset": chunk.end as u64,
+                "ingested_at": ingested_at,
+            });
+            if let Some(page) = section.page {
+                metadata["page"] = serde_json::json!(page as u64);
+            }
+            pending.push((format!("{}_{}", id_prefix, index), chunk.text, metadata));
+        }
+    }
+    if pending.is_empty() {
+        return Err(ConfidantError::validation(format!("No text found in {}", path.display())));
+    }
+
+    let total = pending.len();
+    on_progress("embedding", 0, total);
+    let stored = store_chunks(app, collection_name, &pending, |done| on_progress("embedding", done, total)).await;
+    if let Err(e) = stored {
+        // Drop the partial new version; the previous chunks are still in place
+        let ids: Vec<String> = pending.iter().map(|(id, _, _)| id.clone()).collect();
+        let _ = delete_documents(app.clone(), collection_name.to_string(), ids).await;
+        return Err(e);
+    }
+
+    // Prune chunks from earlier ingests of this file
+    let where_json = serde_json::json!({
+        "$and": [{ "source_path": path_str }, { "ingested_at": { "$ne": ingested_at } }]
+    })
+    .to_string();
+    delete_documents_by_filter(app.clone(), collection_name.to_string(), where_json).await?;
+    Ok(total)
+}
+
+/// Embed and add chunks in batches, reporting the number stored so far.
+async fn store_chunks(
+    app: &AppHandle,
+    collection_name: &str,
+    pending: &[(String, String, serde_json::Value)],
+    mut on_stored: impl FnMut(usize),
+) -> Result<(), ConfidantError> {
+    let mut done = 0;
+    for batch in pending.chunks(EMBED_BATCH_SIZE) {
+        let texts: Vec<String> = batch.iter().map(|(_, text, _)| text.clone()).collect();
+        let embeddings = embed_passages(app.clone(), texts).await?;
+        let documents: Vec<VectorDocument> = batch
+            .iter()
+            .zip(embeddings)
+            .map(|((id, text, metadata), embedding)| VectorDocument {
+                id: id.clone(),
+                text: text.clone(),
+                embedding,
+                metadata: metadata.clone(),
+            })
+            .collect();
+        add_documents_to_collection(app.clone(), collection_name.to_string(), documents).await?;
+        done += batch.len();
+        on_stored(done);
+    }
+    Ok(())
+}
+
+/// Ingest local files into a collection: extract text (.txt, .md, .html, .pdf), split into
+/// sentence-aware overlapping chunks, embed, and store with provenance metadata
+/// (source, source_path, page, chunk_index, start_offset, end_offset).
+/// Emits `ingest-progress` per file; a failing file is reported and the rest still run.
+#[tauri::command]
+pub async fn ingest_files(
+    app: AppHandle,
+    paths: Vec<String>,
+    collection_name: String,
+    options: Option<IngestOptions>,
+) -> Result<IngestReport, ConfidantError> {
+    let options = options.unwrap_or_default();
+    let chunk_size = options.chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE);
+    let chunk_overlap = options.chunk_overlap.unwrap_or(DEFAULT_CHUNK_OVERLAP);
+    if chunk_size == 0 {
+        return Err(ConfidantError::validation("chunk_size must be at least 1"));
+    }
+    if chunk_overlap >= chunk_size {
+        return Err(ConfidantError::validation(format!(
+            "chunk_overlap ({}) must be smaller than chunk_size ({})",
+            chunk_overlap, chunk_size
+        )));
+    }
+
+    initialize_vector_store(app.clone(), collection_name.clone(), None).await?;
+
+    let total_files = paths.len();
+    let mut files = Vec::with_capacity(total_files);
+    let mut total_chunks = 0;
+    for (file_index, path) in paths.iter().enumerate() {
+        let result = ingest_file(
+            &app,
+            Path::new(path),
+            &collection_name,
+            chunk_size,
+            chunk_overlap,
+            |stage, chunks_done, chunks_total| {
+                emit_progress(
+                    &app,
+                    IngestProgress {
+                        path,
+                        file_index,
+                        total_files,
+                        stage,
+                        chunks_done,
+                        chunks_total,
+                        error: None,
+                    },
+                )
+            },
+        )
+        .await;
+
+        match result {
+            Ok(chunks) => {
+                emit_progress(
+                    &app,
+                    IngestProgress {
+                        path,
+                        file_index,
+                        total_files,
+                        stage: "stored",
+                        chunks_done: chunks,
+                        chunks_total: chunks,
+                        error: None,
+                    },
+                );
+                total_chunks += chunks;
+                files.push(IngestedFile {
+                    path: path.clone(),
+                    chunks,
+                    error: None,
+                });
+            }
+            Err(e) => {
+                let e = e.to_string();
+                eprintln!("[Confidant] Ingest failed for {}: {}", path, e);
+                emit_progress(
+                    &app,
+                    IngestProgress {
+                        path,
+                        file_index,
+                        total_files,
+                        stage: "error",
+                        chunks_done: 0,
+                        chunks_total: 0,
+                        error: Some(&e),
+                    },
+                );
+                files.push(IngestedFile {
+                    path: path.clone(),
+                    chunks: 0,
+                    error: Some(e),
+                });
+            }
+        }
+    }
+
+    Ok(IngestReport {
+        collection: collection_name,
+        files,
+        total_chunks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentences(text: &str) -> Vec<&str> {
+        sentence_spans(text).into_iter().map(|(s, e)| &text[s..e]).collect()
+    }
+
+    #[test]
+    fn sentence_spans_split_on_terminators_and_paragraphs() {
+        assert_eq!(
+            sentences("  First one. Second! Third?  Version 1.5 stays whole."),
+            vec!["First one.", "Second!", "Third?", "Version 1.5 stays whole."]
+        );
+        assert_eq!(
+            sentences("Heading without stop\n\nNext paragraph\nsame paragraph"),
+            vec!["Heading without stop", "Next paragraph\nsame paragraph"]
+        );
+        assert_eq!(sentences("Ünïcode ends here… Then more."), vec!["Ünïcode ends here… Then more."]);
+        assert!(sentences(" \n\t ").is_empty());
+    }
+
+    #[test]
+    fn chunk_text_packs_sentences_with_overlap() {
+        let text = "One two three. Four five six. Seven eight nine. Ten eleven twelve.";
+        let chunks = chunk_text(text, 6, 3);
+        let texts: Vec<&str> = chunks.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec!["One two three. Four five six.", "Four five six. Seven eight nine.", "Seven eight nine. Ten eleven twelve."]
+        );
+        // Offsets are character offsets into the text
+        for chunk in &chunks {
+            let slice: String = text.chars().skip(chunk.start).take(chunk.end - chunk.start).collect();
+            assert_eq!(slice, chunk.text);
+        }
+    }
+
+    #[test]
+    fn chunk_text_splits_long_sentences_and_uses_char_offsets() {
+        let long = (0..25).map(|i| format!("w{}", i)).collect::<Vec<_>>().join(" ");
+        let chunks = chunk_text(&long, 10, 0);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|c| c.text.split_whitespace().count() <= 10));
+
+        let text = "Café crème. Naïve résumé.";
+        let chunks = chunk_text(text, 2, 0);
+        assert_eq!(chunks[1].text, "Naïve résumé.");
+        assert_eq!(chunks[1].start, 12);
+        assert_eq!(chunks[1].end, text.chars().count());
+
+        assert!(chunk_text("", 10, 2).is_empty());
+    }
+
+    #[test]
+    fn html_to_text_strips_markup() {
+        let html = "<html><head><style>p { color: red; }</style><script>var a = '<p>';</script></head>\
+            <body><h1>Title</h1><p>Tom &amp; Jerry&nbsp;say &lt;hi&gt;</p><ul><li>One</li><li>Two</li></ul>\
+            <p>  spaced   out  </p></body></html>";
+        // Block elements become paragraph breaks (runs of blank lines collapse to one)
+        assert_eq!(html_to_text(html), "Title\n\nTom & Jerry say <hi>\n\nOne\n\nTwo\n\nspaced out\n");
+        assert_eq!(html_to_text("<SCRIPT>alert(1)</SCRIPT>kept"), "kept\n");
+        assert_eq!(html_to_text("text <unclosed"), "text\n");
+    }
+}
@@ -0,0 +1,562 @@
+// KB Binary - Compact binary encoding of knowledge base packages (.ckb)
+//
+// Layout (all integers little-endian):
+//   magic "CKB1" | u16 format version | u8 embedding encoding | u8 reserved
+//   u32 manifest length | manifest JSON (same KnowledgeBaseManifest as the JSON format)
+//   u64 document count | u32 embedding dimension
+//   embedding matrix, one row per document:
+//     f32:  dimension x f32
+//     f16:  dimension x f16
+//     int8: f32 scale followed by dimension x i8 (value = i8 * scale)
+//   zstd-compressed JSON Lines, one { id, text, metadata } object per document, to end of file
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::error::ConfidantError;
+use crate::kb_package::{
+    check_embedding_row, format_package_errors, parse_document_row, validate_manifest, KnowledgeBaseManifest,
+    KnowledgeBasePackage, PackageDocument,
+};
+
+/// File extension used for binary packages.
+pub const FILE_EXTENSION: &str = "ckb";
+
+const MAGIC: &[u8; 4] = b"CKB1";
+
+/// Binary layout version written by this build.
+const FORMAT_VERSION: u16 = 1;
+
+/// zstd level for the document section.
+const ZSTD_LEVEL: i32 = 19;
+
+/// Header limits, checked before anything is allocated from the (untrusted) header.
+const MAX_MANIFEST_BYTES: usize = 1024 * 1024;
+const MAX_DIMENSION: usize = 8192;
+
+/// How embedding rows are stored in the matrix section.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingEncoding {
+    /// Lossless 32-bit floats.
+    #[default]
+    F32,
+    /// Half precision; halves the matrix size.
+    F16,
+    /// Symmetric 8-bit quantization with one f32 scale per row.
+    Int8,
+}
+
+impl EmbeddingEncoding {
+    fn tag(self) -> u8 {
+        match self {
+            EmbeddingEncoding::F32 => 0,
+            EmbeddingEncoding::F16 => 1,
+            EmbeddingEncoding::Int8 => 2,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Result<Self, ConfidantError> {
+        match tag {
+            0 => Ok(EmbeddingEncoding::F32),
+            1 => Ok(EmbeddingEncoding::F16),
+            2 => Ok(EmbeddingEncoding::Int8),
+            other => Err(ConfidantError::validation(format!("Unknown embedding encoding {} in binary package", other))),
+        }
+    }
+
+    /// Bytes per matrix row for `dimension` values.
+    fn row_size(self, dimension: usize) -> usize {
+        match self {
+            EmbeddingEncoding::F32 => dimension * 4,
+            EmbeddingEncoding::F16 => dimension * 2,
+            EmbeddingEncoding::Int8 => 4 + dimension,
+        }
+    }
+
+    fn encode_row(self, row: &[f32], out: &mut Vec<u8>) {
+        match self {
+            EmbeddingEncoding::F32 => {
+                for v in row {
+                    out.extend_from_slice(&v.to_le_bytes());
+                }
+            }
+            EmbeddingEncoding::F16 => {
+                for v in row {
+                    out.extend_from_slice(&half::f16::from_f32(*v).to_le_bytes());
+                }
+            }
+            EmbeddingEncoding::Int8 => {
+                let max = row.iter().fold(0.0f32, |m, v| m.max(v.abs()));
+                let scale = if max > 0.0 { max / 127.0 } else { 0.0 };
+                out.extend_from_slice(&scale.to_le_bytes());
+                for v in row {
+                    let q = if scale > 0.0 { (v / scale).round().clamp(-127.0, 127.0) as i8 } else { 0 };
+                    out.push(q as u8);
+                }
+            }
+        }
+    }
+
+    fn decode_row(self, bytes: &[u8], dimension: usize) -> Vec<f32> {
+        match self {
+            EmbeddingEncoding::F32 => bytes
+                .chunks_exact(4)
+                .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+                .collect(),
+            EmbeddingEncoding::F16 => bytes
+                .chunks_exact(2)
+                .map(|c| half::f16::from_le_bytes([c[0], c[1]]).to_f32())
+                .collect(),
+            EmbeddingEncoding::Int8 => {
+                let scale = f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                bytes[4..4 + dimension].iter().map(|b| (*b as i8) as f32 * scale).collect()
+            }
+        }
+    }
+}
+
+/// True if the file starts with the binary package magic.
+pub fn is_binary_package(path: &Path) -> Result<bool, ConfidantError> {
+    let mut file = File::open(path)
+        .map_err(|e| ConfidantError::io(format!("Failed to open knowledge base package: {}", e)))?;
+    let mut magic = [0u8; 4];
+    match file.read_exact(&mut magic) {
+        Ok(()) => Ok(&magic == MAGIC),
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(ConfidantError::io(format!("Failed to read knowledge base package: {}", e))),
+    }
+}
+
+/// Write a package in the binary format (via a temp file, so a failed write leaves no partial package).
+pub fn write_binary_package(
+    package: &KnowledgeBasePackage,
+    path: &Path,
+    encoding: EmbeddingEncoding,
+) -> Result<(), ConfidantError> {
+    let dimension = package.manifest.embedding_dimension;
+    if package.embeddings.len() != package.documents.len() {
+        return Err(ConfidantError::validation(format!(
+            "Package has {} embeddings for {} documents",
+            package.embeddings.len(),
+            package.documents.len()
+        )));
+    }
+    if let Some(i) = package.embeddings.iter().position(|e| e.len() != dimension) {
+        return Err(ConfidantError::validation(format!(
+            "embeddings[{}]: expected {} values, got {}",
+            i,
+            dimension,
+            package.embeddings[i].len()
+        )));
+    }
+
+    let mut manifest = package.manifest.clone();
+    manifest.document_count = package.documents.len();
+    let manifest_json = serde_json::to_vec(&manifest)
+        .map_err(|e| ConfidantError::internal(format!("Failed to serialize manifest: {}", e)))?;
+
+    let tmp_path = path.with_extension("ckb.tmp");
+    let file = File::create(&tmp_path)
+        .map_err(|e| ConfidantError::io(format!("Failed to create package file: {}", e)))?;
+    let mut writer = BufWriter::new(file);
+    let write_err = |e: std::io::Error| ConfidantError::io(format!("Failed to write package file: {}", e));
+
+    let mut header = Vec::with_capacity(24 + manifest_json.len());
+    header.extend_from_slice(MAGIC);
+    header.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    header.push(encoding.tag());
+    header.push(0);
+    header.extend_from_slice(&(manifest_json.len() as u32).to_le_bytes());
+    header.extend_from_slice(&manifest_json);
+    header.extend_from_slice(&(package.documents.len() as u64).to_le_bytes());
+    header.extend_from_slice(&(dimension as u32).to_le_bytes());
+    writer.write_all(&header).map_err(write_err)?;
+
+    let mut row = Vec::with_capacity(encoding.row_size(dimension));
+    for embedding in &package.embeddings {
+        row.clear();
+        encoding.encode_row(embedding, &mut row);
+        writer.write_all(&row).map_err(write_err)?;
+    }
+
+    let mut encoder = zstd::Encoder::new(writer, ZSTD_LEVEL).map_err(write_err)?;
+    for doc in &package.documents {
+        serde_json::to_writer(&mut encoder, doc)
+            .map_err(|e| ConfidantError::internal(format!("Failed to serialize document: {}", e)))?;
+        encoder.write_all(b"\n").map_err(write_err)?;
+    }
+    let mut writer = encoder.finish().map_err(write_err)?;
+    writer.flush().map_err(write_err)?;
+    drop(writer);
+
+    fs::rename(&tmp_path, path)
+        .map_err(|e| ConfidantError::io(format!("Failed to finalize package file: {}", e)))?;
+    Ok(())
+}
+
+/// Streaming reader: yields `(document, embedding)` rows without loading the whole package.
+/// Rows are not validated; run `validate_binary_package` first when the source is untrusted.
+pub struct BinaryPackageReader {
+    pub manifest: KnowledgeBaseManifest,
+    pub encoding: EmbeddingEncoding,
+    pub document_count: usize,
+    pub dimension: usize,
+    matrix: BufReader<File>,
+    documents: BufReader<zstd::Decoder<'static, BufReader<File>>>,
+    row: Vec<u8>,
+    line: String,
+    read: usize,
+}
+
+impl BinaryPackageReader {
+    pub fn open(path: &Path) -> Result<Self, ConfidantError> {
+        let open_err = |e: std::io::Error| ConfidantError::io(format!("Failed to read binary package: {}", e));
+        let mut matrix = BufReader::new(File::open(path).map_err(open_err)?);
+
+        let mut fixed = [0u8; 12];
+        matrix.read_exact(&mut fixed).map_err(open_err)?;
+        if &fixed[0..4] != MAGIC {
+            return Err(ConfidantError::validation("Not a binary knowledge base package"));
+        }
+        let version = u16::from_le_bytes([fixed[4], fixed[5]]);
+        if version != FORMAT_VERSION {
+            return Err(ConfidantError::validation(format!("Unsupported binary package version {}", version)));
+        }
+        let encoding = EmbeddingEncoding::from_tag(fixed[6])?;
+        let manifest_len = u32::from_le_bytes([fixed[8], fixed[9], fixed[10], fixed[11]]) as usize;
+        let file_len = matrix.get_ref().metadata().map_err(open_err)?.len();
+        if manifest_len > MAX_MANIFEST_BYTES || (12 + manifest_len + 12) as u64 > file_len {
+            return Err(ConfidantError::validation(format!(
+                "Binary package manifest length {} is invalid (limit {} bytes, file has {})",
+                manifest_len, MAX_MANIFEST_BYTES, file_len
+            )));
+        }
+
+        let mut manifest_json = vec![0u8; manifest_len];
+        matrix.read_exact(&mut manifest_json).map_err(open_err)?;
+        let manifest: KnowledgeBaseManifest = serde_json::from_slice(&manifest_json)
+            .map_err(|e| ConfidantError::validation(format!("manifest: {}", e)))?;
+
+        let mut counts = [0u8; 12];
+        matrix.read_exact(&mut counts).map_err(open_err)?;
+        let document_count = usize::try_from(u64::from_le_bytes(counts[0..8].try_into().unwrap_or_default()))
+            .map_err(|_| ConfidantError::validation("Binary package declares too many documents"))?;
+        let dimension = u32::from_le_bytes(counts[8..12].try_into().unwrap_or_default()) as usize;
+
+        if dimension == 0 || dimension > MAX_DIMENSION {
+            return Err(ConfidantError::validation(format!(
+                "Binary package embedding dimension {} is invalid (limit {})",
+                dimension, MAX_DIMENSION
+            )));
+        }
+
+        let matrix_start = (12 + manifest_len + 12) as u64;
+        let documents_start = (encoding.row_size(dimension) as u64)
+            .checked_mul(document_count as u64)
+            .and_then(|matrix_len| matrix_len.checked_add(matrix_start))
+            .ok_or_else(|| {
+                ConfidantError::validation(format!("Binary package declares too many documents ({})", document_count))
+            })?;
+        if documents_start > file_len {
+            return Err(ConfidantError::validation(format!(
+                "Binary package is truncated: embedding matrix needs {} bytes, file has {}",
+                documents_start, file_len
+            )));
+        }
+
+        let mut doc_file = File::open(path).map_err(open_err)?;
+        doc_file.seek(SeekFrom::Start(documents_start)).map_err(open_err)?;
+        let decoder = zstd::Decoder::new(doc_file).map_err(open_err)?;
+
+        Ok(BinaryPackageReader {
+            manifest,
+            encoding,
+            document_count,
+            dimension,
+            matrix,
+            documents: BufReader::new(decoder),
+            row: vec![0u8; encoding.row_size(dimension)],
+            line: String::new(),
+            read: 0,
+        })
+    }
+
+    /// Next row with the document still as raw JSON (used by validation).
+    fn next_raw(&mut self) -> Option<Result<(serde_json::Value, Vec<f32>), String>> {
+        if self.read >= self.document_count {
+            return None;
+        }
+        let index = self.read;
+        self.read += 1;
+
+        if let Err(e) = self.matrix.read_exact(&mut self.row) {
+            return Some(Err(format!("embeddings[{}]: {}", index, e)));
+        }
+        let embedding = self.encoding.decode_row(&self.row, self.dimension);
+
+        self.line.clear();
+        match self.documents.read_line(&mut self.line) {
+            Ok(0) => Some(Err(format!("documents[{}]: unexpected end of document section", index))),
+            Ok(_) => match serde_json::from_str(self.line.trim_end()) {
+                Ok(value) => Some(Ok((value, embedding))),
+                Err(e) => Some(Err(format!("documents[{}]: invalid JSON: {}", index, e))),
+            },
+            Err(e) => Some(Err(format!("documents[{}]: {}", index, e))),
+        }
+    }
+
+    /// True if the document section has data past the declared document count.
+    fn has_trailing_documents(&mut self) -> Result<bool, ConfidantError> {
+        self.documents
+            .fill_buf()
+            .map(|buf| buf.iter().any(|b| !b.is_ascii_whitespace()))
+            .map_err(|e| ConfidantError::io(format!("Failed to read document section: {}", e)))
+    }
+}
+
+impl Iterator for BinaryPackageReader {
+    type Item = Result<(PackageDocument, Vec<f32>), ConfidantError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.read;
+        self.next_raw().map(|row| {
+            let (value, embedding) = row.map_err(ConfidantError::validation)?;
+            let mut doc: PackageDocument = serde_json::from_value(value)
+                .map_err(|e| ConfidantError::validation(format!("documents[{}]: {}", index, e)))?;
+            if doc.metadata.is_null() {
+                doc.metadata = serde_json::json!({});
+            }
+            Ok((doc, embedding))
+        })
+    }
+}
+
+/// Streaming validation pass with the same per-row checks as the JSON importer (`reembed` as in validate_manifest).
+pub fn validate_binary_package(path: &Path, reembed: bool) -> Result<KnowledgeBaseManifest, ConfidantError> {
+    let mut reader = BinaryPackageReader::open(path)?;
+    let mut errors: Vec<String> = Vec::new();
+    validate_manifest(&reader.manifest, reembed, &mut errors);
+    if reader.manifest.document_count != reader.document_count {
+        errors.push(format!(
+            "manifest.documentCount: declares {} documents but package contains {}",
+            reader.manifest.document_count, reader.document_count
+        ));
+    }
+    if reader.manifest.embedding_dimension != reader.dimension {
+        errors.push(format!(
+            "manifest.embeddingDimension: declares {} but matrix rows have {} values",
+            reader.manifest.embedding_dimension, reader.dimension
+        ));
+    }
+
+    let mut seen_ids = HashSet::new();
+    let mut i = 0;
+    while let Some(row) = reader.next_raw() {
+        match row {
+            Ok((raw, embedding)) => {
+                parse_document_row(i, &raw, &mut seen_ids, &mut errors);
+                check_embedding_row(i, &embedding, reader.manifest.embedding_dimension, &mut errors);
+            }
+            Err(e) => {
+                // The stream is unusable after a structural error
+                errors.push(e);
+                break;
+            }
+        }
+        i += 1;
+    }
+    if errors.is_empty() && reader.has_trailing_documents()? {
+        errors.push(format!(
+            "documents: more than the {} documents declared in the header",
+            reader.document_count
+        ));
+    }
+
+    if !errors.is_empty() {
+        return Err(ConfidantError::validation(format_package_errors(errors)));
+    }
+    Ok(reader.manifest)
+}
+
+/// Validate and read a whole binary package into memory (used for conversion back to JSON).
+pub fn read_binary_package(path: &Path) -> Result<KnowledgeBasePackage, ConfidantError> {
+    validate_binary_package(path, false)?;
+    let reader = BinaryPackageReader::open(path)?;
+    let manifest = reader.manifest.clone();
+    let mut documents = Vec::with_capacity(reader.document_count);
+    let mut embeddings = Vec::with_capacity(reader.document_count);
+    for row in reader {
+        let (doc, embedding) = row?;
+        documents.push(doc);
+        embeddings.push(embedding);
+    }
+    Ok(KnowledgeBasePackage {
+        manifest,
+        documents,
+        embeddings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write a header-only package (magic, version, f32 encoding, manifest, counts) to a temp file.
+    fn header_file(
+        name: &str,
+        manifest_len: u32,
+        manifest: &[u8],
+        document_count: u64,
+        dimension: u32,
+    ) -> std::path::PathBuf {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&[EmbeddingEncoding::F32.tag(), 0]);
+        bytes.extend_from_slice(&manifest_len.to_le_bytes());
+        bytes.extend_from_slice(manifest);
+        bytes.extend_from_slice(&document_count.to_le_bytes());
+        bytes.extend_from_slice(&dimension.to_le_bytes());
+        let path = std::env::temp_dir().join(format!("confidant-kb-binary-{}-{}.ckb", name, std::process::id()));
+        fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    fn open_err(path: &Path) -> String {
+        let result = BinaryPackageReader::open(path).map(|_| ());
+        let _ = fs::remove_file(path);
+        let err = result.expect_err("header should be rejected");
+        assert_eq!(err.code(), "validation");
+        err.to_string()
+    }
+
+    #[test]
+    fn rejects_oversized_header_fields_before_allocating() {
+        let manifest = br#"{"name":"t","version":"1","embeddingModel":"all-MiniLM-L6-v2","embeddingDimension":384,"documentCount":1}"#;
+        let len = manifest.len() as u32;
+
+        let err = open_err(&header_file("manifest", u32::MAX, manifest, 1, 384));
+        assert!(err.contains("manifest length"), "{}", err);
+
+        let err = open_err(&header_file("dimension", len, manifest, 1, u32::MAX));
+        assert!(err.contains("dimension"), "{}", err);
+
+        let err = open_err(&header_file("overflow", len, manifest, u64::MAX, 384));
+        assert!(err.contains("too many documents") || err.contains("truncated"), "{}", err);
+
+        let err = open_err(&header_file("truncated", len, manifest, 1000, 384));
+        assert!(err.contains("truncated"), "{}", err);
+    }
+
+    fn sample_package(documents: usize) -> KnowledgeBasePackage {
+        let dimension = crate::embeddings::embedding_model_spec(crate::embeddings::EMBEDDING_MODEL_ID)
+            .map(|spec| spec.dimension)
+            .unwrap_or(384);
+        KnowledgeBasePackage {
+            manifest: KnowledgeBaseManifest {
+                version: "1.0.0".to_string(),
+                name: "test".to_string(),
+                description: String::new(),
+                document_count: documents,
+                embedding_dimension: dimension,
+                created_at: String::new(),
+                sources: Vec::new(),
+                embedding_model: None,
+            },
+            documents: (0..documents)
+                .map(|i| PackageDocument {
+                    id: format!("doc-{}", i),
+                    text: format!("Document number {}", i),
+                    metadata: serde_json::json!({ "source": "test", "page": i }),
+                })
+                .collect(),
+            embeddings: (0..documents)
+                .map(|i| (0..dimension).map(|j| ((i * 31 + j) as f32 * 0.37).sin()).collect())
+                .collect(),
+        }
+    }
+
+    fn temp_package(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("confidant-kb-binary-{}-{}.ckb", name, std::process::id()))
+    }
+
+    /// Offset of the compressed document section in a written package.
+    fn documents_offset(bytes: &[u8], encoding: EmbeddingEncoding) -> usize {
+        let manifest_len = u32::from_le_bytes(bytes[8..12].try_into().unwrap()) as usize;
+        let counts = 12 + manifest_len;
+        let count = u64::from_le_bytes(bytes[counts..counts + 8].try_into().unwrap()) as usize;
+        let dimension = u32::from_le_bytes(bytes[counts + 8..counts + 12].try_into().unwrap()) as usize;
+        counts + 12 + encoding.row_size(dimension) * count
+    }
+
+    #[test]
+    fn round_trips_every_encoding() {
+        let package = sample_package(3);
+        for (name, encoding) in [
+            ("f32", EmbeddingEncoding::F32),
+            ("f16", EmbeddingEncoding::F16),
+            ("int8", EmbeddingEncoding::Int8),
+        ] {
+            let path = temp_package(&format!("roundtrip-{}", name));
+            write_binary_package(&package, &path, encoding).unwrap();
+            let read = read_binary_package(&path).unwrap();
+            fs::remove_file(&path).unwrap();
+
+            assert_eq!(read.manifest.document_count, 3);
+            let ids: Vec<&str> = read.documents.iter().map(|d| d.id.as_str()).collect();
+            assert_eq!(ids, ["doc-0", "doc-1", "doc-2"]);
+            assert_eq!(read.documents[1].metadata, package.documents[1].metadata);
+            for (original, decoded) in package.embeddings.iter().zip(&read.embeddings) {
+                let max = original.iter().fold(0.0f32, |m, v| m.max(v.abs()));
+                // Half a quantization step for int8, f16 rounding otherwise
+                let bound = match encoding {
+                    EmbeddingEncoding::F32 => 0.0,
+                    EmbeddingEncoding::F16 => 1e-3,
+                    EmbeddingEncoding::Int8 => max / 127.0 / 2.0 + 1e-6,
+                };
+                for (a, b) in original.iter().zip(decoded) {
+                    assert!((a - b).abs() <= bound, "{:?}: {} vs {}", encoding, a, b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_documents_past_the_declared_count() {
+        let path = temp_package("trailing");
+        let package = sample_package(1);
+        write_binary_package(&package, &path, EmbeddingEncoding::F32).unwrap();
+        let mut bytes = fs::read(&path).unwrap();
+        bytes.truncate(documents_offset(&bytes, EmbeddingEncoding::F32));
+        let mut lines = Vec::new();
+        for doc in sample_package(2).documents {
+            lines.extend(serde_json::to_vec(&doc).unwrap());
+            lines.push(b'\n');
+        }
+        bytes.extend(zstd::encode_all(&lines[..], 3).unwrap());
+        fs::write(&path, bytes).unwrap();
+
+        let err = validate_binary_package(&path, false).expect_err("trailing document should be rejected");
+        fs::remove_file(&path).unwrap();
+        assert_eq!(err.code(), "validation");
+        assert!(err.to_string().contains("more than the 1 documents"), "{}", err);
+    }
+
+    #[test]
+    fn rejects_truncated_document_section() {
+        let path = temp_package("truncated-docs");
+        write_binary_package(&sample_package(4), &path, EmbeddingEncoding::F16).unwrap();
+        let mut bytes = fs::read(&path).unwrap();
+        let start = documents_offset(&bytes, EmbeddingEncoding::F16);
+        bytes.truncate(start + (bytes.len() - start) / 2);
+        fs::write(&path, bytes).unwrap();
+
+        let err = validate_binary_package(&path, false).expect_err("truncated package should be rejected");
+        fs::remove_file(&path).unwrap();
+        assert_eq!(err.code(), "validation");
+        assert!(err.to_string().contains("documents["), "{}", err);
+    }
+}
@@ -0,0 +1,678 @@
+// KB Package - Knowledge base package format ({ manifest, documents, embeddings }) shared with the frontend
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::embeddings::{
+    active_embedding_model, active_embedding_model_id, embed_passages, embedding_model_spec, same_embedding_model,
+    EMBEDDING_MODEL_ID,
+};
+use crate::error::ConfidantError;
+use crate::kb_binary;
+use crate::package_signing::{copy_trusted_package, read_trusted_package, SignatureStatus};
+use crate::vector_store::{
+    delete_documents, get_all_documents, get_collection_embedding_info, get_collection_stats_by_name,
+    initialize_vector_store, upsert_documents, VectorDocument,
+};
+
+/// Package format version written by export.
+const PACKAGE_VERSION: &str = "1.0.0";
+
+/// Default collection for imports (must match frontend).
+const GLOBAL_KB_COLLECTION: &str = "dant_knowledge_global";
+
+/// Stop collecting row errors after this many; the rest are summarized.
+const MAX_REPORTED_ERRORS: usize = 50;
+
+/// Documents per upsert call during import.
+const IMPORT_BATCH_SIZE: usize = 50;
+
+/// Same shape as the frontend KnowledgeBaseManifest (camelCase keys).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct KnowledgeBaseManifest {
+    pub version: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub document_count: usize,
+    pub embedding_dimension: usize,
+    #[serde(default)]
+    pub created_at: String,
+    #[serde(default)]
+    pub sources: Vec<String>,
+    /// Model that produced the embeddings (older packages omit it).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_model: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PackageDocument {
+    pub id: String,
+    pub text: String,
+    #[serde(default)]
+    pub metadata: serde_json::Value,
+}
+
+/// Full package: `embeddings[i]` belongs to `documents[i]`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KnowledgeBasePackage {
+    pub manifest: KnowledgeBaseManifest,
+    pub documents: Vec<PackageDocument>,
+    pub embeddings: Vec<Vec<f32>>,
+}
+
+/// Write a package as JSON. Writes to a temp file first so a failed export never leaves a half-written package.
+pub fn write_package_json(package: &KnowledgeBasePackage, path: &Path) -> Result<(), ConfidantError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| ConfidantError::io(format!("Failed to create export directory: {}", e)))?;
+    }
+    let tmp_path = path.with_extension("json.tmp");
+    let file = fs::File::create(&tmp_path)
+        .map_err(|e| ConfidantError::io(format!("Failed to create package file: {}", e)))?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer(&mut writer, package)
+        .map_err(|e| ConfidantError::io(format!("Failed to write package: {}", e)))?;
+    writer.flush()
+        .map_err(|e| ConfidantError::io(format!("Failed to write package: {}", e)))?;
+    fs::rename(&tmp_path, path)
+        .map_err(|e| ConfidantError::io(format!("Failed to move package into place: {}", e)))
+}
+
+/// Export a collection as a KnowledgeBasePackage file that the importer can read back
+/// (JSON, or the binary format when `path` ends in .ckb).
+/// `name` defaults to the collection name; `version` defaults to 1.0.0.
+#[tauri::command]
+pub async fn export_collection(
+    app: AppHandle,
+    collection_name: String,
+    path: String,
+    name: Option<String>,
+    description: Option<String>,
+    version: Option<String>,
+) -> Result<KnowledgeBaseManifest, ConfidantError> {
+    // Collections without a recorded model were built with the default model
+    let embedding_model = get_collection_embedding_info(app.clone(), collection_name.clone())
+        .await?
+        .recorded_model
+        .unwrap_or_else(|| EMBEDDING_MODEL_ID.to_string());
+    let docs = get_all_documents(&app, &collection_name).await?;
+    let package = package_from_documents(&collection_name, &embedding_model, docs, name, description, version)?;
+
+    #[cfg(debug_assertions)]
+    eprintln!("[KB Package] Exporting {} documents from {} to {}", package.documents.len(), collection_name, path);
+
+    let out_path = Path::new(&path);
+    if out_path.extension().is_some_and(|ext| ext == kb_binary::FILE_EXTENSION) {
+        kb_binary::write_binary_package(&package, out_path, kb_binary::EmbeddingEncoding::default())?;
+    } else {
+        write_package_json(&package, out_path)?;
+    }
+
+    Ok(package.manifest)
+}
+
+/// Build a package from a collection's documents (all embeddings must share one dimension). `embedding_model` is
+/// the model the collection was built with.
+pub fn package_from_documents(
+    collection_name: &str,
+    embedding_model: &str,
+    docs: Vec<VectorDocument>,
+    name: Option<String>,
+    description: Option<String>,
+    version: Option<String>,
+) -> Result<KnowledgeBasePackage, ConfidantError> {
+    let embedding_dimension = docs.first().map(|d| d.embedding.len()).unwrap_or(0);
+    if let Some(bad) = docs.iter().find(|d| d.embedding.len() != embedding_dimension) {
+        return Err(ConfidantError::validation(format!(
+            "Cannot export {}: document '{}' has embedding dimension {} (expected {})",
+            collection_name,
+            bad.id,
+            bad.embedding.len(),
+            embedding_dimension
+        )));
+    }
+
+    let mut sources: BTreeSet<String> = docs
+        .iter()
+        .filter_map(|d| d.metadata.get("source").and_then(|s| s.as_str()))
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect();
+    if sources.is_empty() {
+        sources.insert(collection_name.to_string());
+    }
+
+    let manifest = KnowledgeBaseManifest {
+        version: version.unwrap_or_else(|| PACKAGE_VERSION.to_string()),
+        name: name.unwrap_or_else(|| collection_name.to_string()),
+        description: description.unwrap_or_default(),
+        document_count: docs.len(),
+        embedding_dimension,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        sources: sources.into_iter().collect(),
+        embedding_model: Some(embedding_model.to_string()),
+    };
+
+    let (documents, embeddings): (Vec<PackageDocument>, Vec<Vec<f32>>) = docs
+        .into_iter()
+        .map(|d| {
+            let metadata = if d.metadata.is_null() { serde_json::json!({}) } else { d.metadata };
+            (PackageDocument { id: d.id, text: d.text, metadata }, d.embedding)
+        })
+        .unzip();
+
+    Ok(KnowledgeBasePackage {
+        manifest,
+        documents,
+        embeddings,
+    })
+}
+
+/// Package installed into a collection, persisted in data/kb_packages.json so upgrades can be diffed.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledPackage {
+    pub name: String,
+    pub version: String,
+    pub installed_at: String,
+    #[serde(default)]
+    pub embedding_model: Option<String>,
+    /// Signature status of the package file when it was installed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<SignatureStatus>,
+    /// Document id -> content hash at install time.
+    pub documents: BTreeMap<String, String>,
+}
+
+/// Result of import_kb_package.
+#[derive(Debug, Serialize)]
+pub struct ImportReport {
+    pub collection: String,
+    pub name: String,
+    pub version: String,
+    pub previous_version: Option<String>,
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+    pub signature: Option<SignatureStatus>,
+    /// True when the package was built with another model and its text was embedded with the active one.
+    pub reembedded: bool,
+}
+
+/// Get installed packages file path (data/kb_packages.json).
+fn get_installed_packages_path(app: &AppHandle) -> Result<PathBuf, ConfidantError> {
+    let base_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| ConfidantError::io(format!("Failed to get app data dir: {}", e)))?;
+    let data_dir = base_dir.join("data");
+    fs::create_dir_all(&data_dir)
+        .map_err(|e| ConfidantError::io(format!("Failed to create data directory: {}", e)))?;
+    Ok(data_dir.join("kb_packages.json"))
+}
+
+/// Unique path for the verified copy of a binary package being imported (data/kb_import/).
+fn get_import_copy_path(app: &AppHandle) -> Result<PathBuf, ConfidantError> {
+    let base_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| ConfidantError::io(format!("Failed to get app data dir: {}", e)))?;
+    let dir = base_dir.join("data").join("kb_import");
+    fs::create_dir_all(&dir)
+        .map_err(|e| ConfidantError::io(format!("Failed to create import directory: {}", e)))?;
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    Ok(dir.join(format!("{}-{}.{}", std::process::id(), nanos, kb_binary::FILE_EXTENSION)))
+}
+
+/// Installed package per collection (empty map if nothing was imported through import_kb_package yet).
+pub fn load_installed_packages(app: &AppHandle) -> Result<HashMap<String, InstalledPackage>, ConfidantError> {
+    let path = get_installed_packages_path(app)?;
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| ConfidantError::io(format!("Failed to read installed packages: {}", e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| ConfidantError::internal(format!("Failed to parse installed packages: {}", e)))
+}
+
+fn save_installed_packages(
+    app: &AppHandle,
+    packages: &HashMap<String, InstalledPackage>,
+) -> Result<(), ConfidantError> {
+    let path = get_installed_packages_path(app)?;
+    let content = serde_json::to_string_pretty(packages)
+        .map_err(|e| ConfidantError::internal(format!("Failed to serialize installed packages: {}", e)))?;
+    fs::write(&path, content)
+        .map_err(|e| ConfidantError::io(format!("Failed to write installed packages: {}", e)))
+}
+
+/// Hash of a document's text, metadata and embedding. Metadata keys serialize sorted, so the hash is stable.
+pub fn content_hash(doc: &PackageDocument, embedding: &[f32]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(doc.text.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(doc.metadata.to_string().as_bytes());
+    hasher.update([0u8]);
+    for v in embedding {
+        hasher.update(v.to_le_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Manifest-level checks shared by the JSON and binary formats. The package must be built with the active model,
+/// unless `reembed` is set (its text will be embedded again); a registered model must still match the dimension.
+pub fn validate_manifest(manifest: &KnowledgeBaseManifest, reembed: bool, errors: &mut Vec<String>) {
+    let model = package_model(manifest);
+    let active = active_embedding_model_id();
+    if !reembed && !same_embedding_model(model, &active) {
+        errors.push(format!(
+            "manifest.embeddingModel: package was built with '{}' but this app embeds with '{}'",
+            model, active
+        ));
+    }
+    if let Some(spec) = embedding_model_spec(model) {
+        if spec.dimension != manifest.embedding_dimension {
+            errors.push(format!(
+                "manifest.embeddingDimension: {} produces {}-dimensional vectors but the package declares {}",
+                spec.id, spec.dimension, manifest.embedding_dimension
+            ));
+        }
+    }
+}
+
+/// Model that produced a package's vectors; packages from before the field was added used the default model.
+fn package_model(manifest: &KnowledgeBaseManifest) -> &str {
+    manifest.embedding_model.as_deref().unwrap_or(EMBEDDING_MODEL_ID)
+}
+
+/// Check one raw document row (id, text, flat metadata) and convert it. Problems are appended to `errors`.
+pub fn parse_document_row(
+    i: usize,
+    raw: &serde_json::Value,
+    seen_ids: &mut HashSet<String>,
+    errors: &mut Vec<String>,
+) -> PackageDocument {
+    let id = raw.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string();
+    if id.trim().is_empty() {
+        errors.push(format!("documents[{}]: missing or empty 'id'", i));
+    } else if !seen_ids.insert(id.clone()) {
+        errors.push(format!("documents[{}]: duplicate id '{}'", i, id));
+    }
+    let text = match raw.get("text").and_then(|v| v.as_str()) {
+        Some(t) if !t.trim().is_empty() => t.to_string(),
+        _ => {
+            errors.push(format!("documents[{}] ({}): missing or empty 'text'", i, id));
+            String::new()
+        }
+    };
+    let metadata = match raw.get("metadata") {
+        None | Some(serde_json::Value::Null) => serde_json::json!({}),
+        Some(serde_json::Value::Object(map)) => {
+            // Chroma only stores flat metadata with scalar values
+            for (key, value) in map {
+                if !(value.is_string() || value.is_number() || value.is_boolean()) {
+                    errors.push(format!(
+                        "documents[{}] ({}): metadata '{}' must be a string, number or boolean",
+                        i, id, key
+                    ));
+                }
+            }
+            serde_json::Value::Object(map.clone())
+        }
+        Some(_) => {
+            errors.push(format!("documents[{}] ({}): 'metadata' must be an object", i, id));
+            serde_json::json!({})
+        }
+    };
+    PackageDocument { id, text, metadata }
+}
+
+/// Check one embedding row against the manifest dimension.
+pub fn check_embedding_row(i: usize, embedding: &[f32], dimension: usize, errors: &mut Vec<String>) {
+    if embedding.len() != dimension {
+        errors.push(format!(
+            "embeddings[{}]: expected {} values, got {}",
+            i,
+            dimension,
+            embedding.len()
+        ));
+    } else if embedding.iter().any(|v| !v.is_finite()) {
+        errors.push(format!("embeddings[{}]: contains non-finite values", i));
+    }
+}
+
+/// Turn collected row problems into one error message (first MAX_REPORTED_ERRORS listed).
+pub fn format_package_errors(errors: Vec<String>) -> String {
+    let total = errors.len();
+    let mut message = format!("Invalid knowledge base package ({} problems):\n", total);
+    message.push_str(&errors.into_iter().take(MAX_REPORTED_ERRORS).collect::<Vec<_>>().join("\n"));
+    if total > MAX_REPORTED_ERRORS {
+        message.push_str(&format!("\n... and {} more", total - MAX_REPORTED_ERRORS));
+    }
+    message
+}
+
+/// Parse a package JSON value, collecting every malformed row instead of stopping at the first.
+/// Checks: manifest shape, documentCount, embedding model (see validate_manifest), ids/text/metadata per
+/// document, and each embedding's length against embeddingDimension.
+pub fn parse_package(data: serde_json::Value, reembed: bool) -> Result<KnowledgeBasePackage, ConfidantError> {
+    let mut data = data;
+    let manifest: KnowledgeBaseManifest = serde_json::from_value(data["manifest"].take())
+        .map_err(|e| ConfidantError::validation(format!("manifest: {}", e)))?;
+    let raw_documents = match data["documents"].take() {
+        serde_json::Value::Array(arr) => arr,
+        _ => return Err(ConfidantError::validation("Missing 'documents' array")),
+    };
+    let raw_embeddings = match data["embeddings"].take() {
+        serde_json::Value::Array(arr) => arr,
+        _ => return Err(ConfidantError::validation("Missing 'embeddings' array")),
+    };
+
+    let mut errors: Vec<String> = Vec::new();
+    validate_manifest(&manifest, reembed, &mut errors);
+    if manifest.document_count != raw_documents.len() {
+        errors.push(format!(
+            "manifest.documentCount: declares {} documents but package contains {}",
+            manifest.document_count,
+            raw_documents.len()
+        ));
+    }
+    if raw_embeddings.len() != raw_documents.len() {
+        errors.push(format!(
+            "embeddings: {} rows for {} documents",
+            raw_embeddings.len(),
+            raw_documents.len()
+        ));
+    }
+
+    let mut seen_ids = HashSet::new();
+    let documents: Vec<PackageDocument> = raw_documents
+        .iter()
+        .enumerate()
+        .map(|(i, raw)| parse_document_row(i, raw, &mut seen_ids, &mut errors))
+        .collect();
+
+    let mut embeddings = Vec::with_capacity(raw_embeddings.len());
+    for (i, raw) in raw_embeddings.into_iter().enumerate() {
+        let values = raw.as_array().map(|arr| {
+            arr.iter()
+                .map(|v| v.as_f64().map(|f| f as f32))
+                .collect::<Option<Vec<f32>>>()
+        });
+        match values {
+            Some(Some(v)) => {
+                check_embedding_row(i, &v, manifest.embedding_dimension, &mut errors);
+                embeddings.push(v);
+            }
+            _ => {
+                errors.push(format!("embeddings[{}]: must be an array of numbers", i));
+                embeddings.push(Vec::new());
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(ConfidantError::validation(format_package_errors(errors)));
+    }
+
+    Ok(KnowledgeBasePackage {
+        manifest,
+        documents,
+        embeddings,
+    })
+}
+
+/// Import a validated package into a collection (default: global KB). Accepts the JSON format or the
+/// binary .ckb format (detected from the file header).
+/// The first import upserts everything; later imports of the same collection apply only the documents
+/// that were added, changed (by content hash) or removed since the recorded install.
+/// The package must carry a valid signature; unsigned packages need `allow_unsigned` (user consent).
+/// A package built with another embedding model than the active one is rejected unless `reembed` is set, in
+/// which case its text is embedded with the active model.
+#[tauri::command]
+pub async fn import_kb_package(
+    app: AppHandle,
+    path: String,
+    collection_name: Option<String>,
+    allow_unsigned: Option<bool>,
+    reembed: Option<bool>,
+) -> Result<ImportReport, ConfidantError> {
+    let collection_name = collection_name.unwrap_or_else(|| GLOBAL_KB_COLLECTION.to_string());
+    import_package_file(
+        &app,
+        Path::new(&path),
+        &collection_name,
+        allow_unsigned.unwrap_or(false),
+        reembed.unwrap_or(false),
+    )
+    .await
+}
+
+/// Verify the signature, then validate and install a package file of either format.
+pub async fn import_package_file(
+    app: &AppHandle,
+    path: &Path,
+    collection_name: &str,
+    allow_unsigned: bool,
+    reembed: bool,
+) -> Result<ImportReport, ConfidantError> {
+    if kb_binary::is_binary_package(path)? {
+        // Import from a private copy whose bytes were verified while copying
+        let copy = get_import_copy_path(app)?;
+        let signature = Some(copy_trusted_package(path, &copy, allow_unsigned)?);
+        let result = import_binary_copy(app, &copy, collection_name, signature, reembed).await;
+        let _ = fs::remove_file(&copy);
+        return result;
+    }
+
+    let (content, signature) = read_trusted_package(path, allow_unsigned)?;
+    let data: serde_json::Value = serde_json::from_slice(&content)
+        .map_err(|e| ConfidantError::validation(format!("Invalid knowledge base JSON: {}", e)))?;
+    let package = parse_package(data, reembed)?;
+
+    let rows = package.documents.into_iter().zip(package.embeddings).map(Ok);
+    install_rows(app, collection_name, package.manifest, rows, Some(signature)).await
+}
+
+async fn import_binary_copy(
+    app: &AppHandle,
+    copy: &Path,
+    collection_name: &str,
+    signature: Option<SignatureStatus>,
+    reembed: bool,
+) -> Result<ImportReport, ConfidantError> {
+    // Validate in a first streaming pass so nothing is written from a malformed package
+    kb_binary::validate_binary_package(copy, reembed)?;
+    let reader = kb_binary::BinaryPackageReader::open(copy)?;
+    let manifest = reader.manifest.clone();
+    install_rows(app, collection_name, manifest, reader, signature).await
+}
+
+/// Document counts and content hashes gathered while installing a package.
+#[derive(Default)]
+struct InstallTally {
+    hashes: BTreeMap<String, String>,
+    added: usize,
+    updated: usize,
+    unchanged: usize,
+}
+
+/// Apply validated rows to a collection in batches and record the package as installed.
+/// Rows are consumed as a stream so large packages never need to be held in memory at once. A package that
+/// passed validation with another model than the active one (import with `reembed`) has its text embedded
+/// again, and its hashes cover the vectors that were stored.
+async fn install_rows<I>(
+    app: &AppHandle,
+    collection_name: &str,
+    manifest: KnowledgeBaseManifest,
+    rows: I,
+    signature: Option<SignatureStatus>,
+) -> Result<ImportReport, ConfidantError>
+where
+    I: Iterator<Item = Result<(PackageDocument, Vec<f32>), ConfidantError>>,
+{
+    initialize_vector_store(app.clone(), collection_name.to_string(), None).await?;
+
+    let active = active_embedding_model();
+    let reembed = !same_embedding_model(package_model(&manifest), &active.id);
+    let stored_dimension = if reembed { active.dimension } else { manifest.embedding_dimension };
+    let stats = get_collection_stats_by_name(app.clone(), collection_name.to_string()).await?;
+    if let Some(dimension) = stats["embedding_dimension"].as_u64() {
+        if dimension as usize != stored_dimension {
+            return Err(ConfidantError::validation(format!(
+                "manifest.embeddingDimension: package has {} but collection {} stores {}-dimensional vectors",
+                stored_dimension, collection_name, dimension
+            )));
+        }
+    }
+
+    let mut installed = load_installed_packages(app)?;
+    let previous = installed.get(collection_name).cloned();
+
+    let mut tally = InstallTally::default();
+    let mut pending: Vec<(PackageDocument, Vec<f32>)> = Vec::with_capacity(IMPORT_BATCH_SIZE);
+    for row in rows {
+        pending.push(row?);
+        if pending.len() >= IMPORT_BATCH_SIZE {
+            let batch = std::mem::take(&mut pending);
+            install_batch(app, collection_name, batch, reembed, previous.as_ref(), &mut tally).await?;
+        }
+    }
+    if !pending.is_empty() {
+        install_batch(app, collection_name, pending, reembed, previous.as_ref(), &mut tally).await?;
+    }
+    let InstallTally { hashes, added, updated, unchanged } = tally;
+
+    let removed_ids: Vec<String> = previous
+        .as_ref()
+        .map(|p| p.documents.keys().filter(|id| !hashes.contains_key(*id)).cloned().collect())
+        .unwrap_or_default();
+    let removed = if removed_ids.is_empty() {
+        0
+    } else {
+        delete_documents(app.clone(), collection_name.to_string(), removed_ids).await?.deleted as usize
+    };
+
+    #[cfg(debug_assertions)]
+    eprintln!(
+        "[KB Package] Imported {} {} into {}: {} added, {} updated, {} removed, {} unchanged",
+        manifest.name, manifest.version, collection_name, added, updated, removed, unchanged
+    );
+
+    installed.insert(
+        collection_name.to_string(),
+        InstalledPackage {
+            name: manifest.name.clone(),
+            version: manifest.version.clone(),
+            installed_at: chrono::Utc::now().to_rfc3339(),
+            embedding_model: if reembed { Some(active.id.clone()) } else { manifest.embedding_model.clone() },
+            signature,
+            documents: hashes,
+        },
+    );
+    save_installed_packages(app, &installed)?;
+
+    Ok(ImportReport {
+        collection: collection_name.to_string(),
+        name: manifest.name,
+        version: manifest.version,
+        previous_version: previous.map(|p| p.version),
+        added,
+        updated,
+        removed,
+        unchanged,
+        signature,
+        reembedded: reembed,
+    })
+}
+
+/// Install one batch of rows: embed their text with the active model when `reembed` is set (the embedding cache
+/// makes a repeated import cheap), hash what will be stored, and upsert the rows that differ from `previous`.
+async fn install_batch(
+    app: &AppHandle,
+    collection_name: &str,
+    mut rows: Vec<(PackageDocument, Vec<f32>)>,
+    reembed: bool,
+    previous: Option<&InstalledPackage>,
+    tally: &mut InstallTally,
+) -> Result<(), ConfidantError> {
+    if reembed {
+        let texts = rows.iter().map(|(doc, _)| doc.text.clone()).collect();
+        let embeddings = embed_passages(app.clone(), texts).await?;
+        for ((_, embedding), new) in rows.iter_mut().zip(embeddings) {
+            *embedding = new;
+        }
+    }
+
+    let mut batch: Vec<VectorDocument> = Vec::with_capacity(rows.len());
+    for (doc, embedding) in rows {
+        let hash = content_hash(&doc, &embedding);
+        let old_hash = previous.and_then(|p| p.documents.get(&doc.id));
+        let is_unchanged = old_hash == Some(&hash);
+        match old_hash {
+            Some(_) if is_unchanged => tally.unchanged += 1,
+            Some(_) => tally.updated += 1,
+            None => tally.added += 1,
+        }
+        tally.hashes.insert(doc.id.clone(), hash);
+        if !is_unchanged {
+            batch.push(VectorDocument {
+                id: doc.id,
+                text: doc.text,
+                embedding,
+                metadata: doc.metadata,
+            });
+        }
+    }
+    if !batch.is_empty() {
+        upsert_documents(app.clone(), collection_name.to_string(), batch).await?;
+    }
+    Ok(())
+}
+
+/// Convert a package between the JSON and binary (.ckb) formats. The input format is detected from the
+/// file; the output is the other format. `encoding` (f32, f16 or int8) applies when writing binary.
+/// The output is unsigned; re-sign it with scripts/sign_package.py before shipping.
+#[tauri::command]
+pub async fn convert_kb_package(
+    input_path: String,
+    output_path: String,
+    encoding: Option<kb_binary::EmbeddingEncoding>,
+) -> Result<KnowledgeBaseManifest, ConfidantError> {
+    let input = Path::new(&input_path);
+    let output = Path::new(&output_path);
+    if kb_binary::is_binary_package(input)? {
+        let package = kb_binary::read_binary_package(input)?;
+        write_package_json(&package, output)?;
+        Ok(package.manifest)
+    } else {
+        let content = fs::read_to_string(input)
+            .map_err(|e| ConfidantError::io(format!("Failed to read knowledge base package: {}", e)))?;
+        let data: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|e| ConfidantError::validation(format!("Invalid knowledge base JSON: {}", e)))?;
+        let package = parse_package(data, false)?;
+        kb_binary::write_binary_package(&package, output, encoding.unwrap_or_default())?;
+        Ok(package.manifest)
+    }
+}
+
+/// Get the package recorded as installed in a collection, if any.
+#[tauri::command]
+pub async fn get_installed_kb_package(
+    app: AppHandle,
+    collection_name: String,
+) -> Result<Option<InstalledPackage>, ConfidantError> {
+    Ok(load_installed_packages(&app)?.remove(&collection_name))
+}
@@ -12,6 +12,7 @@ use std::path::{Path, PathBuf};
 use std::thread;
 use tauri::{AppHandle, Emitter, Manager};
 
+use crate::error::ConfidantError;
 use crate::python_bundle;
 
 // Global state for the LLM engine
@@ -64,28 +65,28 @@ pub struct LLMResponse {
 }
 
 /// Get path to Python helper script
-fn get_llama_helper_path() -> Result<std::path::PathBuf, String> {
+fn get_llama_helper_path() -> Result<std::path::PathBuf, ConfidantError> {
     let exe_path = std::env::current_exe()
-        .map_err(|e| format!("Failed to get executable path: {}", e))?;
+        .map_err(|e| ConfidantError::io(format!("Failed to get executable path: {}", e)))?;
     
     // Go up from target/debug/dant-desktop to src-tauri/scripts/
     let script_path = exe_path
         .parent() // target/debug/
         .and_then(|p| p.parent()) // target/
         .and_then(|p| p.parent()) // src-tauri/
-        .ok_or("Failed to find script directory")?
+        .ok_or_else(|| ConfidantError::not_found("Failed to find script directory"))?
         .join("scripts")
         .join("llama_helper.py");
     
     if !script_path.exists() {
         // Fallback: try relative to current_dir
         let current_dir = std::env::current_dir()
-            .map_err(|e| format!("Failed to get current directory: {}", e))?;
+            .map_err(|e| ConfidantError::io(format!("Failed to get current directory: {}", e)))?;
         let fallback_path = current_dir.join("src-tauri").join("scripts").join("llama_helper.py");
         if fallback_path.exists() {
             return Ok(fallback_path);
         }
-        return Err(format!("Helper script not found at: {:?}", script_path));
+        return Err(ConfidantError::not_found(format!("Helper script not found at: {:?}", script_path)));
     }
     
     Ok(script_path)
@@ -93,7 +94,7 @@ fn get_llama_helper_path() -> Result<std::path::PathBuf, String> {
 
 /// Resolve Python executable and llama_helper.py path.
 /// Prefer venv when present; then bundled; then system Python.
-fn resolve_python_and_script(bundled: Option<(PathBuf, PathBuf)>) -> Result<(String, PathBuf), String> {
+fn resolve_python_and_script(bundled: Option<(PathBuf, PathBuf)>) -> Result<(String, PathBuf), ConfidantError> {
     if let Some(venv_py) = crate::python_bundle::find_venv_python() {
         if Command::new(&venv_py).arg("--version").output().is_ok() {
             let dev_script = get_llama_helper_path()?;
@@ -113,7 +114,7 @@ fn resolve_python_and_script(bundled: Option<(PathBuf, PathBuf)>) -> Result<(Str
         } else if Command::new("python").arg("--version").output().is_ok() {
             "python".to_string()
         } else {
-            return Err("Python not found. Please install Python 3.".to_string());
+            return Err(ConfidantError::python_missing("Python not found. Please install Python 3."));
         };
         return Ok((python_cmd, script_path));
     }
@@ -133,7 +134,7 @@ fn resolve_python_and_script(bundled: Option<(PathBuf, PathBuf)>) -> Result<(Str
     } else if Command::new("python").arg("--version").output().is_ok() {
         "python".to_string()
     } else {
-        return Err("Python not found. Please install Python 3.".to_string());
+        return Err(ConfidantError::python_missing("Python not found. Please install Python 3."));
     };
     Ok((python_cmd, script_path))
 }
@@ -145,7 +146,7 @@ fn call_llama_helper(
     command: &str,
     args: &[&str],
     stdin_data: Option<&str>,
-) -> Result<String, String> {
+) -> Result<String, ConfidantError> {
     let (python_cmd, script_path) = resolve_python_and_script(bundled)?;
 
     let mut cmd = Command::new(&python_cmd);
@@ -160,18 +161,18 @@ fn call_llama_helper(
     let mut child = cmd.stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped())
         .spawn()
-        .map_err(|e| format!("Failed to spawn Python process: {}", e))?;
+        .map_err(|e| ConfidantError::helper(format!("Failed to spawn Python process: {}", e)))?;
     
     // Write stdin data if provided
     if let Some(ref data) = stdin_data {
         if let Some(mut stdin) = child.stdin.take() {
             stdin.write_all(data.as_bytes())
-                .map_err(|e| format!("Failed to write to Python stdin: {}", e))?;
+                .map_err(|e| ConfidantError::helper(format!("Failed to write to Python stdin: {}", e)))?;
         }
     }
     
     let output = child.wait_with_output()
-        .map_err(|e| format!("Failed to wait for Python process: {}", e))?;
+        .map_err(|e| ConfidantError::helper(format!("Failed to wait for Python process: {}", e)))?;
     
     #[cfg(debug_assertions)]
     if !output.stderr.is_empty() {
@@ -182,16 +183,22 @@ fn call_llama_helper(
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         let stdout = String::from_utf8_lossy(&output.stdout);
-        return Err(format!("Python LLM script failed (exit code: {}): stderr: {}, stdout: {}", 
-            output.status.code().unwrap_or(-1), stderr, stdout));
+        let error = ConfidantError::from_helper_stderr(
+            format!("Python LLM script failed (exit code: {})", output.status.code().unwrap_or(-1)),
+            &stderr,
+        );
+        return Err(match error.details() {
+            None if !stdout.trim().is_empty() => error.with_details(stdout.trim()),
+            _ => error,
+        });
     }
     
     let stdout = String::from_utf8(output.stdout)
-        .map_err(|e| format!("Failed to parse Python output: {}", e))?;
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse Python output: {}", e)))?;
     
     if stdout.trim().is_empty() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Python LLM script returned empty output. stderr: {}", stderr));
+        return Err(ConfidantError::helper("Python LLM script returned empty output.").with_details(stderr));
     }
     
     Ok(stdout)
@@ -201,7 +208,7 @@ fn call_llama_helper(
 async fn initialize_model_internal(
     mut model_path: String,
     bundled: Option<(PathBuf, PathBuf)>,
-) -> Result<(), String> {
+) -> Result<(), ConfidantError> {
     // Try to resolve the actual file path (handles case-insensitive matching)
     loop {
         #[cfg(debug_assertions)]
@@ -219,12 +226,12 @@ async fn initialize_model_internal(
                 // Files smaller than this are likely incomplete downloads
                 if file_size < 1_500_000_000 {
                     println!("[LLM] Warning: Model file is too small ({} bytes, {:.2} GB). Expected at least 1.5GB.", file_size, size_gb);
-                    return Err(format!(
+                    return Err(ConfidantError::validation(format!(
                         "Model file appears to be too small ({} bytes, {:.2} GB). It may be corrupted or incomplete.\n\
                         Expected size: ~2GB for Q4_0, ~2.5GB for Q4_K_M\n\
                         The file might be a partial download. Please check the file or use 'Use Existing Model' to select a different file.",
                         file_size, size_gb
-                    ));
+                    )));
                 }
                 #[cfg(debug_assertions)]
                 eprintln!("[LLM] Model file size: {} bytes ({:.2} GB)", file_size, size_gb);
@@ -233,10 +240,10 @@ async fn initialize_model_internal(
         }
         
         // Try to find the file with different case (case-insensitive search)
-        let parent = model_file.parent().ok_or("Invalid model path")?;
+        let parent = model_file.parent().ok_or_else(|| ConfidantError::validation("Invalid model path"))?;
         let expected_name = model_file.file_name()
             .and_then(|n| n.to_str())
-            .ok_or("Invalid filename")?;
+            .ok_or_else(|| ConfidantError::validation("Invalid filename"))?;
         
         // Search for files with similar names (case-insensitive)
         let mut found_alternative = None;
@@ -265,24 +272,24 @@ async fn initialize_model_internal(
             continue; // Try again with the found path
         }
         
-        return Err(format!(
+        return Err(ConfidantError::not_found(format!(
             "Model file not found: {}\n\n\
             Please ensure the model file exists at this path.\n\
             You can use the 'Use Existing Model' option if you have a model file elsewhere.\n\
             Recommended: Llama-3.2-3B-Instruct (Q4_0 or Q4_K_M quantization)\n\
             Download from: https://huggingface.co/bartowski/Llama-3.2-3B-Instruct-GGUF",
             model_path
-        ));
+        )));
     }
 
     // Call Python helper to load model
     let result_json = call_llama_helper(bundled, "load", &[&model_path], None)?;
     let result: serde_json::Value = serde_json::from_str(&result_json)
-        .map_err(|e| format!("Failed to parse Python response: {}", e))?;
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?;
     
     if result["status"].as_str() != Some("success") {
         let error_msg = result["message"].as_str().unwrap_or("Unknown error");
-        return Err(format!("Failed to load model: {}", error_msg));
+        return Err(ConfidantError::helper(format!("Failed to load model: {}", error_msg)));
     }
 
     println!("[LLM] Model loaded successfully");
@@ -291,10 +298,10 @@ async fn initialize_model_internal(
 
 /// Initialize LLM model from file path
 #[tauri::command]
-pub async fn initialize_model(app: AppHandle, model_path: String) -> Result<(), String> {
+pub async fn initialize_model(app: AppHandle, model_path: String) -> Result<(), ConfidantError> {
     // Check state first (lock and release immediately)
     {
-        let state = LLM_STATE.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+        let state = LLM_STATE.lock().map_err(|e| ConfidantError::internal(format!("Failed to lock state: {}", e)))?;
         if state.is_initialized && state.model_path.as_ref() == Some(&model_path) {
             #[cfg(debug_assertions)]
             eprintln!("[LLM] Model already initialized");
@@ -309,7 +316,7 @@ pub async fn initialize_model(app: AppHandle, model_path: String) -> Result<(),
     // Update state after successful initialization
     let path_for_worker = model_path.clone();
     {
-        let mut state = LLM_STATE.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+        let mut state = LLM_STATE.lock().map_err(|e| ConfidantError::internal(format!("Failed to lock state: {}", e)))?;
         state.model_path = Some(model_path);
         state.is_initialized = true;
     }
@@ -330,7 +337,7 @@ pub async fn initialize_model(app: AppHandle, model_path: String) -> Result<(),
 }
 
 /// Start a long-lived Python process with the model loaded. Called after initialize_model (background).
-fn start_llm_worker(app: &AppHandle, model_path: &str) -> Result<(), String> {
+fn start_llm_worker(app: &AppHandle, model_path: &str) -> Result<(), ConfidantError> {
     let bundled = python_bundle::resolve_bundled_python(app);
     let (python_cmd, script_path) = resolve_python_and_script(bundled)?;
 
@@ -342,10 +349,10 @@ fn start_llm_worker(app: &AppHandle, model_path: &str) -> Result<(), String> {
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .spawn()
-        .map_err(|e| format!("Failed to spawn Python worker: {}", e))?;
+        .map_err(|e| ConfidantError::helper(format!("Failed to spawn Python worker: {}", e)))?;
 
     // Drain stderr in a background thread so the worker never blocks on pipe backpressure; log for debugging.
-    let stderr = child.stderr.take().ok_or("No stderr from Python worker")?;
+    let stderr = child.stderr.take().ok_or_else(|| ConfidantError::helper("No stderr from Python worker"))?;
     std::thread::spawn(move || {
         let mut r = BufReader::new(stderr);
         let mut line = String::new();
@@ -355,7 +362,7 @@ fn start_llm_worker(app: &AppHandle, model_path: &str) -> Result<(), String> {
         }
     });
 
-    let stdout = child.stdout.take().ok_or("No stdout from Python worker")?;
+    let stdout = child.stdout.take().ok_or_else(|| ConfidantError::helper("No stdout from Python worker"))?;
     let mut reader = BufReader::new(stdout);
     // Read lines until we see {"ready": true} (llama_cpp may write other lines to stdout; skip them).
     let mut line = String::new();
@@ -364,9 +371,9 @@ fn start_llm_worker(app: &AppHandle, model_path: &str) -> Result<(), String> {
         line.clear();
         let n = reader
             .read_line(&mut line)
-            .map_err(|e| format!("Failed to read from worker: {}", e))?;
+            .map_err(|e| ConfidantError::helper(format!("Failed to read from worker: {}", e)))?;
         if n == 0 {
-            return Err("Worker stdout closed before ready.".to_string());
+            return Err(ConfidantError::helper("Worker stdout closed before ready."));
         }
         let trimmed = line.trim();
         if trimmed.is_empty() {
@@ -378,15 +385,15 @@ fn start_llm_worker(app: &AppHandle, model_path: &str) -> Result<(), String> {
                 break;
             }
             if let Some(err) = v.get("error").and_then(|e| e.as_str()) {
-                return Err(format!("Worker reported error: {}", err));
+                return Err(ConfidantError::helper(format!("Worker reported error: {}", err)));
             }
         }
     }
     if !ready {
-        return Err("Worker did not send ready in time.".to_string());
+        return Err(ConfidantError::helper("Worker did not send ready in time."));
     }
 
-    let stdin = child.stdin.take().ok_or("No stdin from Python worker")?;
+    let stdin = child.stdin.take().ok_or_else(|| ConfidantError::helper("No stdin from Python worker"))?;
     let worker = LlmWorker {
         _child: child,
         stdin,
@@ -394,7 +401,7 @@ fn start_llm_worker(app: &AppHandle, model_path: &str) -> Result<(), String> {
         model_path: model_path.to_string(),
     };
 
-    let mut guard = LLM_WORKER.lock().map_err(|e| format!("Lock worker: {}", e))?;
+    let mut guard = LLM_WORKER.lock().map_err(|e| ConfidantError::internal(format!("Lock worker: {}", e)))?;
     *guard = Some(worker);
     Ok(())
 }
@@ -406,27 +413,27 @@ fn run_stream_via_worker(
     prompt: &str,
     config_json: &str,
     mut worker: LlmWorker,
-) -> Result<LlmWorker, String> {
+) -> Result<LlmWorker, ConfidantError> {
     let config: serde_json::Value =
-        serde_json::from_str(config_json).map_err(|e| format!("Config JSON: {}", e))?;
+        serde_json::from_str(config_json).map_err(|e| ConfidantError::internal(format!("Config JSON: {}", e)))?;
     let req = serde_json::json!({
         "prompt": prompt,
         "temperature": config.get("temperature").and_then(|v| v.as_f64()).unwrap_or(0.7),
         "top_p": config.get("top_p").and_then(|v| v.as_f64()).unwrap_or(0.9),
         "max_tokens": config.get("max_tokens").and_then(|v| v.as_u64()).unwrap_or(512) as u32,
     });
-    let line = serde_json::to_string(&req).map_err(|e| format!("Serialize request: {}", e))?;
+    let line = serde_json::to_string(&req).map_err(|e| ConfidantError::internal(format!("Serialize request: {}", e)))?;
     worker
         .stdin
         .write_all(format!("{}\n", line).as_bytes())
-        .map_err(|e| format!("Write to worker: {}", e))?;
-    worker.stdin.flush().map_err(|e| format!("Flush worker: {}", e))?;
+        .map_err(|e| ConfidantError::helper(format!("Write to worker: {}", e)))?;
+    worker.stdin.flush().map_err(|e| ConfidantError::helper(format!("Flush worker: {}", e)))?;
 
     let mut stream_done = false;
     for line in worker.stdout.by_ref().lines() {
-        let line = line.map_err(|e| format!("Read from worker: {}", e))?;
+        let line = line.map_err(|e| ConfidantError::helper(format!("Read from worker: {}", e)))?;
         let v: serde_json::Value =
-            serde_json::from_str(&line).map_err(|e| format!("Worker JSON: {}", e))?;
+            serde_json::from_str(&line).map_err(|e| ConfidantError::helper(format!("Worker JSON: {}", e)))?;
 
         if let Some(text) = v.get("text").and_then(|t| t.as_str()) {
             let _ = app.emit(
@@ -444,16 +451,14 @@ fn run_stream_via_worker(
             break;
         }
         if let Some(err) = v.get("error").and_then(|e| e.as_str()) {
-            let _ = app.emit(
-                "llm-stream-error",
-                serde_json::json!({ "streamId": stream_id, "error": err }),
-            );
-            return Err(err.to_string());
+            let error = ConfidantError::helper(err);
+            emit_stream_error(app, stream_id, &error);
+            return Err(error);
         }
     }
 
     if !stream_done {
-        return Err("Worker stream ended without done.".to_string());
+        return Err(ConfidantError::helper("Worker stream ended without done."));
     }
     Ok(worker)
 }
@@ -464,39 +469,39 @@ pub async fn generate_text(
     app: AppHandle,
     prompt: String,
     config: LLMConfig,
-) -> Result<LLMResponse, String> {
-    let state = LLM_STATE.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+) -> Result<LLMResponse, ConfidantError> {
+    let state = LLM_STATE.lock().map_err(|e| ConfidantError::internal(format!("Failed to lock state: {}", e)))?;
     
     if !state.is_initialized {
-        return Err("Model not initialized. Call initialize_model first.".to_string());
+        return Err(ConfidantError::not_initialized("Model not initialized. Call initialize_model first."));
     }
 
     #[cfg(debug_assertions)]
     eprintln!("[LLM] Generating (prompt len: {} chars)", prompt.len());
 
     let model_path = state.model_path.as_ref()
-        .ok_or("Model path not set")?;
+        .ok_or_else(|| ConfidantError::not_initialized("Model path not set"))?;
 
     // Prepare config JSON
     let config_json = serde_json::to_string(&serde_json::json!({
         "temperature": config.temperature,
         "top_p": config.top_p,
         "max_tokens": config.max_tokens
-    })).map_err(|e| format!("Failed to serialize config: {}", e))?;
+    })).map_err(|e| ConfidantError::internal(format!("Failed to serialize config: {}", e)))?;
     
     let bundled = python_bundle::resolve_bundled_python(&app);
     // Call Python helper to generate text (pass model_path as first arg)
     let result_json = call_llama_helper(bundled, "generate", &[model_path, &config_json], Some(&prompt))?;
     let result: serde_json::Value = serde_json::from_str(&result_json)
-        .map_err(|e| format!("Failed to parse Python response: {}", e))?;
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?;
     
     if result["status"].as_str() != Some("success") {
         let error_msg = result["message"].as_str().unwrap_or("Unknown error");
-        return Err(format!("Failed to generate text: {}", error_msg));
+        return Err(ConfidantError::helper(format!("Failed to generate text: {}", error_msg)));
     }
     
     let text = result["text"].as_str()
-        .ok_or("Invalid response format: missing text")?
+        .ok_or_else(|| ConfidantError::helper("Invalid response format: missing text"))?
         .to_string();
     
     let finish_reason = result["finish_reason"].as_str().map(|s| s.to_string());
@@ -520,16 +525,16 @@ pub fn generate_text_stream(
     stream_id: String,
     prompt: String,
     config: LLMConfig,
-) -> Result<(), String> {
+) -> Result<(), ConfidantError> {
     let model_path = {
-        let state = LLM_STATE.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+        let state = LLM_STATE.lock().map_err(|e| ConfidantError::internal(format!("Failed to lock state: {}", e)))?;
         if !state.is_initialized {
-            return Err("Model not initialized. Call initialize_model first.".to_string());
+            return Err(ConfidantError::not_initialized("Model not initialized. Call initialize_model first."));
         }
         state
             .model_path
             .as_ref()
-            .ok_or("Model path not set")?
+            .ok_or_else(|| ConfidantError::not_initialized("Model path not set"))?
             .clone()
     };
 
@@ -538,7 +543,7 @@ pub fn generate_text_stream(
         "top_p": config.top_p,
         "max_tokens": config.max_tokens
     }))
-    .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    .map_err(|e| ConfidantError::internal(format!("Failed to serialize config: {}", e)))?;
 
     #[cfg(debug_assertions)]
     eprintln!("[LLM] Starting stream {} (prompt len: {} chars)", stream_id, prompt.len());
@@ -558,10 +563,7 @@ pub fn generate_text_stream(
                         true
                     }
                     Err(e) => {
-                        let _ = app.emit(
-                            "llm-stream-error",
-                            serde_json::json!({ "streamId": stream_id, "error": e }),
-                        );
+                        emit_stream_error(&app, &stream_id, &e);
                         false
                     }
                 }
@@ -577,10 +579,7 @@ pub fn generate_text_stream(
         };
         if !used_worker {
             if let Err(e) = run_stream_process(&app, &stream_id, &prompt, &model_path, &config_json) {
-                let _ = app.emit(
-                    "llm-stream-error",
-                    serde_json::json!({ "streamId": stream_id, "error": e }),
-                );
+                emit_stream_error(&app, &stream_id, &e);
             }
         }
     });
@@ -588,13 +587,27 @@ pub fn generate_text_stream(
     Ok(())
 }
 
+/// Emit `llm-stream-error` for a stream. `code` and `details` mirror ConfidantError so listeners need not
+/// parse the message.
+fn emit_stream_error(app: &AppHandle, stream_id: &str, error: &ConfidantError) {
+    let _ = app.emit(
+        "llm-stream-error",
+        serde_json::json!({
+            "streamId": stream_id,
+            "error": error.to_string(),
+            "code": error.code(),
+            "details": error.details(),
+        }),
+    );
+}
+
 fn run_stream_process(
     app: &AppHandle,
     stream_id: &str,
     prompt: &str,
     model_path: &str,
     config_json: &str,
-) -> Result<(), String> {
+) -> Result<(), ConfidantError> {
     let bundled = python_bundle::resolve_bundled_python(app);
     let (python_cmd, script_path) = resolve_python_and_script(bundled)?;
 
@@ -607,20 +620,20 @@ fn run_stream_process(
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .spawn()
-        .map_err(|e| format!("Failed to spawn Python process: {}", e))?;
+        .map_err(|e| ConfidantError::helper(format!("Failed to spawn Python process: {}", e)))?;
 
     if let Some(mut stdin) = child.stdin.take() {
         stdin
             .write_all(prompt.as_bytes())
-            .map_err(|e| format!("Failed to write prompt to Python stdin: {}", e))?;
+            .map_err(|e| ConfidantError::helper(format!("Failed to write prompt to Python stdin: {}", e)))?;
     }
 
-    let reader = BufReader::new(child.stdout.take().ok_or("No stdout from Python process")?);
+    let reader = BufReader::new(child.stdout.take().ok_or_else(|| ConfidantError::helper("No stdout from Python process"))?);
     let mut stream_done = false;
     for line in reader.lines() {
-        let line = line.map_err(|e| format!("Failed to read line from Python: {}", e))?;
+        let line = line.map_err(|e| ConfidantError::helper(format!("Failed to read line from Python: {}", e)))?;
         let v: serde_json::Value =
-            serde_json::from_str(&line).map_err(|e| format!("Invalid JSON from Python: {}", e))?;
+            serde_json::from_str(&line).map_err(|e| ConfidantError::helper(format!("Invalid JSON from Python: {}", e)))?;
 
         if let Some(text) = v.get("text").and_then(|t| t.as_str()) {
             let _ = app.emit(
@@ -638,65 +651,56 @@ fn run_stream_process(
             break;
         }
         if let Some(err) = v.get("error").and_then(|e| e.as_str()) {
-            let _ = app.emit(
-                "llm-stream-error",
-                serde_json::json!({ "streamId": stream_id, "error": err }),
-            );
+            emit_stream_error(app, stream_id, &ConfidantError::helper(err));
             stream_done = true;
             break;
         }
     }
 
-    let status = child.wait().map_err(|e| format!("Failed to wait for Python process: {}", e))?;
+    let status = child.wait().map_err(|e| ConfidantError::helper(format!("Failed to wait for Python process: {}", e)))?;
     if !stream_done {
-        let mut msg = if status.success() {
-            "Stream ended without a response. The model may have exited unexpectedly.".to_string()
-        } else {
-            "Python process failed.".to_string()
-        };
+        let mut stderr_text = String::new();
         if let Some(mut stderr) = child.stderr {
-            let mut err = String::new();
-            if stderr.read_to_string(&mut err).is_ok() && !err.trim().is_empty() {
-                let last_line = err.lines().filter(|l| !l.trim().is_empty()).last().unwrap_or("");
-                if last_line.contains("llama_cpp") || last_line.contains("ModuleNotFoundError") {
-                    msg = format!("Python: {} Install with: pip install llama-cpp-python", last_line.trim());
-                } else {
-                    msg = format!("{} Stderr: {}", msg, last_line.trim());
-                }
-            }
-        }
-        if msg == "Python process failed." {
-            msg = "Python process failed. Check that the model is loaded and llama-cpp-python is installed (pip install llama-cpp-python).".to_string();
+            let _ = stderr.read_to_string(&mut stderr_text);
         }
-        let _ = app.emit(
-            "llm-stream-error",
-            serde_json::json!({ "streamId": stream_id, "error": msg }),
-        );
+        let message = if status.success() {
+            "Stream ended without a response. The model may have exited unexpectedly."
+        } else {
+            "Python process failed. Check that the model is loaded and llama-cpp-python is installed (pip install llama-cpp-python)."
+        };
+        let error = match ConfidantError::from_helper_stderr(message, &stderr_text) {
+            ConfidantError::PythonMissing { details, .. } => ConfidantError::PythonMissing {
+                message: "llama-cpp-python is not installed. Install with: pip install llama-cpp-python".to_string(),
+                details,
+            },
+            error => error,
+        };
+        emit_stream_error(app, stream_id, &error);
     }
     Ok(())
 }
 
 /// Check if model is loaded
 #[tauri::command]
-pub async fn is_model_loaded() -> Result<bool, String> {
-    let state = LLM_STATE.lock().map_err(|e| format!("Failed to lock state: {}", e))?;
+pub async fn is_model_loaded() -> Result<bool, ConfidantError> {
+    let state = LLM_STATE.lock().map_err(|e| ConfidantError::internal(format!("Failed to lock state: {}", e)))?;
     Ok(state.is_initialized)
 }
 
 /// Get app data directory for storing models. Uses Tauri's writable app data dir
 /// (e.g. ~/Library/Application Support/com.confidant) so the packaged app can write when run from DMG.
 #[tauri::command]
-pub async fn get_app_data_dir(app: AppHandle) -> Result<String, String> {
+pub async fn get_app_data_dir(app: AppHandle) -> Result<String, ConfidantError> {
     let base_dir = app
         .path()
         .app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+        .map_err(|e| ConfidantError::io(format!("Failed to get app data dir: {}", e)))?;
     let data_dir = base_dir.join("data");
     std::fs::create_dir_all(&data_dir)
-        .map_err(|e| format!("Failed to create data directory: {}", e))?;
+        .map_err(|e| ConfidantError::io(format!("Failed to create data directory: {}", e)))?;
     let models_dir = data_dir.join("models");
     std::fs::create_dir_all(&models_dir)
-        .map_err(|e| format!("Failed to create models directory: {}", e))?;
+        .map_err(|e| ConfidantError::io(format!("Failed to create models directory: {}", e)))?;
     #[cfg(debug_assertions)]
     eprintln!("[App Data Dir] Using data directory: {}", data_dir.display());
     Ok(data_dir.to_string_lossy().to_string())
@@ -704,21 +708,21 @@ pub async fn get_app_data_dir(app: AppHandle) -> Result<String, String> {
 
 /// Check if model file exists at path
 #[tauri::command]
-pub async fn check_model_exists(path: String) -> Result<bool, String> {
+pub async fn check_model_exists(path: String) -> Result<bool, ConfidantError> {
     Ok(Path::new(&path).exists())
 }
 
 /// Find existing model files in common directories
 #[tauri::command]
-pub async fn find_existing_models() -> Result<Vec<String>, String> {
+pub async fn find_existing_models() -> Result<Vec<String>, ConfidantError> {
     let mut found_models = Vec::new();
     
     // Helper to find project root
     // We want to find the actual project root (where desktop/ and data/ both exist)
     // not just any directory with data/ (like desktop/src-tauri/data/)
-    let find_project_root = || -> Result<PathBuf, String> {
+    let find_project_root = || -> Result<PathBuf, ConfidantError> {
         let mut current = std::env::current_dir()
-            .map_err(|e| format!("Failed to get current directory: {}", e))?;
+            .map_err(|e| ConfidantError::io(format!("Failed to get current directory: {}", e)))?;
         
         // Collect all directories with data/ subdirectory
         let mut candidates = Vec::new();
@@ -755,7 +759,7 @@ pub async fn find_existing_models() -> Result<Vec<String>, String> {
         } else {
             // Fallback: use current directory
             Ok(std::env::current_dir()
-                .map_err(|e| format!("Failed to get current directory: {}", e))?)
+                .map_err(|e| ConfidantError::io(format!("Failed to get current directory: {}", e)))?)
         }
     };
     
@@ -838,14 +842,14 @@ pub async fn find_existing_models() -> Result<Vec<String>, String> {
 
 /// Download model from URL
 #[tauri::command]
-pub async fn download_model(url: String, output_path: String) -> Result<(), String> {
+pub async fn download_model(url: String, output_path: String) -> Result<(), ConfidantError> {
     #[cfg(debug_assertions)]
     eprintln!("[Download] Starting: {} -> {}", url, output_path);
     
     // Create parent directory if it doesn't exist
     if let Some(parent) = Path::new(&output_path).parent() {
         fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create directory: {}", e))?;
+            .map_err(|e| ConfidantError::io(format!("Failed to create directory: {}", e)))?;
     }
     
     // Check if file already exists
@@ -858,15 +862,15 @@ pub async fn download_model(url: String, output_path: String) -> Result<(), Stri
     // Download file
     let response = reqwest::get(&url)
         .await
-        .map_err(|e| format!("Failed to start download: {}", e))?;
+        .map_err(|e| ConfidantError::io(format!("Failed to start download: {}", e)))?;
     
     if !response.status().is_success() {
-        return Err(format!("Download failed with status: {}", response.status()));
+        return Err(ConfidantError::io(format!("Download failed with status: {}", response.status())));
     }
     
     let total_size = response.content_length();
     let mut file = fs::File::create(&output_path)
-        .map_err(|e| format!("Failed to create file: {}", e))?;
+        .map_err(|e| ConfidantError::io(format!("Failed to create file: {}", e)))?;
     
     let mut stream = response.bytes_stream();
     let mut downloaded: u64 = 0;
@@ -875,9 +879,9 @@ pub async fn download_model(url: String, output_path: String) -> Result<(), Stri
     use std::io::Write as IoWrite;
     
     while let Some(item) = stream.next().await {
-        let chunk = item.map_err(|e| format!("Download error: {}", e))?;
+        let chunk = item.map_err(|e| ConfidantError::io(format!("Download error: {}", e)))?;
         file.write_all(&chunk)
-            .map_err(|e| format!("Failed to write to file: {}", e))?;
+            .map_err(|e| ConfidantError::io(format!("Failed to write to file: {}", e)))?;
         downloaded += chunk.len() as u64;
         
         if let Some(total) = total_size {
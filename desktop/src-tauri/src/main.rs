@@ -4,26 +4,58 @@
 mod llm;
 mod vector_store;
 mod embeddings;
+mod embedding_cache;
 mod user_management;
 mod cache;
 mod bundled_defaults;
 mod python_bundle;
+mod collection_settings;
+mod ranking;
+mod citations;
+mod kb_package;
+mod kb_binary;
+mod package_signing;
+mod ingest;
+mod watched_folders;
+mod reembed;
+mod store_maintenance;
+mod collection_gc;
+mod error;
+mod phonebook;
+#[cfg(feature = "native-embeddings")]
+mod native_embeddings;
 
 use llm::{initialize_model, generate_text, generate_text_stream, is_model_loaded, download_model, check_model_exists, get_app_data_dir, find_existing_models};
 use vector_store::{
     initialize_vector_store, add_documents, add_documents_to_collection,
-    search_similar, search_collection, get_collection_stats, get_collection_stats_by_name,
-    get_documents_by_filter,
+    search_similar, search_collection, search_many, get_collection_stats, get_collection_stats_by_name,
+    get_documents_by_filter, upsert_documents, delete_documents, delete_documents_by_filter,
+    get_documents, get_collection_embedding_info,
     initialize_user_vector_store, delete_user_knowledge_base,
 };
-use embeddings::{generate_embedding, generate_embeddings_batch};
+use embeddings::{
+    generate_embedding, generate_embeddings_batch, embed_query, embed_passages, list_embedding_models,
+    set_active_embedding_model,
+};
 use user_management::{
     get_users, create_user, verify_password, get_current_user, set_current_user,
     clear_current_user_on_exit,
     save_user_chat, load_user_chat, delete_user_chat, delete_user, get_user_language, set_user_language,
 };
 use cache::{read_cache_file, write_cache_file};
-use bundled_defaults::ensure_bundled_defaults_initialized;
+use collection_settings::{get_collection_settings, set_collection_settings};
+use kb_package::{export_collection, import_kb_package, get_installed_kb_package, convert_kb_package};
+use package_signing::get_package_signature_status;
+use ingest::ingest_files;
+use watched_folders::{set_watched_folder, remove_watched_folder, sync_watched_folder, get_watched_folder};
+use reembed::{reembed_collection, get_reembed_status};
+use store_maintenance::{backup_vector_store, restore_vector_store, verify_vector_store};
+use collection_gc::gc_user_collections;
+use phonebook::{
+    find_nearest_contacts, list_personal_contacts, add_personal_contact, update_personal_contact,
+    delete_personal_contact,
+};
+use bundled_defaults::{ensure_bundled_defaults_initialized, import_phonebook_file};
 use tauri::Manager;
 
 /// Print a message to stderr so it appears in the terminal when running the app (e.g. dev timing logs).
@@ -36,6 +68,19 @@ fn log_to_terminal(message: String) -> Result<(), String> {
 fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
+        .setup(|app| {
+            // Custom embedding model specs and the active model (data/embedding_models.json)
+            if let Err(e) = embeddings::load_embedding_models(app.handle()) {
+                eprintln!("[Embeddings] {}; using the built-in models", e);
+            }
+            // Opt-in per-user watched folders are polled in the background
+            watched_folders::start_folder_watcher(app.handle().clone());
+            // Re-embed jobs interrupted by a restart continue where they stopped
+            reembed::resume_reembed_jobs(app.handle());
+            // Per-user collections of deleted profiles are quarantined
+            collection_gc::reconcile_user_collections_on_startup(app.handle());
+            Ok(())
+        })
         .on_window_event(|window, event| {
             if let tauri::WindowEvent::CloseRequested { .. } = event {
                 let _ = clear_current_user_on_exit(&window.app_handle());
@@ -57,14 +102,32 @@ fn main() {
             add_documents_to_collection,
             search_similar,
             search_collection,
+            search_many,
             get_collection_stats,
             get_collection_stats_by_name,
             get_documents_by_filter,
+            upsert_documents,
+            delete_documents,
+            delete_documents_by_filter,
+            get_documents,
             initialize_user_vector_store,
             delete_user_knowledge_base,
+            get_collection_settings,
+            set_collection_settings,
+            get_collection_embedding_info,
+            reembed_collection,
+            get_reembed_status,
+            backup_vector_store,
+            restore_vector_store,
+            verify_vector_store,
+            gc_user_collections,
             // Embeddings commands
             generate_embedding,
             generate_embeddings_batch,
+            embed_query,
+            embed_passages,
+            list_embedding_models,
+            set_active_embedding_model,
             // User management commands
             get_users,
             create_user,
@@ -80,6 +143,25 @@ fn main() {
             // Cache commands
             read_cache_file,
             write_cache_file,
+            // Phone book commands
+            find_nearest_contacts,
+            list_personal_contacts,
+            add_personal_contact,
+            update_personal_contact,
+            delete_personal_contact,
+            import_phonebook_file,
+            // Knowledge base package commands
+            export_collection,
+            import_kb_package,
+            convert_kb_package,
+            get_package_signature_status,
+            // Document ingestion
+            ingest_files,
+            set_watched_folder,
+            remove_watched_folder,
+            sync_watched_folder,
+            get_watched_folder,
+            get_installed_kb_package,
             // Bundled defaults (opinionated setup)
             ensure_bundled_defaults_initialized,
             log_to_terminal,
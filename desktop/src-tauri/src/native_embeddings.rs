@@ -0,0 +1,221 @@
+// Native embeddings - all-MiniLM-L6-v2 in-process (candle + tokenizers), no Python helper
+//
+// Built with --features native-embeddings. The model files (config.json, tokenizer.json, model.safetensors from
+// sentence-transformers/all-MiniLM-L6-v2) are loaded once from resources/models/all-MiniLM-L6-v2 or
+// CONFIDANT_MINILM_DIR. Embedding follows sentence-transformers: BERT forward pass on sequences truncated to
+// 256 tokens, mean pooling over the attention mask, L2 normalization. When the files are missing,
+// embeddings fall back to embeddings_helper.py.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use candle_core::{DType, Device, Tensor};
+use candle_nn::VarBuilder;
+use candle_transformers::models::bert::{BertModel, Config};
+use tauri::{AppHandle, Manager};
+use tokenizers::{PaddingParams, PaddingStrategy, Tokenizer, TruncationParams};
+
+use crate::embeddings::EMBEDDING_MODEL_ID;
+use crate::error::ConfidantError;
+
+/// Directory (under resources/models) holding the model files.
+const MODEL_DIR_NAME: &str = "all-MiniLM-L6-v2";
+
+/// sentence-transformers max_seq_length for all-MiniLM-L6-v2.
+const MAX_SEQ_LENGTH: usize = 256;
+
+const MODEL_FILES: [&str; 3] = ["config.json", "tokenizer.json", "model.safetensors"];
+
+lazy_static::lazy_static! {
+    /// Loaded model, or the reason it could not be loaded (so a broken install is not retried per call).
+    static ref EMBEDDER: Mutex<Option<Result<Arc<NativeEmbedder>, String>>> = Mutex::new(None);
+}
+
+pub struct NativeEmbedder {
+    model: BertModel,
+    tokenizer: Tokenizer,
+    device: Device,
+}
+
+impl NativeEmbedder {
+    /// Load config.json, tokenizer.json and model.safetensors from `dir` (CPU).
+    pub fn load(dir: &Path) -> Result<Self, ConfidantError> {
+        let device = Device::Cpu;
+        let config_json = std::fs::read_to_string(dir.join("config.json"))
+            .map_err(|e| ConfidantError::io(format!("Failed to read {}: {}", dir.join("config.json").display(), e)))?;
+        let config: Config = serde_json::from_str(&config_json)
+            .map_err(|e| ConfidantError::validation(format!("Invalid embedding model config: {}", e)))?;
+
+        let mut tokenizer = Tokenizer::from_file(dir.join("tokenizer.json"))
+            .map_err(|e| ConfidantError::io(format!("Failed to load tokenizer: {}", e)))?;
+        tokenizer.with_padding(Some(PaddingParams {
+            strategy: PaddingStrategy::BatchLongest,
+            ..Default::default()
+        }));
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: MAX_SEQ_LENGTH,
+                ..Default::default()
+            }))
+            .map_err(|e| ConfidantError::internal(format!("Failed to configure tokenizer: {}", e)))?;
+
+        // Safety: the weights file is memory-mapped read-only and not modified while the app runs
+        let vb = unsafe {
+            VarBuilder::from_mmaped_safetensors(&[dir.join("model.safetensors")], DType::F32, &device)
+                .map_err(|e| ConfidantError::io(format!("Failed to load embedding model weights: {}", e)))?
+        };
+        let model = BertModel::load(vb, &config)
+            .map_err(|e| ConfidantError::io(format!("Failed to load embedding model: {}", e)))?;
+
+        Ok(Self { model, tokenizer, device })
+    }
+
+    /// Normalized 384-dim embeddings, one per text.
+    pub fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, ConfidantError> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let encodings = self.tokenizer
+            .encode_batch(texts.to_vec(), true)
+            .map_err(|e| ConfidantError::internal(format!("Failed to tokenize: {}", e)))?;
+
+        let tensor = |rows: Vec<&[u32]>| -> candle_core::Result<Tensor> {
+            let rows = rows
+                .into_iter()
+                .map(|row| Tensor::new(row, &self.device))
+                .collect::<candle_core::Result<Vec<_>>>()?;
+            Tensor::stack(&rows, 0)
+        };
+        let run = || -> candle_core::Result<Vec<Vec<f32>>> {
+            let input_ids = tensor(encodings.iter().map(|e| e.get_ids()).collect())?;
+            let token_type_ids = tensor(encodings.iter().map(|e| e.get_type_ids()).collect())?;
+            let attention_mask = tensor(encodings.iter().map(|e| e.get_attention_mask()).collect())?;
+
+            let hidden = self.model.forward(&input_ids, &token_type_ids, Some(&attention_mask))?;
+            // Mean over real tokens only
+            let mask = attention_mask.to_dtype(DType::F32)?.unsqueeze(2)?;
+            let summed = hidden.broadcast_mul(&mask)?.sum(1)?;
+            let counts = mask.sum(1)?.clamp(1e-9, f32::MAX)?;
+            let pooled = summed.broadcast_div(&counts)?;
+            let norms = pooled.sqr()?.sum_keepdim(1)?.sqrt()?.clamp(1e-12, f32::MAX)?;
+            pooled.broadcast_div(&norms)?.to_vec2::<f32>()
+        };
+        run().map_err(|e| ConfidantError::internal(format!("Embedding model failed: {}", e)))
+    }
+}
+
+/// Directory with the model files: CONFIDANT_MINILM_DIR, then resources/models, then the dev source tree.
+fn resolve_model_dir(app: &AppHandle) -> Option<PathBuf> {
+    let complete = |dir: &Path| MODEL_FILES.iter().all(|f| dir.join(f).exists());
+    if let Ok(env_path) = std::env::var("CONFIDANT_MINILM_DIR") {
+        let p = PathBuf::from(&env_path);
+        if complete(&p) {
+            return Some(p);
+        }
+    }
+    if let Ok(resource_dir) = app.path().resource_dir() {
+        for base in [resource_dir.clone(), resource_dir.join("resources")] {
+            let p = base.join("models").join(MODEL_DIR_NAME);
+            if complete(&p) {
+                return Some(p);
+            }
+        }
+    }
+    // Dev fallback
+    let p = Path::new(env!("CARGO_MANIFEST_DIR")).join("resources").join("models").join(MODEL_DIR_NAME);
+    complete(&p).then_some(p)
+}
+
+/// Loaded model, or None when its files are not installed (the caller then uses the Python helper).
+fn embedder(app: &AppHandle) -> Result<Option<Arc<NativeEmbedder>>, ConfidantError> {
+    let mut cached = EMBEDDER.lock()
+        .map_err(|_| ConfidantError::internal("Embedding model lock poisoned"))?;
+    if cached.is_none() {
+        let Some(dir) = resolve_model_dir(app) else {
+            return Ok(None);
+        };
+        #[cfg(debug_assertions)]
+        eprintln!("[Embeddings] Loading native {} from {}", EMBEDDING_MODEL_ID, dir.display());
+        *cached = Some(NativeEmbedder::load(&dir).map(Arc::new).map_err(|e| e.to_string()));
+    }
+    match cached.as_ref() {
+        Some(Ok(embedder)) => Ok(Some(embedder.clone())),
+        Some(Err(e)) => Err(ConfidantError::io(e.clone())),
+        None => Ok(None),
+    }
+}
+
+/// Embed with the native model. Returns None when the model files are not installed.
+pub async fn embed_texts(app: &AppHandle, texts: Vec<String>) -> Result<Option<Vec<Vec<f32>>>, ConfidantError> {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let Some(embedder) = embedder(&app)? else {
+            return Ok(None);
+        };
+        embedder.embed(&texts).map(Some)
+    })
+    .await
+    .map_err(|e| ConfidantError::internal(format!("Embedding task failed: {}", e)))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Written by scripts/make_embedding_fixtures.py with sentence-transformers and committed with the tests.
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/minilm_embeddings.json");
+
+    /// Largest per-component difference allowed between the Python and native embeddings.
+    const TOLERANCE: f32 = 1e-4;
+
+    fn model_dir() -> PathBuf {
+        std::env::var("CONFIDANT_MINILM_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| Path::new(env!("CARGO_MANIFEST_DIR")).join("resources/models").join(MODEL_DIR_NAME))
+    }
+
+    fn fixtures() -> (Vec<String>, Vec<Vec<f32>>) {
+        let content = std::fs::read_to_string(FIXTURES).unwrap_or_else(|e| {
+            panic!("{}: {}. Generate it with scripts/make_embedding_fixtures.py and commit it.", FIXTURES, e)
+        });
+        let data: serde_json::Value = serde_json::from_str(&content).expect("fixture JSON");
+        assert_eq!(data["model"].as_str(), Some(EMBEDDING_MODEL_ID));
+        let texts = serde_json::from_value(data["texts"].clone()).expect("fixture texts");
+        let embeddings = serde_json::from_value(data["embeddings"].clone()).expect("fixture embeddings");
+        (texts, embeddings)
+    }
+
+    fn load() -> NativeEmbedder {
+        let dir = model_dir();
+        NativeEmbedder::load(&dir).unwrap_or_else(|e| {
+            panic!("{} (model files in {}; see scripts/setup-full-bundle.sh)", e, dir.display())
+        })
+    }
+
+    #[test]
+    fn matches_sentence_transformers_output() {
+        let (texts, expected) = fixtures();
+        let actual = load().embed(&texts).unwrap();
+        assert_eq!(actual.len(), expected.len());
+        for (i, (a, e)) in actual.iter().zip(&expected).enumerate() {
+            assert_eq!(a.len(), 384, "text {} dimension", i);
+            assert_eq!(a.len(), e.len(), "text {} dimension", i);
+            let max_diff = a.iter().zip(e).map(|(x, y)| (x - y).abs()).fold(0.0f32, f32::max);
+            assert!(max_diff < TOLERANCE, "text {} ({:?}) differs by {}", i, texts[i], max_diff);
+        }
+    }
+
+    #[test]
+    fn batching_does_not_change_embeddings() {
+        let (texts, _) = fixtures();
+        let embedder = load();
+        let batch = embedder.embed(&texts).unwrap();
+        for (text, batched) in texts.iter().zip(&batch) {
+            let single = embedder.embed(std::slice::from_ref(text)).unwrap();
+            let max_diff = single[0].iter().zip(batched).map(|(x, y)| (x - y).abs()).fold(0.0f32, f32::max);
+            assert!(max_diff < TOLERANCE, "{:?}: padding changed the embedding by {}", text, max_diff);
+            let norm = batched.iter().map(|v| v * v).sum::<f32>().sqrt();
+            assert!((norm - 1.0).abs() < 1e-5, "{:?}: norm {}", text, norm);
+        }
+    }
+}
@@ -0,0 +1,239 @@
+// Package Signing - Detached ed25519 signatures for knowledge base and phone book packages
+//
+// A package `foo.json` is signed by `foo.json.sig` next to it: the hex-encoded 64-byte ed25519
+// signature of the SHA-256 digest of the file's exact bytes (so large packages are hashed as a stream,
+// never loaded whole). Signatures are made with scripts/sign_package.py.
+
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::error::ConfidantError;
+
+/// Development signing key, compiled into debug builds only; the bundled packages in the repo are signed
+/// with it. Release builds must set CONFIDANT_PACKAGE_PUBLIC_KEY (64 hex chars) at compile time and ship
+/// packages re-signed with the matching private key.
+#[cfg(debug_assertions)]
+const DEV_PUBLIC_KEY_HEX: &str = "6f9feb642150a27d8430af2a721f42971148e30487fb2e6f9393c208737dec67";
+
+#[cfg(debug_assertions)]
+const PUBLIC_KEY_HEX: &str = match option_env!("CONFIDANT_PACKAGE_PUBLIC_KEY") {
+    Some(key) => key,
+    None => DEV_PUBLIC_KEY_HEX,
+};
+
+#[cfg(not(debug_assertions))]
+const PUBLIC_KEY_HEX: &str = env!(
+    "CONFIDANT_PACKAGE_PUBLIC_KEY",
+    "Release builds need CONFIDANT_PACKAGE_PUBLIC_KEY (the release package signing public key, 64 hex chars)"
+);
+
+/// Debug builds only: set to 1 to ingest unsigned bundled packages (e.g. a locally generated test KB).
+const ALLOW_UNSIGNED_ENV: &str = "CONFIDANT_ALLOW_UNSIGNED_PACKAGES";
+
+/// Result of checking a package against its detached signature.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureStatus {
+    /// Signature present and made by the compiled-in key.
+    Valid,
+    /// No .sig file next to the package.
+    Unsigned,
+    /// Signature present but malformed or not matching the file (modified or signed by another key).
+    Invalid,
+}
+
+fn public_key() -> Result<VerifyingKey, ConfidantError> {
+    let bytes: [u8; 32] = hex::decode(PUBLIC_KEY_HEX)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| ConfidantError::internal("Package signing public key is not 32 hex-encoded bytes"))?;
+    VerifyingKey::from_bytes(&bytes)
+        .map_err(|e| ConfidantError::internal(format!("Invalid package signing public key: {}", e)))
+}
+
+/// Detached signature path for a package (`<file>.sig`).
+pub fn signature_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".sig");
+    PathBuf::from(name)
+}
+
+/// Check a package's SHA-256 digest against its detached signature.
+fn check_signature(path: &Path, digest: &[u8]) -> Result<SignatureStatus, ConfidantError> {
+    check_signature_with(&public_key()?, path, digest)
+}
+
+fn check_signature_with(key: &VerifyingKey, path: &Path, digest: &[u8]) -> Result<SignatureStatus, ConfidantError> {
+    let sig_path = signature_path(path);
+    if !sig_path.exists() {
+        return Ok(SignatureStatus::Unsigned);
+    }
+    let sig_hex = fs::read_to_string(&sig_path)
+        .map_err(|e| ConfidantError::io(format!("Failed to read package signature: {}", e)))?;
+    let signature = match hex::decode(sig_hex.trim()).ok().and_then(|b| Signature::from_slice(&b).ok()) {
+        Some(s) => s,
+        None => return Ok(SignatureStatus::Invalid),
+    };
+    Ok(match key.verify(digest, &signature) {
+        Ok(()) => SignatureStatus::Valid,
+        Err(_) => SignatureStatus::Invalid,
+    })
+}
+
+/// Verify a package file against its detached signature (status only; importers use
+/// `read_trusted_package` / `copy_trusted_package` so the verified bytes are the ones parsed).
+pub fn verify_package_file(path: &Path) -> Result<SignatureStatus, ConfidantError> {
+    let mut file = File::open(path)
+        .map_err(|e| ConfidantError::io(format!("Failed to read package: {}", e)))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)
+        .map_err(|e| ConfidantError::io(format!("Failed to read package: {}", e)))?;
+    check_signature(path, &hasher.finalize())
+}
+
+fn unsigned_allowed_by_env() -> bool {
+    cfg!(debug_assertions) && std::env::var(ALLOW_UNSIGNED_ENV).map(|v| v == "1").unwrap_or(false)
+}
+
+/// Packages with a bad signature are always refused; unsigned packages only pass with `allow_unsigned`
+/// (explicit user consent).
+fn require_trusted(
+    path: &Path,
+    status: SignatureStatus,
+    allow_unsigned: bool,
+) -> Result<SignatureStatus, ConfidantError> {
+    match status {
+        SignatureStatus::Valid => Ok(SignatureStatus::Valid),
+        SignatureStatus::Unsigned if allow_unsigned || unsigned_allowed_by_env() => Ok(SignatureStatus::Unsigned),
+        SignatureStatus::Unsigned => Err(ConfidantError::validation(format!(
+            "Package {} is not signed. Only import it if you trust its source.",
+            path.display()
+        ))),
+        SignatureStatus::Invalid => Err(ConfidantError::validation(format!(
+            "Package {} has an invalid signature; it may have been modified and will not be imported.",
+            path.display()
+        ))),
+    }
+}
+
+/// Read a package once and verify those bytes before ingest. Returns the bytes to parse and the status.
+pub fn read_trusted_package(
+    path: &Path,
+    allow_unsigned: bool,
+) -> Result<(Vec<u8>, SignatureStatus), ConfidantError> {
+    let content = fs::read(path)
+        .map_err(|e| ConfidantError::io(format!("Failed to read package: {}", e)))?;
+    let status = check_signature(path, &Sha256::digest(&content))?;
+    Ok((content, require_trusted(path, status, allow_unsigned)?))
+}
+
+/// Stream a (large) package into `dest`, hashing the bytes as they are copied, and verify them. The caller
+/// reads `dest`, so later changes to `path` cannot swap in unverified content. `dest` is removed on failure.
+pub fn copy_trusted_package(
+    path: &Path,
+    dest: &Path,
+    allow_unsigned: bool,
+) -> Result<SignatureStatus, ConfidantError> {
+    let result = (|| {
+        let mut source = File::open(path)
+            .map_err(|e| ConfidantError::io(format!("Failed to read package: {}", e)))?;
+        let mut copy = File::create(dest)
+            .map_err(|e| ConfidantError::io(format!("Failed to copy package: {}", e)))?;
+        let mut hasher = Sha256::new();
+        let mut buf = vec![0u8; 1 << 16];
+        loop {
+            let n = source.read(&mut buf)
+                .map_err(|e| ConfidantError::io(format!("Failed to read package: {}", e)))?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+            copy.write_all(&buf[..n])
+                .map_err(|e| ConfidantError::io(format!("Failed to copy package: {}", e)))?;
+        }
+        copy.sync_all()
+            .map_err(|e| ConfidantError::io(format!("Failed to copy package: {}", e)))?;
+        let status = check_signature(path, &hasher.finalize())?;
+        require_trusted(path, status, allow_unsigned)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(dest);
+    }
+    result
+}
+
+/// Signature status of a package file, e.g. to show before asking the user to import it.
+#[tauri::command]
+pub async fn get_package_signature_status(path: String) -> Result<SignatureStatus, ConfidantError> {
+    verify_package_file(Path::new(&path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    /// Package plus detached signature made with a throwaway key.
+    fn signed_package(name: &str, content: &[u8]) -> (PathBuf, VerifyingKey) {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let dir = std::env::temp_dir().join(format!("confidant-signing-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("package.json");
+        fs::write(&path, content).unwrap();
+        let signature = key.sign(&Sha256::digest(content));
+        fs::write(signature_path(&path), hex::encode(signature.to_bytes())).unwrap();
+        (path, key.verifying_key())
+    }
+
+    fn status(key: &VerifyingKey, path: &Path) -> SignatureStatus {
+        let digest = Sha256::digest(fs::read(path).unwrap());
+        check_signature_with(key, path, &digest).unwrap()
+    }
+
+    #[test]
+    fn valid_signature_verifies() {
+        let (path, key) = signed_package("valid", b"{\"documents\":[]}");
+        assert_eq!(status(&key, &path), SignatureStatus::Valid);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn modified_byte_is_invalid() {
+        let (path, key) = signed_package("modified", b"{\"documents\":[]}");
+        let mut content = fs::read(&path).unwrap();
+        content[2] ^= 1;
+        fs::write(&path, content).unwrap();
+        assert_eq!(status(&key, &path), SignatureStatus::Invalid);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn missing_signature_is_unsigned() {
+        let (path, key) = signed_package("unsigned", b"{}");
+        fs::remove_file(signature_path(&path)).unwrap();
+        assert_eq!(status(&key, &path), SignatureStatus::Unsigned);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn malformed_signature_is_invalid_not_an_error() {
+        let (path, key) = signed_package("malformed", b"{}");
+        fs::write(signature_path(&path), "not hex").unwrap();
+        assert_eq!(status(&key, &path), SignatureStatus::Invalid);
+        fs::write(signature_path(&path), "abcd").unwrap();
+        assert_eq!(status(&key, &path), SignatureStatus::Invalid);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn unsigned_needs_consent_and_invalid_is_refused() {
+        let path = Path::new("package.json");
+        assert!(require_trusted(path, SignatureStatus::Unsigned, true).is_ok());
+        assert!(require_trusted(path, SignatureStatus::Invalid, true).is_err());
+        assert_eq!(require_trusted(path, SignatureStatus::Valid, false).unwrap(), SignatureStatus::Valid);
+    }
+}
@@ -0,0 +1,580 @@
+// Phone book - Proximity-aware lookup over the dant_phonebook collection
+//
+// Entries carry country, postal_code, city and state. find_nearest_contacts places the user's postal code
+// (or city) and each entry with the bundled offline geocoding table (resources/postal_geocodes.json), ranks
+// local entries by great-circle distance and always appends the country's NATIONAL crisis lines. Table keys
+// are postal prefixes (US ZIP3, Canadian FSA or province letter, UK outward code or area), so a code that is
+// not listed still lands on its area.
+//
+// Users can also keep personal contacts (their therapist, doctor or a trusted friend) in
+// data/users/{id}/contacts.json, separate from the global seed. Lookups for a user list those first.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use chrono::Utc;
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+
+use crate::error::ConfidantError;
+use crate::vector_store::{get_documents_by_filter, FilterDocument};
+
+const PHONEBOOK_COLLECTION: &str = "dant_phonebook";
+
+/// postal_code of country-wide entries (crisis lines).
+const NATIONAL_POSTAL_CODE: &str = "NATIONAL";
+
+const GEOCODES_FILENAME: &str = "postal_geocodes.json";
+
+const DEFAULT_RADIUS_KM: f64 = 100.0;
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+lazy_static::lazy_static! {
+    /// Geocoding table, loaded on first lookup.
+    static ref GEOCODES: Mutex<Option<Arc<Vec<GeoPlace>>>> = Mutex::new(None);
+    /// Serializes read-modify-write of personal contact files.
+    static ref CONTACTS_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// Row of the geocoding table.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GeoPlace {
+    pub country: String,
+    /// Postal prefix, upper case without spaces.
+    pub postal_code: String,
+    pub city: String,
+    #[serde(default)]
+    pub state: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NearbyContact {
+    #[serde(flatten)]
+    pub document: FilterDocument,
+    /// Kilometres from the user's location; None for national lines and personal contacts that cannot be placed.
+    pub distance_km: Option<f64>,
+    pub national: bool,
+    /// One of the user's own contacts rather than a phone book entry.
+    pub personal: bool,
+}
+
+/// Contact a user keeps for themselves. Fields mirror the phone book seed.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PersonalContact {
+    pub id: String,
+    pub name: String,
+    pub phone: String,
+    #[serde(default)]
+    pub profession: String,
+    #[serde(default)]
+    pub address: String,
+    #[serde(default)]
+    pub city: String,
+    #[serde(default)]
+    pub state: String,
+    #[serde(default)]
+    pub postal_code: String,
+    #[serde(default)]
+    pub country: String,
+    #[serde(default)]
+    pub notes: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Editable fields of a personal contact.
+#[derive(Debug, Deserialize, Clone)]
+pub struct PersonalContactInput {
+    pub name: String,
+    pub phone: String,
+    #[serde(default)]
+    pub profession: String,
+    #[serde(default)]
+    pub address: String,
+    #[serde(default)]
+    pub city: String,
+    #[serde(default)]
+    pub state: String,
+    #[serde(default)]
+    pub postal_code: String,
+    #[serde(default)]
+    pub country: String,
+    #[serde(default)]
+    pub notes: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NearestContacts {
+    /// Where the postal code (or city) was placed; None when the table does not cover it, in which case only
+    /// entries with the same postal code are listed as local.
+    pub origin: Option<GeoPlace>,
+    pub radius_km: f64,
+    /// The user's personal contacts, then local entries nearest first, then the country's national crisis lines.
+    pub contacts: Vec<NearbyContact>,
+}
+
+/// Resolve path to the bundled geocoding table.
+fn resolve_geocodes_path(app: &AppHandle) -> Option<PathBuf> {
+    if let Ok(env_path) = std::env::var("CONFIDANT_POSTAL_GEOCODES_PATH") {
+        let p = PathBuf::from(&env_path);
+        if p.exists() {
+            return Some(p);
+        }
+    }
+    if let Ok(resource_dir) = app.path().resource_dir() {
+        for base in [resource_dir.clone(), resource_dir.join("resources")] {
+            let p = base.join(GEOCODES_FILENAME);
+            if p.exists() {
+                return Some(p);
+            }
+        }
+    }
+    // Dev fallback
+    if let Ok(mut current) = std::env::current_dir() {
+        for _ in 0..6 {
+            let p = current.join("src-tauri").join("resources").join(GEOCODES_FILENAME);
+            if p.exists() {
+                return Some(p);
+            }
+            let p = current.join("desktop").join("src-tauri").join("resources").join(GEOCODES_FILENAME);
+            if p.exists() {
+                return Some(p);
+            }
+            if let Some(parent) = current.parent() {
+                current = parent.to_path_buf();
+            } else {
+                break;
+            }
+        }
+    }
+    None
+}
+
+fn load_geocodes(app: &AppHandle) -> Result<Vec<GeoPlace>, String> {
+    let path = resolve_geocodes_path(app).ok_or_else(|| "Postal geocoding table not found".to_string())?;
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid geocoding table {}: {}", path.display(), e))
+}
+
+/// Geocoding table; empty when it cannot be loaded, so lookups still return national lines.
+fn geocodes(app: &AppHandle) -> Result<Arc<Vec<GeoPlace>>, ConfidantError> {
+    let mut cached = GEOCODES.lock()
+        .map_err(|_| ConfidantError::internal("Geocoding table lock poisoned"))?;
+    if let Some(table) = cached.as_ref() {
+        return Ok(table.clone());
+    }
+    let table = Arc::new(load_geocodes(app).unwrap_or_else(|e| {
+        eprintln!("[Phonebook] {}; distance ranking disabled", e);
+        Vec::new()
+    }));
+    *cached = Some(table.clone());
+    Ok(table)
+}
+
+/// Postal code in the form the table is keyed by: US 5-digit ZIP, Canadian FSA, UK outward code.
+fn normalize_postal(country: &str, code: &str) -> String {
+    let upper = code.trim().to_uppercase();
+    let compact: String = upper.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    match country {
+        "US" => compact.chars().take_while(|c| c.is_ascii_digit()).take(5).collect(),
+        "CA" => compact.chars().take(3).collect(),
+        "GB" => match upper.split_whitespace().collect::<Vec<_>>().as_slice() {
+            [outward, _, ..] => outward.chars().filter(|c| c.is_ascii_alphanumeric()).collect(),
+            // Full postcodes without a space end in a 3-character inward code
+            _ if compact.len() >= 5 => compact[..compact.len() - 3].to_string(),
+            _ => compact,
+        },
+        _ => compact,
+    }
+}
+
+/// `key` covers `code` when it is a prefix that does not cut a run of letters ("B" covers "B1", not "BA1").
+fn is_area_prefix(key: &str, code: &str) -> bool {
+    if key.is_empty() || !code.starts_with(key) {
+        return false;
+    }
+    let key_ends_in_letter = key.chars().last().is_some_and(|c| c.is_ascii_alphabetic());
+    let next_is_letter = code[key.len()..].chars().next().is_some_and(|c| c.is_ascii_alphabetic());
+    !(key_ends_in_letter && next_is_letter)
+}
+
+/// Most specific table row covering the postal code.
+fn lookup_postal<'a>(table: &'a [GeoPlace], country: &str, postal_code: &str) -> Option<&'a GeoPlace> {
+    let code = normalize_postal(country, postal_code);
+    if code.is_empty() {
+        return None;
+    }
+    table.iter()
+        .filter(|p| p.country == country && is_area_prefix(&p.postal_code, &code))
+        .max_by_key(|p| p.postal_code.len())
+}
+
+/// First table row for the city (and state, when both sides have one).
+fn lookup_city<'a>(table: &'a [GeoPlace], country: &str, city: &str, state: &str) -> Option<&'a GeoPlace> {
+    let city = city.trim();
+    let state = state.trim();
+    if city.is_empty() {
+        return None;
+    }
+    table.iter().find(|p| {
+        p.country == country
+            && p.city.eq_ignore_ascii_case(city)
+            && (state.is_empty() || p.state.is_empty() || p.state.eq_ignore_ascii_case(state))
+    })
+}
+
+/// Coordinates of a phone book entry: explicit lat/lon metadata, else its postal code, else its city.
+fn locate_entry(table: &[GeoPlace], country: &str, metadata: &serde_json::Value) -> Option<(f64, f64)> {
+    if let (Some(lat), Some(lon)) = (metadata["lat"].as_f64(), metadata["lon"].as_f64()) {
+        return Some((lat, lon));
+    }
+    let field = |key: &str| metadata[key].as_str().unwrap_or("");
+    lookup_postal(table, country, field("postal_code"))
+        .or_else(|| lookup_city(table, country, field("city"), field("state")))
+        .map(|p| (p.lat, p.lon))
+}
+
+/// Great-circle distance in kilometres.
+fn haversine_km(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (lat1, lon1) = (a.0.to_radians(), a.1.to_radians());
+    let (lat2, lon2) = (b.0.to_radians(), b.1.to_radians());
+    let h = ((lat2 - lat1) / 2.0).sin().powi(2)
+        + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * h.sqrt().min(1.0).asin()
+}
+
+/// Get app data directory (same layout as user_management).
+fn get_app_data_dir(app: &AppHandle) -> Result<PathBuf, ConfidantError> {
+    let base_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| ConfidantError::io(format!("Failed to get app data dir: {}", e)))?;
+    let data_dir = base_dir.join("data");
+    fs::create_dir_all(&data_dir)
+        .map_err(|e| ConfidantError::io(format!("Failed to create data directory: {}", e)))?;
+    Ok(data_dir)
+}
+
+/// Path to a user's contacts file; fails for unknown users so ids never reach the filesystem unchecked.
+fn contacts_path(app: &AppHandle, user_id: &str) -> Result<PathBuf, ConfidantError> {
+    let users = crate::user_management::load_users(app)?;
+    if !users.iter().any(|u| u.id == user_id) {
+        return Err(ConfidantError::not_found("User not found"));
+    }
+    Ok(get_app_data_dir(app)?.join("users").join(user_id).join("contacts.json"))
+}
+
+fn load_contacts(path: &Path) -> Result<Vec<PersonalContact>, ConfidantError> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| ConfidantError::io(format!("Failed to read contacts: {}", e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| ConfidantError::internal(format!("Failed to parse contacts: {}", e)))
+}
+
+fn save_contacts(path: &Path, contacts: &[PersonalContact]) -> Result<(), ConfidantError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| ConfidantError::io(format!("Failed to create user directory: {}", e)))?;
+    }
+    let content = serde_json::to_string_pretty(contacts)
+        .map_err(|e| ConfidantError::internal(format!("Failed to serialize contacts: {}", e)))?;
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, content)
+        .map_err(|e| ConfidantError::io(format!("Failed to write contacts: {}", e)))?;
+    fs::rename(&tmp, path)
+        .map_err(|e| ConfidantError::io(format!("Failed to write contacts: {}", e)))
+}
+
+/// Trimmed input with upper-case country; name and phone are required.
+fn validate_contact(input: PersonalContactInput) -> Result<PersonalContactInput, ConfidantError> {
+    let input = PersonalContactInput {
+        name: input.name.trim().to_string(),
+        phone: input.phone.trim().to_string(),
+        profession: input.profession.trim().to_string(),
+        address: input.address.trim().to_string(),
+        city: input.city.trim().to_string(),
+        state: input.state.trim().to_string(),
+        postal_code: input.postal_code.trim().to_string(),
+        country: input.country.trim().to_uppercase(),
+        notes: input.notes.trim().to_string(),
+    };
+    if input.name.is_empty() {
+        return Err(ConfidantError::validation("Contact name is required"));
+    }
+    if input.phone.is_empty() {
+        return Err(ConfidantError::validation("Contact phone is required"));
+    }
+    Ok(input)
+}
+
+fn apply_input(contact: &mut PersonalContact, input: PersonalContactInput) {
+    contact.name = input.name;
+    contact.phone = input.phone;
+    contact.profession = input.profession;
+    contact.address = input.address;
+    contact.city = input.city;
+    contact.state = input.state;
+    contact.postal_code = input.postal_code;
+    contact.country = input.country;
+    contact.notes = input.notes;
+    contact.updated_at = Utc::now().to_rfc3339();
+}
+
+/// Personal contact in the shape of a phone book document, so lookups return one list.
+fn contact_document(contact: &PersonalContact) -> FilterDocument {
+    let text = if contact.profession.is_empty() {
+        contact.name.clone()
+    } else {
+        format!("{} ({})", contact.name, contact.profession)
+    };
+    FilterDocument {
+        id: contact.id.clone(),
+        text,
+        metadata: serde_json::json!({
+            "country": contact.country,
+            "postal_code": contact.postal_code,
+            "profession": contact.profession,
+            "name": contact.name,
+            "phone": contact.phone,
+            "address": contact.address,
+            "city": contact.city,
+            "state": contact.state,
+            "notes": contact.notes,
+        }),
+        embedding: None,
+    }
+}
+
+fn personal_contacts(app: &AppHandle, user_id: &str) -> Result<Vec<PersonalContact>, ConfidantError> {
+    let path = contacts_path(app, user_id)?;
+    let _guard = CONTACTS_LOCK.lock().map_err(|_| ConfidantError::internal("Contacts lock poisoned"))?;
+    load_contacts(&path)
+}
+
+/// List a user's personal contacts in the order they were added.
+#[tauri::command]
+pub async fn list_personal_contacts(app: AppHandle, user_id: String) -> Result<Vec<PersonalContact>, ConfidantError> {
+    personal_contacts(&app, &user_id)
+}
+
+/// Add a personal contact for a user.
+#[tauri::command]
+pub async fn add_personal_contact(
+    app: AppHandle,
+    user_id: String,
+    contact: PersonalContactInput,
+) -> Result<PersonalContact, ConfidantError> {
+    let input = validate_contact(contact)?;
+    let path = contacts_path(&app, &user_id)?;
+    let _guard = CONTACTS_LOCK.lock().map_err(|_| ConfidantError::internal("Contacts lock poisoned"))?;
+    let mut contacts = load_contacts(&path)?;
+    let now = Utc::now().to_rfc3339();
+    let mut added = PersonalContact {
+        id: format!("contact_{}", Uuid::new_v4()),
+        name: String::new(),
+        phone: String::new(),
+        profession: String::new(),
+        address: String::new(),
+        city: String::new(),
+        state: String::new(),
+        postal_code: String::new(),
+        country: String::new(),
+        notes: String::new(),
+        created_at: now.clone(),
+        updated_at: now,
+    };
+    apply_input(&mut added, input);
+    contacts.push(added.clone());
+    save_contacts(&path, &contacts)?;
+    Ok(added)
+}
+
+/// Replace the fields of a personal contact.
+#[tauri::command]
+pub async fn update_personal_contact(
+    app: AppHandle,
+    user_id: String,
+    contact_id: String,
+    contact: PersonalContactInput,
+) -> Result<PersonalContact, ConfidantError> {
+    let input = validate_contact(contact)?;
+    let path = contacts_path(&app, &user_id)?;
+    let _guard = CONTACTS_LOCK.lock().map_err(|_| ConfidantError::internal("Contacts lock poisoned"))?;
+    let mut contacts = load_contacts(&path)?;
+    let existing = contacts.iter_mut()
+        .find(|c| c.id == contact_id)
+        .ok_or_else(|| ConfidantError::not_found("Contact not found"))?;
+    apply_input(existing, input);
+    let updated = existing.clone();
+    save_contacts(&path, &contacts)?;
+    Ok(updated)
+}
+
+/// Delete a personal contact.
+#[tauri::command]
+pub async fn delete_personal_contact(app: AppHandle, user_id: String, contact_id: String) -> Result<(), ConfidantError> {
+    let path = contacts_path(&app, &user_id)?;
+    let _guard = CONTACTS_LOCK.lock().map_err(|_| ConfidantError::internal("Contacts lock poisoned"))?;
+    let mut contacts = load_contacts(&path)?;
+    let before = contacts.len();
+    contacts.retain(|c| c.id != contact_id);
+    if contacts.len() == before {
+        return Err(ConfidantError::not_found("Contact not found"));
+    }
+    save_contacts(&path, &contacts)
+}
+
+/// Phone book entries for a country nearest to a postal code (or city name), optionally of one profession,
+/// within `radius_km` (default 100). National crisis lines for the country are always appended. With a
+/// `user_id`, that user's personal contacts (of the profession, wherever they are) come first.
+#[tauri::command]
+pub async fn find_nearest_contacts(
+    app: AppHandle,
+    country: String,
+    postal_code: String,
+    profession: Option<String>,
+    radius_km: Option<f64>,
+    user_id: Option<String>,
+) -> Result<NearestContacts, ConfidantError> {
+    let country = country.trim().to_uppercase();
+    if country.is_empty() {
+        return Err(ConfidantError::validation("country is required"));
+    }
+    let radius_km = radius_km.unwrap_or(DEFAULT_RADIUS_KM);
+    if !radius_km.is_finite() || radius_km <= 0.0 {
+        return Err(ConfidantError::validation("radius_km must be a positive number"));
+    }
+
+    let table = geocodes(&app)?;
+    let origin = lookup_postal(&table, &country, &postal_code)
+        .or_else(|| lookup_city(&table, &country, &postal_code, ""))
+        .cloned();
+    let user_postal = normalize_postal(&country, &postal_code);
+    let profession = profession.as_deref().map(str::trim).filter(|p| !p.is_empty());
+
+    let mut personal = Vec::new();
+    if let Some(user_id) = user_id.as_deref() {
+        // Crisis lines must still come back if the contacts file is unreadable
+        let contacts = personal_contacts(&app, user_id).unwrap_or_else(|e| {
+            eprintln!("[Phonebook] Could not load contacts of user {}: {}", user_id, e);
+            Vec::new()
+        });
+        for contact in contacts {
+            if profession.is_some_and(|wanted| !contact.profession.eq_ignore_ascii_case(wanted)) {
+                continue;
+            }
+            let document = contact_document(&contact);
+            let distance_km = match &origin {
+                Some(o) if contact.country == country => locate_entry(&table, &country, &document.metadata)
+                    .map(|at| haversine_km((o.lat, o.lon), at)),
+                _ => None,
+            };
+            personal.push(NearbyContact { document, distance_km, national: false, personal: true });
+        }
+    }
+
+    let where_json = serde_json::json!({ "country": { "$eq": country } }).to_string();
+    let documents = get_documents_by_filter(app, PHONEBOOK_COLLECTION.to_string(), where_json, None)
+        .await?
+        .documents;
+
+    let mut local = Vec::new();
+    let mut national = Vec::new();
+    for document in documents {
+        let entry_postal = document.metadata["postal_code"].as_str().unwrap_or("").trim().to_string();
+        if entry_postal.eq_ignore_ascii_case(NATIONAL_POSTAL_CODE) {
+            national.push(NearbyContact { document, distance_km: None, national: true, personal: false });
+            continue;
+        }
+        if let Some(wanted) = profession {
+            let entry_profession = document.metadata["profession"].as_str().unwrap_or("").trim();
+            if !entry_profession.eq_ignore_ascii_case(wanted) {
+                continue;
+            }
+        }
+        let same_postal = !user_postal.is_empty() && normalize_postal(&country, &entry_postal) == user_postal;
+        let distance_km = match (&origin, locate_entry(&table, &country, &document.metadata)) {
+            (Some(o), Some(at)) => Some(haversine_km((o.lat, o.lon), at)),
+            _ if same_postal => Some(0.0),
+            _ => None,
+        };
+        if let Some(d) = distance_km.filter(|d| *d <= radius_km) {
+            local.push(NearbyContact { document, distance_km: Some(d), national: false, personal: false });
+        }
+    }
+
+    let name = |c: &NearbyContact| c.document.metadata["name"].as_str().unwrap_or("").to_string();
+    local.sort_by(|a, b| {
+        a.distance_km.partial_cmp(&b.distance_km)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| name(a).cmp(&name(b)))
+    });
+    personal.extend(local);
+    personal.extend(national);
+
+    Ok(NearestContacts { origin, radius_km, contacts: personal })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn place(country: &str, postal_code: &str, city: &str) -> GeoPlace {
+        GeoPlace {
+            country: country.to_string(),
+            postal_code: postal_code.to_string(),
+            city: city.to_string(),
+            state: String::new(),
+            lat: 0.0,
+            lon: 0.0,
+        }
+    }
+
+    #[test]
+    fn normalizes_postal_codes_per_country() {
+        assert_eq!(normalize_postal("US", "02139-4307"), "02139");
+        assert_eq!(normalize_postal("CA", "k1a 0b1"), "K1A");
+        assert_eq!(normalize_postal("GB", "SW1A 1AA"), "SW1A");
+        assert_eq!(normalize_postal("GB", "sw1a1aa"), "SW1A");
+        assert_eq!(normalize_postal("GB", "B1 1AA"), "B1");
+        assert_eq!(normalize_postal("GB", "B11AA"), "B1");
+    }
+
+    #[test]
+    fn area_prefix_does_not_split_letters() {
+        assert!(is_area_prefix("B", "B1"));
+        assert!(!is_area_prefix("B", "BA1"));
+        assert!(is_area_prefix("BA", "BA1"));
+        assert!(!is_area_prefix("", "B1"));
+    }
+
+    #[test]
+    fn lookup_picks_the_most_specific_row() {
+        let table = vec![
+            place("GB", "B", "Birmingham"),
+            place("GB", "B1", "Birmingham Centre"),
+            place("GB", "BA", "Bath"),
+            place("US", "B1", "Elsewhere"),
+        ];
+        assert_eq!(lookup_postal(&table, "GB", "B1 1AA").map(|p| p.city.as_str()), Some("Birmingham Centre"));
+        assert_eq!(lookup_postal(&table, "GB", "B2 4QA").map(|p| p.city.as_str()), Some("Birmingham"));
+        assert_eq!(lookup_postal(&table, "GB", "BA1 1LN").map(|p| p.city.as_str()), Some("Bath"));
+        assert!(lookup_postal(&table, "GB", "").is_none());
+    }
+
+    #[test]
+    fn haversine_matches_known_distances() {
+        assert_eq!(haversine_km((40.0, -74.0), (40.0, -74.0)), 0.0);
+        let london_paris = haversine_km((51.5074, -0.1278), (48.8566, 2.3522));
+        assert!((london_paris - 343.5).abs() < 2.0, "{}", london_paris);
+    }
+}
@@ -0,0 +1,173 @@
+// Ranking - Similarity metrics and result selection applied on top of vector search
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ConfidantError;
+
+/// How query/document similarity is scored. All variants produce "higher is more similar".
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DistanceMetric {
+    /// Cosine similarity in [-1, 1].
+    #[default]
+    Cosine,
+    /// Raw inner product (equals cosine for normalized embeddings).
+    DotProduct,
+    /// Euclidean distance mapped to (0, 1] as 1 / (1 + d).
+    Euclidean,
+}
+
+/// Per-search options. When given, scores are recomputed with `metric` from the candidate embeddings.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SearchOptions {
+    /// Drop results scoring below this (e.g. 0.7 as in config.example.yaml `similarity_threshold`).
+    #[serde(default)]
+    pub min_score: Option<f32>,
+    #[serde(default)]
+    pub metric: DistanceMetric,
+    /// Maximal marginal relevance trade-off: 1.0 = pure relevance, 0.0 = pure diversity. None disables MMR.
+    #[serde(default)]
+    pub mmr_lambda: Option<f32>,
+    /// Number of nearest neighbours fetched before thresholding and MMR (defaults to 4x the limit).
+    #[serde(default)]
+    pub candidates: Option<u32>,
+}
+
+impl SearchOptions {
+    pub fn validate(&self) -> Result<(), ConfidantError> {
+        if let Some(lambda) = self.mmr_lambda {
+            if !(0.0..=1.0).contains(&lambda) {
+                return Err(ConfidantError::validation(format!("mmr_lambda must be between 0 and 1, got {}", lambda)));
+            }
+        }
+        Ok(())
+    }
+
+    /// How many candidates to fetch from the store for a search returning `limit` results.
+    pub fn candidate_count(&self, limit: u32) -> u32 {
+        self.candidates.unwrap_or(limit.saturating_mul(4)).max(limit)
+    }
+}
+
+/// Similarity between two vectors under `metric`. Mismatched or empty vectors score 0.
+pub fn similarity(metric: DistanceMetric, a: &[f32], b: &[f32]) -> f32 {
+    if a.is_empty() || a.len() != b.len() {
+        return 0.0;
+    }
+    match metric {
+        DistanceMetric::Cosine => {
+            let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+            let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+            let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+            if norm_a == 0.0 || norm_b == 0.0 {
+                0.0
+            } else {
+                dot / (norm_a * norm_b)
+            }
+        }
+        DistanceMetric::DotProduct => a.iter().zip(b).map(|(x, y)| x * y).sum(),
+        DistanceMetric::Euclidean => {
+            let d = a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum::<f32>().sqrt();
+            1.0 / (1.0 + d)
+        }
+    }
+}
+
+/// Greedy maximal marginal relevance: pick `k` indices balancing relevance against similarity
+/// to what was already picked. `relevance[i]` is the relevance of `embeddings[i]` to the query.
+pub fn mmr_select(
+    relevance: &[f32],
+    embeddings: &[Vec<f32>],
+    k: usize,
+    lambda: f32,
+    metric: DistanceMetric,
+) -> Vec<usize> {
+    let mut selected: Vec<usize> = Vec::with_capacity(k.min(relevance.len()));
+    let mut remaining: Vec<usize> = (0..relevance.len()).collect();
+
+    while selected.len() < k && !remaining.is_empty() {
+        let mut best_pos = 0;
+        let mut best_score = f32::NEG_INFINITY;
+        for (pos, &i) in remaining.iter().enumerate() {
+            let redundancy = selected
+                .iter()
+                .map(|&j| similarity(metric, &embeddings[i], &embeddings[j]))
+                .fold(f32::NEG_INFINITY, f32::max);
+            let redundancy = if selected.is_empty() { 0.0 } else { redundancy };
+            let score = lambda * relevance[i] - (1.0 - lambda) * redundancy;
+            if score > best_score {
+                best_score = score;
+                best_pos = pos;
+            }
+        }
+        selected.push(remaining.remove(best_pos));
+    }
+
+    selected
+}
+
+/// Min-max normalize scores to [0, 1]. A constant list maps to all 1.0.
+pub fn normalize_scores(scores: &[f32]) -> Vec<f32> {
+    let min = scores.iter().copied().fold(f32::INFINITY, f32::min);
+    let max = scores.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    if max <= min {
+        return vec![1.0; scores.len()];
+    }
+    scores.iter().map(|s| (s - min) / (max - min)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn euclidean_maps_distance_into_unit_interval() {
+        assert_eq!(similarity(DistanceMetric::Euclidean, &[1.0, 2.0], &[1.0, 2.0]), 1.0);
+        assert_eq!(similarity(DistanceMetric::Euclidean, &[0.0, 0.0], &[3.0, 4.0]), 1.0 / 6.0);
+        assert_eq!(similarity(DistanceMetric::Euclidean, &[1.0], &[1.0, 2.0]), 0.0);
+    }
+
+    #[test]
+    fn mmr_prefers_a_diverse_item_over_a_near_duplicate() {
+        let embeddings = vec![vec![1.0, 0.0], vec![0.99, 0.01], vec![0.0, 1.0]];
+        let relevance = [0.9, 0.89, 0.6];
+        let picked = mmr_select(&relevance, &embeddings, 2, 0.5, DistanceMetric::Cosine);
+        assert_eq!(picked, vec![0, 2]);
+    }
+
+    #[test]
+    fn mmr_with_lambda_one_is_a_top_k_sort() {
+        let embeddings = vec![vec![1.0, 0.0], vec![0.99, 0.01], vec![0.0, 1.0], vec![0.5, 0.5]];
+        let relevance = [0.4, 0.9, 0.1, 0.7];
+        let picked = mmr_select(&relevance, &embeddings, 3, 1.0, DistanceMetric::Cosine);
+        assert_eq!(picked, vec![1, 3, 0]);
+    }
+
+    #[test]
+    fn normalize_scores_maps_to_unit_range() {
+        assert_eq!(normalize_scores(&[0.5, 0.5, 0.5]), vec![1.0, 1.0, 1.0]);
+        assert_eq!(normalize_scores(&[1.0, 2.0, 3.0]), vec![0.0, 0.5, 1.0]);
+        assert!(normalize_scores(&[]).is_empty());
+    }
+
+    #[test]
+    fn candidate_count_defaults_to_four_times_the_limit() {
+        let mut opts = SearchOptions::default();
+        assert_eq!(opts.candidate_count(5), 20);
+        assert_eq!(opts.candidate_count(u32::MAX), u32::MAX);
+        opts.candidates = Some(50);
+        assert_eq!(opts.candidate_count(5), 50);
+        opts.candidates = Some(2);
+        assert_eq!(opts.candidate_count(5), 5);
+    }
+
+    #[test]
+    fn mmr_lambda_out_of_range_is_a_validation_error() {
+        let opts = SearchOptions {
+            mmr_lambda: Some(1.5),
+            ..Default::default()
+        };
+        assert_eq!(opts.validate().unwrap_err().code(), "validation");
+        assert!(SearchOptions::default().validate().is_ok());
+    }
+}
@@ -0,0 +1,450 @@
+// Re-embed - Rebuild a collection's vectors when the embedding model changes
+//
+// reembed_collection pages through the collection's stored text in sorted id order, embeds it with the target
+// model and upserts it into a shadow collection `{name}__reembed`. The last copied id is checkpointed to
+// data/reembed_jobs.json after every page, so a job interrupted by a restart resumes after it
+// (resume_reembed_jobs runs at startup). When every document is copied, the shadow collection replaces the original by rename; the
+// original stays searchable until then, but writes to it are refused while the job runs.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::embeddings::{active_embedding_model_id, embed_texts_with_model, same_embedding_model, EMBEDDING_MODEL_ID};
+use crate::error::ConfidantError;
+use crate::vector_store::{
+    call_python_helper, lock_collection_read, lock_collection_write, open_store_or_default, record_collection_model,
+};
+
+/// Documents embedded per page (and per checkpoint).
+const PAGE_SIZE: usize = 64;
+
+/// Suffix of the shadow collection a re-embed writes into.
+pub const SHADOW_SUFFIX: &str = "__reembed";
+
+/// Event emitted after every page and when a job finishes or fails.
+pub const REEMBED_PROGRESS_EVENT: &str = "reembed-progress";
+
+lazy_static::lazy_static! {
+    /// Collections with a job running in this process.
+    static ref RUNNING_JOBS: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+    /// Serializes read-modify-write of reembed_jobs.json between concurrent jobs.
+    static ref JOBS_FILE: Mutex<()> = Mutex::new(());
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReembedStatus {
+    /// Copying documents into the shadow collection.
+    Running,
+    /// All documents copied; replacing the original collection.
+    Swapping,
+    Completed,
+    Failed,
+}
+
+/// Persisted state of a re-embed job (also the progress event payload).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReembedJob {
+    pub collection: String,
+    pub target_model: String,
+    pub db_path: String,
+    /// Documents copied so far.
+    pub processed: u64,
+    /// Last document id copied, in sorted id order; the job resumes after it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_id: Option<String>,
+    pub total: u64,
+    pub status: ReembedStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub started_at: String,
+    pub updated_at: String,
+}
+
+fn shadow_collection(collection_name: &str) -> String {
+    format!("{}{}", collection_name, SHADOW_SUFFIX)
+}
+
+/// True while a re-embed job is running for the collection.
+pub fn is_reembedding(collection_name: &str) -> bool {
+    RUNNING_JOBS
+        .lock()
+        .map(|jobs| jobs.contains(collection_name))
+        .unwrap_or(false)
+}
+
+/// True while any re-embed job is running.
+pub fn any_reembedding() -> bool {
+    RUNNING_JOBS
+        .lock()
+        .map(|jobs| !jobs.is_empty())
+        .unwrap_or(false)
+}
+
+/// Get app data dir for job state (data/).
+fn get_data_dir(app: &AppHandle) -> Result<PathBuf, ConfidantError> {
+    let base_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| ConfidantError::io(format!("Failed to get app data dir: {}", e)))?;
+    let data_dir = base_dir.join("data");
+    fs::create_dir_all(&data_dir)
+        .map_err(|e| ConfidantError::io(format!("Failed to create data directory: {}", e)))?;
+    Ok(data_dir)
+}
+
+fn load_jobs(app: &AppHandle) -> Result<HashMap<String, ReembedJob>, ConfidantError> {
+    let path = get_data_dir(app)?.join("reembed_jobs.json");
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| ConfidantError::io(format!("Failed to read re-embed jobs: {}", e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| ConfidantError::internal(format!("Failed to parse re-embed jobs: {}", e)))
+}
+
+/// Checkpoint one job (written atomically so a crash never loses the other jobs' progress).
+fn save_job(app: &AppHandle, job: &ReembedJob) -> Result<(), ConfidantError> {
+    let _guard = JOBS_FILE.lock().map_err(|e| ConfidantError::internal(format!("Failed to lock state: {}", e)))?;
+    let mut jobs = load_jobs(app)?;
+    jobs.insert(job.collection.clone(), job.clone());
+    let path = get_data_dir(app)?.join("reembed_jobs.json");
+    let tmp = path.with_extension("json.tmp");
+    let content = serde_json::to_string_pretty(&jobs)
+        .map_err(|e| ConfidantError::internal(format!("Failed to serialize re-embed jobs: {}", e)))?;
+    fs::write(&tmp, content).map_err(|e| ConfidantError::io(format!("Failed to write re-embed jobs: {}", e)))?;
+    fs::rename(&tmp, &path).map_err(|e| ConfidantError::io(format!("Failed to write re-embed jobs: {}", e)))
+}
+
+/// Run a chromadb_helper command and check its status.
+async fn chroma(
+    bundled: Option<(PathBuf, PathBuf)>,
+    command: &str,
+    args: &[&str],
+    stdin: Option<&str>,
+) -> Result<serde_json::Value, ConfidantError> {
+    let result_json = call_python_helper(bundled, command, args, stdin).await?;
+    let result: serde_json::Value = serde_json::from_str(&result_json)
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?;
+    if result["status"].as_str() != Some("success") {
+        return Err(ConfidantError::helper(format!("{} failed", command)).with_details(result.to_string()));
+    }
+    Ok(result)
+}
+
+/// Index in the sorted `ids` of the first document after `last_id` (0 for a new job).
+fn resume_position(ids: &[String], last_id: Option<&str>) -> usize {
+    last_id.map_or(0, |last| ids.partition_point(|id| id.as_str() <= last))
+}
+
+/// Embed one page of documents with the job's target model and upsert it into the shadow collection.
+async fn copy_page(
+    app: &AppHandle,
+    bundled: Option<(PathBuf, PathBuf)>,
+    job: &ReembedJob,
+    shadow: &str,
+    docs: &[serde_json::Value],
+) -> Result<(), ConfidantError> {
+    let texts: Vec<String> = docs
+        .iter()
+        .map(|d| d["text"].as_str().unwrap_or("").to_string())
+        .collect();
+    let embeddings = embed_texts_with_model(app, &job.target_model, &texts).await?;
+    if embeddings.len() != docs.len() {
+        return Err(ConfidantError::helper(format!(
+            "Expected {} embeddings, got {}",
+            docs.len(),
+            embeddings.len()
+        )));
+    }
+    let rows: Vec<serde_json::Value> = docs
+        .iter()
+        .zip(embeddings)
+        .map(|(d, embedding)| {
+            serde_json::json!({
+                "id": d["id"],
+                "text": d["text"],
+                "embedding": embedding,
+                "metadata": if d["metadata"].is_null() { serde_json::json!({}) } else { d["metadata"].clone() },
+            })
+        })
+        .collect();
+    let stdin_data = serde_json::to_string(&rows)
+        .map_err(|e| ConfidantError::internal(format!("Failed to serialize documents: {}", e)))?;
+    // Upsert, so a page repeated after a crash before its checkpoint is harmless
+    let _access = open_store_or_default(app).await?;
+    let _write = lock_collection_write(shadow).await;
+    chroma(bundled, "upsert", &[&job.db_path, shadow], Some(&stdin_data)).await?;
+    Ok(())
+}
+
+/// Copy the remaining pages into the shadow collection, then swap it in. Store access is taken per step,
+/// so backups and other collections' work can run between pages.
+async fn run_job(app: &AppHandle, job: &mut ReembedJob) -> Result<(), ConfidantError> {
+    let bundled = crate::python_bundle::resolve_bundled_python(app);
+    let shadow = shadow_collection(&job.collection);
+    let db_path = job.db_path.clone();
+
+    if job.status == ReembedStatus::Running {
+        {
+            let _access = open_store_or_default(app).await?;
+            let _write = lock_collection_write(&shadow).await;
+            chroma(bundled.clone(), "init", &[&db_path, &shadow, &job.target_model], None).await?;
+        }
+        let listed = {
+            let _access = open_store_or_default(app).await?;
+            let _read = lock_collection_read(&job.collection).await;
+            chroma(bundled.clone(), "list_ids", &[&db_path, &job.collection], None).await?
+        };
+        let mut ids: Vec<String> = serde_json::from_value(listed["ids"].clone())
+            .map_err(|e| ConfidantError::helper(format!("Failed to parse document ids: {}", e)))?;
+        // Chroma has no stable paging order; sorted ids give one that survives a restart
+        ids.sort();
+        job.total = ids.len() as u64;
+        let start = resume_position(&ids, job.last_id.as_deref());
+        let mut done = start;
+
+        for page_ids in ids[start..].chunks(PAGE_SIZE) {
+            let ids_json = serde_json::to_string(page_ids)
+                .map_err(|e| ConfidantError::internal(format!("Failed to serialize ids: {}", e)))?;
+            let page = {
+                let _access = open_store_or_default(app).await?;
+                let _read = lock_collection_read(&job.collection).await;
+                chroma(bundled.clone(), "get_documents", &[&db_path, &job.collection], Some(&ids_json)).await?
+            };
+            let docs = page["documents"].as_array().cloned().unwrap_or_default();
+            if !docs.is_empty() {
+                copy_page(app, bundled.clone(), job, &shadow, &docs).await?;
+            }
+
+            done += page_ids.len();
+            job.processed = done as u64;
+            job.last_id = page_ids.last().cloned();
+            job.updated_at = chrono::Utc::now().to_rfc3339();
+            save_job(app, job)?;
+            let _ = app.emit(REEMBED_PROGRESS_EVENT, job.clone());
+        }
+        job.status = ReembedStatus::Swapping;
+        job.updated_at = chrono::Utc::now().to_rfc3339();
+        save_job(app, job)?;
+    }
+
+    // Idempotent: finishes a swap interrupted by a restart
+    {
+        let _access = open_store_or_default(app).await?;
+        let _write = lock_collection_write(&job.collection).await;
+        let _write_shadow = lock_collection_write(&shadow).await;
+        chroma(bundled, "swap_collections", &[&db_path, &job.collection, &shadow], None).await?;
+    }
+    record_collection_model(&job.collection, &job.target_model);
+    job.status = ReembedStatus::Completed;
+    job.updated_at = chrono::Utc::now().to_rfc3339();
+    save_job(app, job)
+}
+
+/// Run a job in the background. The caller must have added the collection to RUNNING_JOBS.
+fn spawn_job(app: AppHandle, mut job: ReembedJob) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = run_job(&app, &mut job).await {
+            eprintln!("[Confidant] Re-embed of {} failed: {}", job.collection, e);
+            job.status = ReembedStatus::Failed;
+            job.error = Some(e.to_string());
+            job.updated_at = chrono::Utc::now().to_rfc3339();
+            let _ = save_job(&app, &job);
+        }
+        if let Ok(mut running) = RUNNING_JOBS.lock() {
+            running.remove(&job.collection);
+        }
+        let _ = app.emit(REEMBED_PROGRESS_EVENT, job.clone());
+    });
+}
+
+/// Resume jobs interrupted by a restart. Called once at startup.
+pub fn resume_reembed_jobs(app: &AppHandle) {
+    let jobs = match load_jobs(app) {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            eprintln!("[Confidant] Could not load re-embed jobs: {}", e);
+            return;
+        }
+    };
+    for job in jobs.into_values() {
+        if !matches!(job.status, ReembedStatus::Running | ReembedStatus::Swapping) {
+            continue;
+        }
+        if let Ok(mut running) = RUNNING_JOBS.lock() {
+            if !running.insert(job.collection.clone()) {
+                continue;
+            }
+        }
+        eprintln!("[Confidant] Resuming re-embed of {} at {}/{}", job.collection, job.processed, job.total);
+        spawn_job(app.clone(), job);
+    }
+}
+
+/// Rebuild a collection's vectors from its stored text with `model` (default: the active embedding model).
+/// Returns immediately; progress is reported with `reembed-progress` events and get_reembed_status.
+/// Calling it again for an unfinished job with the same model resumes it; another model starts over.
+#[tauri::command]
+pub async fn reembed_collection(
+    app: AppHandle,
+    collection_name: String,
+    model: Option<String>,
+) -> Result<ReembedJob, ConfidantError> {
+    if collection_name.ends_with(SHADOW_SUFFIX) {
+        return Err(ConfidantError::validation(format!("{} is a re-embed working collection", collection_name)));
+    }
+    let target_model = model
+        .filter(|m| !m.trim().is_empty())
+        .unwrap_or_else(active_embedding_model_id);
+
+    {
+        let mut running = RUNNING_JOBS.lock()
+            .map_err(|e| ConfidantError::internal(format!("Failed to lock state: {}", e)))?;
+        if !running.insert(collection_name.clone()) {
+            return Err(ConfidantError::validation(format!(
+                "Collection {} is already being re-embedded",
+                collection_name
+            )));
+        }
+    }
+
+    let prepared = prepare_job(&app, &collection_name, &target_model).await;
+    match prepared {
+        Ok(job) => {
+            spawn_job(app, job.clone());
+            Ok(job)
+        }
+        Err(e) => {
+            if let Ok(mut running) = RUNNING_JOBS.lock() {
+                running.remove(&collection_name);
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Collections whose vectors came from another model than the active one (see start_reembeds).
+#[derive(Debug, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct StaleCollections {
+    /// Collections now being re-embedded.
+    pub reembedding: Vec<String>,
+    /// Collections whose re-embed could not be started, with the reason.
+    pub failed: Vec<String>,
+}
+
+/// Collections built with another model than `model`. Collections without a recorded model were built with the
+/// default model.
+pub(crate) async fn list_stale_collections(app: &AppHandle, model: &str) -> Result<Vec<String>, ConfidantError> {
+    let bundled = crate::python_bundle::resolve_bundled_python(app);
+    let result_json = {
+        let store = open_store_or_default(app).await?;
+        call_python_helper(bundled, "list_collections", &[&store.db_path], None).await?
+    };
+    let result: serde_json::Value = serde_json::from_str(&result_json)
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?;
+    if result["status"].as_str() != Some("success") {
+        return Err(ConfidantError::helper("Failed to list collections").with_details(result.to_string()));
+    }
+
+    Ok(result["collections"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|entry| {
+            let name = entry["name"].as_str().unwrap_or("").to_string();
+            let built_with = entry["embedding_model"].as_str().unwrap_or(EMBEDDING_MODEL_ID);
+            let stale = !name.is_empty() && !name.ends_with(SHADOW_SUFFIX) && !same_embedding_model(built_with, model);
+            stale.then_some(name)
+        })
+        .collect())
+}
+
+/// Start a re-embed with `model` for each collection (after the active model changed). A collection whose job
+/// cannot start is reported in `failed` instead of stopping the others.
+pub(crate) async fn start_reembeds(app: &AppHandle, collections: Vec<String>, model: &str) -> StaleCollections {
+    let mut stale = StaleCollections::default();
+    for name in collections {
+        match reembed_collection(app.clone(), name.clone(), Some(model.to_string())).await {
+            Ok(_) => stale.reembedding.push(name),
+            Err(e) => stale.failed.push(format!("{}: {}", name, e)),
+        }
+    }
+    stale
+}
+
+/// Resume the saved job for the collection if it targets the same model, else start a new one.
+async fn prepare_job(app: &AppHandle, collection_name: &str, target_model: &str) -> Result<ReembedJob, ConfidantError> {
+    let existing = load_jobs(app)?.remove(collection_name);
+    if let Some(job) = existing.filter(|j| {
+        matches!(j.status, ReembedStatus::Running | ReembedStatus::Swapping | ReembedStatus::Failed)
+            && j.target_model == target_model
+            && j.processed > 0
+    }) {
+        let mut job = job;
+        if job.status == ReembedStatus::Failed {
+            job.status = ReembedStatus::Running;
+            job.error = None;
+        }
+        return Ok(job);
+    }
+
+    // Open the source collection (records its model and sets the db path if not set yet)
+    crate::vector_store::initialize_vector_store(app.clone(), collection_name.to_string(), None).await?;
+    let store = open_store_or_default(app).await?;
+    let db_path = store.db_path.clone();
+
+    // Start from an empty shadow collection
+    let bundled = crate::python_bundle::resolve_bundled_python(app);
+    let shadow = shadow_collection(collection_name);
+    {
+        let _write = lock_collection_write(&shadow).await;
+        chroma(bundled, "delete_collection", &[&db_path, &shadow], None).await.ok();
+    }
+    drop(store);
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let job = ReembedJob {
+        collection: collection_name.to_string(),
+        target_model: target_model.to_string(),
+        db_path,
+        processed: 0,
+        last_id: None,
+        total: 0,
+        status: ReembedStatus::Running,
+        error: None,
+        started_at: now.clone(),
+        updated_at: now,
+    };
+    save_job(app, &job)?;
+    Ok(job)
+}
+
+/// Last known state of the collection's re-embed job, if one was ever started.
+#[tauri::command]
+pub async fn get_reembed_status(app: AppHandle, collection_name: String) -> Result<Option<ReembedJob>, ConfidantError> {
+    Ok(load_jobs(&app)?.remove(&collection_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resume_position_skips_ids_up_to_the_cursor() {
+        let ids: Vec<String> = ["a", "b", "d", "e"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(resume_position(&ids, None), 0);
+        assert_eq!(resume_position(&ids, Some("b")), 2);
+        // A cursor id that is gone (or never existed) still resumes at the next id after it
+        assert_eq!(resume_position(&ids, Some("c")), 2);
+        assert_eq!(resume_position(&ids, Some("e")), 4);
+    }
+}
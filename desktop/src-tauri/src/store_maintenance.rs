@@ -0,0 +1,388 @@
+// Store Maintenance - Backup, restore and integrity check of the ChromaDB directory
+//
+// Backups are zstd-compressed tar archives of data/chromadb, written to data/backups as
+// chromadb-YYYYMMDD-HHMMSSmmm.tar.zst. All operations take exclusive access to the vector store so no command touches
+// ChromaDB while its files are copied, replaced or checked; archive I/O runs on the blocking pool.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::embeddings::{active_embedding_model_id, embed_texts_with_model};
+use crate::error::ConfidantError;
+use crate::vector_store::{call_python_helper, forget_collection_models, open_store_exclusive};
+
+/// ChromaDB's sqlite file; every valid backup has it at the archive root.
+const SQLITE_FILE: &str = "chroma.sqlite3";
+
+/// Rows re-embedded per helper call during repair.
+const REPAIR_BATCH_SIZE: usize = 32;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub path: String,
+    pub size_bytes: u64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreReport {
+    pub restored_from: String,
+    /// Backup of the store as it was before the restore, in case the restore has to be undone.
+    pub previous_store_backup: BackupInfo,
+}
+
+/// Row reported by the helper's integrity check.
+#[derive(Debug, Deserialize, Clone)]
+struct ProblemRow {
+    id: String,
+    text: String,
+    metadata: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct HelperCollectionCheck {
+    name: String,
+    opened: bool,
+    error: Option<String>,
+    count: u64,
+    vector_count: u64,
+    dimension: Option<u64>,
+    embedding_model: Option<String>,
+    missing_vectors: Vec<ProblemRow>,
+    wrong_dimension: Vec<ProblemRow>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionCheck {
+    pub name: String,
+    pub opened: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Documents according to the collection.
+    pub count: u64,
+    /// Documents with a vector in the index.
+    pub vector_count: u64,
+    pub dimension: Option<u64>,
+    /// Ids stored without a vector in the index.
+    pub dangling_ids: Vec<String>,
+    /// Ids whose vector length differs from the collection's dimension.
+    pub wrong_dimension_ids: Vec<String>,
+    /// Problem rows fixed by re-embedding their stored text (or removed when they had no text).
+    pub repaired: usize,
+    pub healthy: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyReport {
+    pub sqlite_ok: bool,
+    pub sqlite_message: String,
+    pub collections: Vec<CollectionCheck>,
+    /// No problems left (after repair, when requested).
+    pub healthy: bool,
+}
+
+/// Get app data dir for backups (data/backups/).
+fn get_backup_dir(app: &AppHandle) -> Result<PathBuf, ConfidantError> {
+    let base_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| ConfidantError::io(format!("Failed to get app data dir: {}", e)))?;
+    let backup_dir = base_dir.join("data").join("backups");
+    fs::create_dir_all(&backup_dir)
+        .map_err(|e| ConfidantError::io(format!("Failed to create backup directory: {}", e)))?;
+    Ok(backup_dir)
+}
+
+/// Sibling path of the store directory, e.g. `chromadb.restore`.
+fn sibling(db_path: &Path, suffix: &str) -> PathBuf {
+    let mut name = db_path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    db_path.with_file_name(name)
+}
+
+/// Archive the store directory into `dest_dir` (written to a temp file, then renamed).
+fn write_backup(db_path: &Path, dest_dir: &Path) -> Result<BackupInfo, ConfidantError> {
+    fs::create_dir_all(dest_dir).map_err(|e| ConfidantError::io(format!("Failed to create backup directory: {}", e)))?;
+    let created_at = chrono::Utc::now();
+    let stamp = created_at.format("%Y%m%d-%H%M%S%3f").to_string();
+    // The temp file is created exclusively, so two backups in the same millisecond get different names
+    let mut attempt = 0;
+    let (path, tmp, file) = loop {
+        let name = match attempt {
+            0 => format!("chromadb-{}.tar.zst", stamp),
+            n => format!("chromadb-{}-{}.tar.zst", stamp, n),
+        };
+        let path = dest_dir.join(name);
+        let tmp = path.with_extension("zst.tmp");
+        if !path.exists() {
+            match fs::File::create_new(&tmp) {
+                Ok(file) => break (path, tmp, file),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(ConfidantError::io(format!("Failed to create backup: {}", e))),
+            }
+        }
+        attempt += 1;
+    };
+
+    let encoder = zstd::Encoder::new(file, 3)
+        .map_err(|e| ConfidantError::io(format!("Failed to create backup: {}", e)))?;
+    let mut builder = tar::Builder::new(encoder);
+    builder
+        .append_dir_all(".", db_path)
+        .map_err(|e| ConfidantError::io(format!("Failed to archive vector store: {}", e)))?;
+    let encoder = builder.into_inner().map_err(|e| ConfidantError::io(format!("Failed to write backup: {}", e)))?;
+    encoder.finish().map_err(|e| ConfidantError::io(format!("Failed to write backup: {}", e)))?;
+    fs::rename(&tmp, &path).map_err(|e| ConfidantError::io(format!("Failed to write backup: {}", e)))?;
+
+    let size_bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    Ok(BackupInfo {
+        path: path.to_string_lossy().to_string(),
+        size_bytes,
+        created_at: created_at.to_rfc3339(),
+    })
+}
+
+/// Extract a backup into `dest` (which must not exist). Entries escaping `dest` are rejected by tar.
+fn extract_backup(archive: &Path, dest: &Path) -> Result<(), ConfidantError> {
+    let file = fs::File::open(archive).map_err(|e| ConfidantError::io(format!("Failed to open backup: {}", e)))?;
+    let decoder = zstd::Decoder::new(file)
+        .map_err(|e| ConfidantError::validation(format!("Not a vector store backup: {}", e)))?;
+    let mut tar = tar::Archive::new(decoder);
+    fs::create_dir_all(dest).map_err(|e| ConfidantError::io(format!("Failed to create restore directory: {}", e)))?;
+    let entries = tar.entries().map_err(|e| ConfidantError::validation(format!("Not a vector store backup: {}", e)))?;
+    for entry in entries {
+        let mut entry = entry.map_err(|e| ConfidantError::validation(format!("Backup is corrupted: {}", e)))?;
+        let unpacked = entry.unpack_in(dest)
+            .map_err(|e| ConfidantError::validation(format!("Backup is corrupted: {}", e)))?;
+        if !unpacked {
+            return Err(ConfidantError::validation("Backup contains paths outside the store directory"));
+        }
+    }
+    if !dest.join(SQLITE_FILE).is_file() {
+        return Err(ConfidantError::validation(format!("Not a vector store backup: {} is missing", SQLITE_FILE)));
+    }
+    Ok(())
+}
+
+/// Swap the store directory for the extracted backup, keeping a backup of the current store first.
+fn replace_store(db_path: &Path, archive: &Path, backup_dir: &Path) -> Result<RestoreReport, ConfidantError> {
+    let staging = sibling(db_path, ".restore");
+    if staging.exists() {
+        fs::remove_dir_all(&staging)
+            .map_err(|e| ConfidantError::io(format!("Failed to clear restore directory: {}", e)))?;
+    }
+    if let Err(e) = extract_backup(archive, &staging) {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    let previous_store_backup = write_backup(db_path, backup_dir)?;
+    let retired = sibling(db_path, ".replaced");
+    if retired.exists() {
+        fs::remove_dir_all(&retired).map_err(|e| ConfidantError::io(format!("Failed to clear old store: {}", e)))?;
+    }
+    if db_path.exists() {
+        fs::rename(db_path, &retired)
+            .map_err(|e| ConfidantError::io(format!("Failed to move current store aside: {}", e)))?;
+    }
+    if let Err(e) = fs::rename(&staging, db_path) {
+        // Put the current store back
+        let _ = fs::rename(&retired, db_path);
+        return Err(ConfidantError::io(format!("Failed to restore vector store: {}", e)));
+    }
+    let _ = fs::remove_dir_all(&retired);
+
+    Ok(RestoreReport {
+        restored_from: archive.to_string_lossy().to_string(),
+        previous_store_backup,
+    })
+}
+
+/// Archive the vector store to `destination_dir` (default data/backups) as a timestamped .tar.zst.
+#[tauri::command]
+pub async fn backup_vector_store(
+    app: AppHandle,
+    destination_dir: Option<String>,
+) -> Result<BackupInfo, ConfidantError> {
+    let dest_dir = match destination_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => get_backup_dir(&app)?,
+    };
+    let (_exclusive, db_path) = open_store_exclusive(&app).await?;
+    tauri::async_runtime::spawn_blocking(move || {
+        if !db_path.join(SQLITE_FILE).exists() {
+            return Err(ConfidantError::validation("Vector store is empty; nothing to back up"));
+        }
+        write_backup(&db_path, &dest_dir)
+    })
+    .await
+    .map_err(|e| ConfidantError::internal(format!("Backup task failed: {}", e)))?
+}
+
+/// Replace the vector store with a backup. The archive is fully extracted and checked before the current
+/// store is touched, and the current store is backed up first.
+#[tauri::command]
+pub async fn restore_vector_store(app: AppHandle, archive_path: String) -> Result<RestoreReport, ConfidantError> {
+    if crate::reembed::any_reembedding() {
+        return Err(ConfidantError::validation("A re-embed job is running; restore when it finishes."));
+    }
+    let archive = PathBuf::from(&archive_path);
+    if !archive.is_file() {
+        return Err(ConfidantError::not_found(format!("Backup not found: {}", archive_path)));
+    }
+    let backup_dir = get_backup_dir(&app)?;
+
+    let (_exclusive, db_path) = open_store_exclusive(&app).await?;
+    let report = tauri::async_runtime::spawn_blocking(move || replace_store(&db_path, &archive, &backup_dir))
+        .await
+        .map_err(|e| ConfidantError::internal(format!("Restore task failed: {}", e)))??;
+
+    forget_collection_models();
+    Ok(report)
+}
+
+/// Re-embed problem rows from their stored text with the collection's model and upsert them; rows without
+/// text cannot be rebuilt and are deleted. Returns how many rows were fixed.
+async fn repair_collection(
+    app: &AppHandle,
+    bundled: Option<(PathBuf, PathBuf)>,
+    db_path: &str,
+    check: &HelperCollectionCheck,
+) -> Result<usize, ConfidantError> {
+    let rows: Vec<&ProblemRow> = check.missing_vectors.iter().chain(&check.wrong_dimension).collect();
+    let (with_text, without_text): (Vec<&ProblemRow>, Vec<&ProblemRow>) =
+        rows.into_iter().partition(|r| !r.text.trim().is_empty());
+    let model = check.embedding_model.clone().unwrap_or_else(active_embedding_model_id);
+    let mut repaired = 0;
+
+    for batch in with_text.chunks(REPAIR_BATCH_SIZE) {
+        let texts: Vec<String> = batch.iter().map(|r| r.text.clone()).collect();
+        let embeddings = embed_texts_with_model(app, &model, &texts).await?;
+        if let (Some(dim), Some(first)) = (check.dimension, embeddings.first()) {
+            if first.len() as u64 != dim {
+                return Err(ConfidantError::validation(format!(
+                    "{} produces {}-dimensional vectors but {} has {}; re-embed the collection instead",
+                    model, first.len(), check.name, dim
+                )));
+            }
+        }
+        let docs: Vec<serde_json::Value> = batch
+            .iter()
+            .zip(embeddings)
+            .map(|(r, embedding)| {
+                serde_json::json!({
+                    "id": r.id,
+                    "text": r.text,
+                    "embedding": embedding,
+                    "metadata": r.metadata,
+                })
+            })
+            .collect();
+        let stdin_data = serde_json::to_string(&docs)
+            .map_err(|e| ConfidantError::internal(format!("Failed to serialize documents: {}", e)))?;
+        let result_json = call_python_helper(bundled.clone(), "upsert", &[db_path, &check.name], Some(&stdin_data)).await?;
+        let result: serde_json::Value = serde_json::from_str(&result_json)
+            .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?;
+        if result["status"].as_str() != Some("success") {
+            return Err(ConfidantError::helper("Failed to repair documents").with_details(result.to_string()));
+        }
+        repaired += batch.len();
+    }
+
+    if !without_text.is_empty() {
+        let ids: Vec<&str> = without_text.iter().map(|r| r.id.as_str()).collect();
+        let ids_json = serde_json::to_string(&ids)
+            .map_err(|e| ConfidantError::internal(format!("Failed to serialize ids: {}", e)))?;
+        let result_json = call_python_helper(bundled, "delete_documents", &[db_path, &check.name], Some(&ids_json)).await?;
+        let result: serde_json::Value = serde_json::from_str(&result_json)
+            .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?;
+        if result["status"].as_str() != Some("success") {
+            return Err(ConfidantError::helper("Failed to remove dangling documents").with_details(result.to_string()));
+        }
+        repaired += without_text.len();
+    }
+    Ok(repaired)
+}
+
+/// Check every collection: it opens, its count matches the vectors in the index, no id is stored without a
+/// vector, and all vectors share one dimension; plus sqlite's own integrity check. With `repair`, problem rows
+/// are rebuilt from their stored text. A collection that does not open or a failed sqlite check can only be
+/// fixed by restoring a backup.
+#[tauri::command]
+pub async fn verify_vector_store(app: AppHandle, repair: Option<bool>) -> Result<VerifyReport, ConfidantError> {
+    let repair = repair.unwrap_or(false);
+    if repair && crate::reembed::any_reembedding() {
+        return Err(ConfidantError::validation("A re-embed job is running; repair when it finishes."));
+    }
+    let bundled = crate::python_bundle::resolve_bundled_python(&app);
+
+    let (_exclusive, db_path) = open_store_exclusive(&app).await?;
+    let db_path_str = db_path.to_str().ok_or_else(|| ConfidantError::validation("Invalid database path"))?;
+    let result_json = call_python_helper(bundled.clone(), "verify", &[db_path_str], None).await?;
+    let result: serde_json::Value = serde_json::from_str(&result_json)
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?;
+    if result["status"].as_str() != Some("success") {
+        return Err(ConfidantError::helper("Vector store check failed").with_details(result.to_string()));
+    }
+    let checks: Vec<HelperCollectionCheck> = serde_json::from_value(result["collections"].clone())
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse check results: {}", e)))?;
+    let sqlite_ok = result["sqlite_ok"].as_bool().unwrap_or(false);
+
+    let mut collections = Vec::with_capacity(checks.len());
+    for check in checks {
+        let problems = check.missing_vectors.len() + check.wrong_dimension.len();
+        let mut repaired = 0;
+        let mut error = check.error.clone();
+        if repair && sqlite_ok && check.opened && problems > 0 {
+            match repair_collection(&app, bundled.clone(), db_path_str, &check).await {
+                Ok(n) => repaired = n,
+                Err(e) => error = Some(e.to_string()),
+            }
+        }
+        let consistent = check.count == check.vector_count + check.missing_vectors.len() as u64;
+        collections.push(CollectionCheck {
+            healthy: check.opened && error.is_none() && consistent && repaired == problems,
+            name: check.name,
+            opened: check.opened,
+            error,
+            count: check.count,
+            vector_count: check.vector_count,
+            dimension: check.dimension,
+            dangling_ids: check.missing_vectors.into_iter().map(|r| r.id).collect(),
+            wrong_dimension_ids: check.wrong_dimension.into_iter().map(|r| r.id).collect(),
+            repaired,
+        });
+    }
+
+    Ok(VerifyReport {
+        healthy: sqlite_ok && collections.iter().all(|c| c.healthy),
+        sqlite_ok,
+        sqlite_message: result["sqlite_message"].as_str().unwrap_or("").to_string(),
+        collections,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn back_to_back_backups_get_distinct_names() {
+        let root = std::env::temp_dir().join(format!("confidant-backup-test-{}", std::process::id()));
+        let (store, backups) = (root.join("chromadb"), root.join("backups"));
+        fs::create_dir_all(&store).unwrap();
+        fs::write(store.join(SQLITE_FILE), b"test").unwrap();
+
+        let paths: Vec<String> = (0..3).map(|_| write_backup(&store, &backups).unwrap().path).collect();
+        let _ = fs::remove_dir_all(&root);
+        assert_ne!(paths[0], paths[1]);
+        assert_ne!(paths[1], paths[2]);
+        assert_ne!(paths[0], paths[2]);
+    }
+}
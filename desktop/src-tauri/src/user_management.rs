@@ -8,6 +8,8 @@ use chrono::Utc;
 use tauri::{AppHandle, Manager};
 use uuid::Uuid;
 
+use crate::error::ConfidantError;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct User {
     pub id: String,
@@ -39,40 +41,40 @@ pub struct ChatMessage {
 
 /// Get app data directory. Uses Tauri's writable app data dir (e.g. ~/Library/Application Support/com.confidant)
 /// so the packaged app can write when run from DMG; falls back to project-relative path for dev.
-fn get_app_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+fn get_app_data_dir(app: &AppHandle) -> Result<PathBuf, ConfidantError> {
     let base_dir = app
         .path()
         .app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+        .map_err(|e| ConfidantError::io(format!("Failed to get app data dir: {}", e)))?;
     let data_dir = base_dir.join("data");
     fs::create_dir_all(&data_dir)
-        .map_err(|e| format!("Failed to create data directory: {}", e))?;
+        .map_err(|e| ConfidantError::io(format!("Failed to create data directory: {}", e)))?;
     Ok(data_dir)
 }
 
 /// Get users file path
-fn get_users_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+fn get_users_file_path(app: &AppHandle) -> Result<PathBuf, ConfidantError> {
     let data_dir = get_app_data_dir(app)?;
     Ok(data_dir.join("users.json"))
 }
 
 /// Get current user file path
-fn get_current_user_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+fn get_current_user_file_path(app: &AppHandle) -> Result<PathBuf, ConfidantError> {
     let data_dir = get_app_data_dir(app)?;
     Ok(data_dir.join("current_user.json"))
 }
 
 /// Get user directory path
-fn get_user_dir_path(app: &AppHandle, user_id: &str) -> Result<PathBuf, String> {
+fn get_user_dir_path(app: &AppHandle, user_id: &str) -> Result<PathBuf, ConfidantError> {
     let data_dir = get_app_data_dir(app)?;
     let users_dir = data_dir.join("users");
     fs::create_dir_all(&users_dir)
-        .map_err(|e| format!("Failed to create users directory: {}", e))?;
+        .map_err(|e| ConfidantError::io(format!("Failed to create users directory: {}", e)))?;
     Ok(users_dir.join(user_id))
 }
 
 /// Path to single chat file per user.
-fn get_chat_history_path(app: &AppHandle, user_id: &str) -> Result<PathBuf, String> {
+fn get_chat_history_path(app: &AppHandle, user_id: &str) -> Result<PathBuf, ConfidantError> {
     let user_dir = get_user_dir_path(app, user_id)?;
     Ok(user_dir.join("chats.json"))
 }
@@ -90,38 +92,38 @@ struct ChatListLegacy {
     chats: Vec<ChatSummaryLegacy>,
 }
 
-fn migrate_multi_chat_to_single_if_needed(app: &AppHandle, user_id: &str) -> Result<(), String> {
+fn migrate_multi_chat_to_single_if_needed(app: &AppHandle, user_id: &str) -> Result<(), ConfidantError> {
     let user_dir = get_user_dir_path(app, user_id)?;
     let list_path = user_dir.join("chat_list.json");
     if !list_path.exists() {
         return Ok(());
     }
     let content = fs::read_to_string(&list_path)
-        .map_err(|e| format!("Failed to read chat list: {}", e))?;
+        .map_err(|e| ConfidantError::io(format!("Failed to read chat list: {}", e)))?;
     let list: ChatListLegacy = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse chat list: {}", e))?;
+        .map_err(|e| ConfidantError::internal(format!("Failed to parse chat list: {}", e)))?;
     let mut chats = list.chats;
     chats.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
-    let latest_id = chats.first().map(|c| c.id.as_str()).ok_or("Empty chat list")?;
+    let latest_id = chats.first().map(|c| c.id.as_str()).ok_or_else(|| ConfidantError::io("Empty chat list"))?;
     let msg_path = user_dir.join("chats").join(format!("{}.json", latest_id));
     if !msg_path.exists() {
         fs::remove_file(&list_path).ok();
         return Ok(());
     }
     let msg_content = fs::read_to_string(&msg_path)
-        .map_err(|e| format!("Failed to read chat messages: {}", e))?;
+        .map_err(|e| ConfidantError::io(format!("Failed to read chat messages: {}", e)))?;
     let messages: Vec<ChatMessage> = serde_json::from_str(&msg_content)
-        .map_err(|e| format!("Failed to parse chat messages: {}", e))?;
+        .map_err(|e| ConfidantError::internal(format!("Failed to parse chat messages: {}", e)))?;
     let chat_file = get_chat_history_path(app, user_id)?;
     if let Some(p) = chat_file.parent() {
-        fs::create_dir_all(p).map_err(|e| format!("Failed to create user dir: {}", e))?;
+        fs::create_dir_all(p).map_err(|e| ConfidantError::io(format!("Failed to create user dir: {}", e)))?;
     }
     let out = serde_json::to_string_pretty(&messages)
-        .map_err(|e| format!("Failed to serialize messages: {}", e))?;
+        .map_err(|e| ConfidantError::internal(format!("Failed to serialize messages: {}", e)))?;
     fs::write(&chat_file, out)
-        .map_err(|e| format!("Failed to write chats.json: {}", e))?;
+        .map_err(|e| ConfidantError::io(format!("Failed to write chats.json: {}", e)))?;
     fs::remove_file(&list_path)
-        .map_err(|e| format!("Failed to remove chat list: {}", e))?;
+        .map_err(|e| ConfidantError::io(format!("Failed to remove chat list: {}", e)))?;
     let chats_dir = user_dir.join("chats");
     if chats_dir.exists() {
         if let Ok(entries) = fs::read_dir(&chats_dir) {
@@ -135,7 +137,7 @@ fn migrate_multi_chat_to_single_if_needed(app: &AppHandle, user_id: &str) -> Res
 }
 
 /// Load users from file
-fn load_users(app: &AppHandle) -> Result<Vec<User>, String> {
+pub(crate) fn load_users(app: &AppHandle) -> Result<Vec<User>, ConfidantError> {
     let users_file = get_users_file_path(app)?;
     
     if !users_file.exists() {
@@ -143,10 +145,10 @@ fn load_users(app: &AppHandle) -> Result<Vec<User>, String> {
     }
     
     let content = fs::read_to_string(&users_file)
-        .map_err(|e| format!("Failed to read users file: {}", e))?;
+        .map_err(|e| ConfidantError::io(format!("Failed to read users file: {}", e)))?;
     
     let users: Vec<User> = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse users file: {}", e))?;
+        .map_err(|e| ConfidantError::internal(format!("Failed to parse users file: {}", e)))?;
     
     // Filter out users with empty password_hash (old format) - they need to be recreated
     // This handles migration from old users.json format
@@ -173,7 +175,7 @@ fn load_users(app: &AppHandle) -> Result<Vec<User>, String> {
 }
 
 /// Save users to file
-fn save_users(app: &AppHandle, users: &[User]) -> Result<(), String> {
+fn save_users(app: &AppHandle, users: &[User]) -> Result<(), ConfidantError> {
     let users_file = get_users_file_path(app)?;
     
     #[cfg(debug_assertions)]
@@ -183,7 +185,7 @@ fn save_users(app: &AppHandle, users: &[User]) -> Result<(), String> {
         .map_err(|e| {
             #[cfg(debug_assertions)]
             eprintln!("[User Management] Serialization failed: {}", e);
-            format!("Failed to serialize users: {}", e)
+            ConfidantError::internal(format!("Failed to serialize users: {}", e))
         })?;
     
     #[cfg(debug_assertions)]
@@ -193,7 +195,7 @@ fn save_users(app: &AppHandle, users: &[User]) -> Result<(), String> {
         .map_err(|e| {
             #[cfg(debug_assertions)]
             eprintln!("[User Management] File write failed: {} (path: {:?})", e, users_file);
-            format!("Failed to write users file: {}", e)
+            ConfidantError::io(format!("Failed to write users file: {}", e))
         })?;
     
     #[cfg(debug_assertions)]
@@ -204,7 +206,7 @@ fn save_users(app: &AppHandle, users: &[User]) -> Result<(), String> {
 
 /// Get all users (public info only, no password hashes)
 #[tauri::command]
-pub async fn get_users(app: AppHandle) -> Result<Vec<UserPublic>, String> {
+pub async fn get_users(app: AppHandle) -> Result<Vec<UserPublic>, ConfidantError> {
     let users = load_users(&app)?;
     
     Ok(users.into_iter().map(|u| UserPublic {
@@ -216,7 +218,7 @@ pub async fn get_users(app: AppHandle) -> Result<Vec<UserPublic>, String> {
 
 /// Create a new user
 #[tauri::command]
-pub async fn create_user(app: AppHandle, name: String, password: String) -> Result<UserPublic, String> {
+pub async fn create_user(app: AppHandle, name: String, password: String) -> Result<UserPublic, ConfidantError> {
     #[cfg(debug_assertions)]
     eprintln!("[User Management] Creating user: name='{}', password_len={}", name, password.len());
     
@@ -225,19 +227,19 @@ pub async fn create_user(app: AppHandle, name: String, password: String) -> Resu
     if name.is_empty() {
         #[cfg(debug_assertions)]
         eprintln!("[User Management] Validation failed: name is empty");
-        return Err("User name cannot be empty".to_string());
+        return Err(ConfidantError::validation("User name cannot be empty"));
     }
     if name.len() > 50 {
         #[cfg(debug_assertions)]
         eprintln!("[User Management] Validation failed: name too long ({})", name.len());
-        return Err("User name is too long (max 50 characters)".to_string());
+        return Err(ConfidantError::validation("User name is too long (max 50 characters)"));
     }
     
     // Validate password
     if password.len() < 4 {
         #[cfg(debug_assertions)]
         eprintln!("[User Management] Validation failed: password too short ({})", password.len());
-        return Err("Password must be at least 4 characters long".to_string());
+        return Err(ConfidantError::validation("Password must be at least 4 characters long"));
     }
     
     // Load existing users
@@ -254,7 +256,7 @@ pub async fn create_user(app: AppHandle, name: String, password: String) -> Resu
     if users.iter().any(|u| u.name.eq_ignore_ascii_case(name)) {
         #[cfg(debug_assertions)]
         eprintln!("[User Management] Validation failed: duplicate name '{}'", name);
-        return Err("A user with this name already exists".to_string());
+        return Err(ConfidantError::validation("A user with this name already exists"));
     }
     
     // Hash password
@@ -264,7 +266,7 @@ pub async fn create_user(app: AppHandle, name: String, password: String) -> Resu
         .map_err(|e| {
             #[cfg(debug_assertions)]
             eprintln!("[User Management] Password hashing failed: {}", e);
-            format!("Failed to hash password: {}", e)
+            ConfidantError::internal(format!("Failed to hash password: {}", e))
         })?;
     
     // Create new user
@@ -302,20 +304,20 @@ pub async fn create_user(app: AppHandle, name: String, password: String) -> Resu
 
 /// Verify password for a user
 #[tauri::command]
-pub async fn verify_password(app: AppHandle, user_id: String, password: String) -> Result<bool, String> {
+pub async fn verify_password(app: AppHandle, user_id: String, password: String) -> Result<bool, ConfidantError> {
     let users = load_users(&app)?;
     
     let user = users.iter()
         .find(|u| u.id == user_id)
-        .ok_or_else(|| "User not found".to_string())?;
+        .ok_or_else(|| ConfidantError::not_found("User not found"))?;
     
     verify(&password, &user.password_hash)
-        .map_err(|e| format!("Failed to verify password: {}", e))
+        .map_err(|e| ConfidantError::internal(format!("Failed to verify password: {}", e)))
 }
 
 /// Get current logged-in user
 #[tauri::command]
-pub async fn get_current_user(app: AppHandle) -> Result<Option<String>, String> {
+pub async fn get_current_user(app: AppHandle) -> Result<Option<String>, ConfidantError> {
     let current_user_file = get_current_user_file_path(&app)?;
     
     if !current_user_file.exists() {
@@ -323,10 +325,10 @@ pub async fn get_current_user(app: AppHandle) -> Result<Option<String>, String>
     }
     
     let content = fs::read_to_string(&current_user_file)
-        .map_err(|e| format!("Failed to read current user file: {}", e))?;
+        .map_err(|e| ConfidantError::io(format!("Failed to read current user file: {}", e)))?;
     
     let user_id: String = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse current user file: {}", e))?;
+        .map_err(|e| ConfidantError::internal(format!("Failed to parse current user file: {}", e)))?;
     
     // Verify user still exists
     let users = load_users(&app)?;
@@ -340,37 +342,37 @@ pub async fn get_current_user(app: AppHandle) -> Result<Option<String>, String>
 }
 
 /// Clear current user file (sync). Call on app exit so next launch shows user selection.
-pub fn clear_current_user_on_exit(app: &AppHandle) -> Result<(), String> {
+pub fn clear_current_user_on_exit(app: &AppHandle) -> Result<(), ConfidantError> {
     let current_user_file = get_current_user_file_path(app)?;
     if current_user_file.exists() {
         fs::remove_file(&current_user_file)
-            .map_err(|e| format!("Failed to remove current user file: {}", e))?;
+            .map_err(|e| ConfidantError::io(format!("Failed to remove current user file: {}", e)))?;
     }
     Ok(())
 }
 
 /// Set current logged-in user (None for guest/logout)
 #[tauri::command]
-pub async fn set_current_user(app: AppHandle, user_id: Option<String>) -> Result<(), String> {
+pub async fn set_current_user(app: AppHandle, user_id: Option<String>) -> Result<(), ConfidantError> {
     let current_user_file = get_current_user_file_path(&app)?;
     
     if let Some(id) = user_id {
         // Verify user exists
         let users = load_users(&app)?;
         if !users.iter().any(|u| u.id == id) {
-            return Err("User not found".to_string());
+            return Err(ConfidantError::not_found("User not found"));
         }
         
         // Save current user
         let content = serde_json::to_string(&id)
-            .map_err(|e| format!("Failed to serialize user ID: {}", e))?;
+            .map_err(|e| ConfidantError::internal(format!("Failed to serialize user ID: {}", e)))?;
         fs::write(&current_user_file, content)
-            .map_err(|e| format!("Failed to write current user file: {}", e))?;
+            .map_err(|e| ConfidantError::io(format!("Failed to write current user file: {}", e)))?;
     } else {
         // Remove current user file (guest mode or logout)
         if current_user_file.exists() {
             fs::remove_file(&current_user_file)
-                .map_err(|e| format!("Failed to remove current user file: {}", e))?;
+                .map_err(|e| ConfidantError::io(format!("Failed to remove current user file: {}", e)))?;
         }
     }
     
@@ -379,10 +381,10 @@ pub async fn set_current_user(app: AppHandle, user_id: Option<String>) -> Result
 
 /// Load chat history for a user (single chat).
 #[tauri::command]
-pub async fn load_user_chat(app: AppHandle, user_id: String) -> Result<Vec<ChatMessage>, String> {
+pub async fn load_user_chat(app: AppHandle, user_id: String) -> Result<Vec<ChatMessage>, ConfidantError> {
     let users = load_users(&app)?;
     if !users.iter().any(|u| u.id == user_id) {
-        return Err("User not found".to_string());
+        return Err(ConfidantError::not_found("User not found"));
     }
     migrate_multi_chat_to_single_if_needed(&app, &user_id)?;
     let chat_file = get_chat_history_path(&app, &user_id)?;
@@ -390,44 +392,44 @@ pub async fn load_user_chat(app: AppHandle, user_id: String) -> Result<Vec<ChatM
         return Ok(Vec::new());
     }
     let content = fs::read_to_string(&chat_file)
-        .map_err(|e| format!("Failed to read chat history: {}", e))?;
+        .map_err(|e| ConfidantError::io(format!("Failed to read chat history: {}", e)))?;
     let messages: Vec<ChatMessage> = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse chat history: {}", e))?;
+        .map_err(|e| ConfidantError::internal(format!("Failed to parse chat history: {}", e)))?;
     Ok(messages)
 }
 
 /// Save chat history for a user (single chat).
 #[tauri::command]
-pub async fn save_user_chat(app: AppHandle, user_id: String, messages: Vec<ChatMessage>) -> Result<(), String> {
+pub async fn save_user_chat(app: AppHandle, user_id: String, messages: Vec<ChatMessage>) -> Result<(), ConfidantError> {
     let users = load_users(&app)?;
     if !users.iter().any(|u| u.id == user_id) {
-        return Err("User not found".to_string());
+        return Err(ConfidantError::not_found("User not found"));
     }
     migrate_multi_chat_to_single_if_needed(&app, &user_id)?;
     let chat_file = get_chat_history_path(&app, &user_id)?;
     if let Some(parent) = chat_file.parent() {
         fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create user directory: {}", e))?;
+            .map_err(|e| ConfidantError::io(format!("Failed to create user directory: {}", e)))?;
     }
     let content = serde_json::to_string_pretty(&messages)
-        .map_err(|e| format!("Failed to serialize chat history: {}", e))?;
+        .map_err(|e| ConfidantError::internal(format!("Failed to serialize chat history: {}", e)))?;
     fs::write(&chat_file, content)
-        .map_err(|e| format!("Failed to write chat history: {}", e))?;
+        .map_err(|e| ConfidantError::io(format!("Failed to write chat history: {}", e)))?;
     Ok(())
 }
 
 /// Delete all chat history for a user.
 #[tauri::command]
-pub async fn delete_user_chat(app: AppHandle, user_id: String) -> Result<(), String> {
+pub async fn delete_user_chat(app: AppHandle, user_id: String) -> Result<(), ConfidantError> {
     let users = load_users(&app)?;
     if !users.iter().any(|u| u.id == user_id) {
-        return Err("User not found".to_string());
+        return Err(ConfidantError::not_found("User not found"));
     }
     migrate_multi_chat_to_single_if_needed(&app, &user_id)?;
     let chat_file = get_chat_history_path(&app, &user_id)?;
     if chat_file.exists() {
         fs::remove_file(&chat_file)
-            .map_err(|e| format!("Failed to delete chat history: {}", e))?;
+            .map_err(|e| ConfidantError::io(format!("Failed to delete chat history: {}", e)))?;
     }
     let user_dir = get_user_dir_path(&app, &user_id)?;
     let list_path = user_dir.join("chat_list.json");
@@ -448,14 +450,14 @@ pub async fn delete_user_chat(app: AppHandle, user_id: String) -> Result<(), Str
 
 /// Delete a user and their data
 #[tauri::command]
-pub async fn delete_user(app: AppHandle, user_id: String) -> Result<(), String> {
+pub async fn delete_user(app: AppHandle, user_id: String) -> Result<(), ConfidantError> {
     // Load users
     let mut users = load_users(&app)?;
     
     // Find user index
     let index = users.iter()
         .position(|u| u.id == user_id)
-        .ok_or_else(|| "User not found".to_string())?;
+        .ok_or_else(|| ConfidantError::not_found("User not found"))?;
     
     // Remove user
     users.remove(index);
@@ -467,7 +469,15 @@ pub async fn delete_user(app: AppHandle, user_id: String) -> Result<(), String>
     let user_dir = get_user_dir_path(&app, &user_id)?;
     if user_dir.exists() {
         fs::remove_dir_all(&user_dir)
-            .map_err(|e| format!("Failed to delete user directory: {}", e))?;
+            .map_err(|e| ConfidantError::io(format!("Failed to delete user directory: {}", e)))?;
+    }
+    
+    // Stop syncing their watched folder, if any
+    crate::watched_folders::forget_user(&app, &user_id)?;
+    
+    // Remove their knowledge base; if that fails, the startup reconciliation quarantines it later
+    if let Err(e) = crate::vector_store::delete_user_knowledge_base(app.clone(), user_id.clone()).await {
+        eprintln!("[User Management] Could not delete knowledge base of user {}: {}", user_id, e);
     }
     
     // If this was the current user, clear current user
@@ -482,24 +492,24 @@ pub async fn delete_user(app: AppHandle, user_id: String) -> Result<(), String>
 
 /// Get user's language preference
 #[tauri::command]
-pub async fn get_user_language(app: AppHandle, user_id: String) -> Result<Option<String>, String> {
+pub async fn get_user_language(app: AppHandle, user_id: String) -> Result<Option<String>, ConfidantError> {
     let users = load_users(&app)?;
     
     let user = users.iter()
         .find(|u| u.id == user_id)
-        .ok_or_else(|| "User not found".to_string())?;
+        .ok_or_else(|| ConfidantError::not_found("User not found"))?;
     
     Ok(Some(user.language.clone()))
 }
 
 /// Set user's language preference
 #[tauri::command]
-pub async fn set_user_language(app: AppHandle, user_id: String, language: String) -> Result<(), String> {
+pub async fn set_user_language(app: AppHandle, user_id: String, language: String) -> Result<(), ConfidantError> {
     let mut users = load_users(&app)?;
     
     let user = users.iter_mut()
         .find(|u| u.id == user_id)
-        .ok_or_else(|| "User not found".to_string())?;
+        .ok_or_else(|| ConfidantError::not_found("User not found"))?;
     
     user.language = language;
     
@@ -2,12 +2,19 @@
 // This module handles vector database operations via Python ChromaDB subprocess
 
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::path::PathBuf;
-use std::process::Command;
-use std::io::Write;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::{OwnedRwLockReadGuard, OwnedRwLockWriteGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use tauri::{AppHandle, Manager};
 
+use crate::citations::{self, NeighborChunk, Provenance};
+use crate::embeddings::{active_embedding_model_id, same_embedding_model};
+use crate::error::ConfidantError;
+use crate::ranking::{self, DistanceMetric, SearchOptions};
+
 // Global state for the vector store
 struct VectorStoreState {
     collection_name: Option<String>,
@@ -21,6 +28,24 @@ lazy_static::lazy_static! {
         is_initialized: false,
         db_path: None,
     });
+    /// Every ChromaDB operation holds this shared; backup, restore and repair take it exclusively.
+    static ref STORE_ACCESS: RwLock<()> = RwLock::new(());
+    /// Per-collection read/write locks (see lock_collection_read / lock_collection_write).
+    static ref COLLECTION_LOCKS: Mutex<HashMap<String, Arc<RwLock<()>>>> = Mutex::new(HashMap::new());
+    /// Embedding model recorded in each collection's metadata, as reported when it was opened.
+    static ref COLLECTION_MODELS: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+}
+
+/// Embedding model recorded for a collection versus the model this app embeds queries with.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CollectionEmbeddingInfo {
+    pub collection: String,
+    pub recorded_model: Option<String>,
+    pub current_model: String,
+    /// False when the collection was built with another model; it must be re-embedded before use.
+    pub compatible: bool,
+    /// A reembed_collection job is running for this collection.
+    pub reembedding: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -35,55 +60,158 @@ pub struct VectorDocument {
 pub struct SearchResult {
     pub id: String,
     pub text: String,
+    /// Vector similarity (1 - distance).
     pub score: f32,
     pub metadata: serde_json::Value,
+    /// Cross-encoder relevance, set when the collection has reranking enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rerank_score: Option<f32>,
+    /// Typed citation info derived from `metadata`.
+    #[serde(default)]
+    pub provenance: Provenance,
+    /// Adjacent chunks of the same document (in order, hit included), when neighbor expansion was requested.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub neighbors: Vec<NeighborChunk>,
+    /// Hit and neighbors merged into one passage, when neighbor expansion was requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expanded_text: Option<String>,
+    /// Candidate embedding, only fetched when rescoring or MMR needs it.
+    #[serde(skip)]
+    pub(crate) embedding: Vec<f32>,
+}
+
+/// Search hit from `search_many`, tagged with the collection it came from.
+/// `score` is the normalized, weighted score used for ranking; `raw_score` is the collection's own score.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FederatedSearchResult {
+    pub collection: String,
+    #[serde(flatten)]
+    pub result: SearchResult,
+    pub raw_score: f32,
 }
 
-/// Document returned by filter-only get (no score).
+/// Document returned by filter-only get (no score). Fields left out by a `FilterQuery` include selector are
+/// empty.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FilterDocument {
     pub id: String,
+    #[serde(default)]
     pub text: String,
+    #[serde(default)]
     pub metadata: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<Vec<f32>>,
+}
+
+/// Document field selectable in a `FilterQuery`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentField {
+    Text,
+    Metadata,
+    Embeddings,
+}
+
+/// Paging, field selection and ordering for get_documents_by_filter.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct FilterQuery {
+    #[serde(default)]
+    pub offset: u32,
+    /// Page size; None returns every match from `offset` on.
+    #[serde(default)]
+    pub limit: Option<u32>,
+    /// Fields to return (default text and metadata); ids are always returned.
+    #[serde(default)]
+    pub include: Option<Vec<DocumentField>>,
+    /// Metadata field to sort by; documents without it come last. Unsorted results keep storage order.
+    #[serde(default)]
+    pub sort_by: Option<String>,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+impl FilterQuery {
+    fn validate(&self) -> Result<(), ConfidantError> {
+        if self.limit == Some(0) {
+            return Err(ConfidantError::validation("limit must be at least 1"));
+        }
+        if self.include.as_ref().is_some_and(|fields| fields.is_empty()) {
+            return Err(ConfidantError::validation("include must name at least one field"));
+        }
+        if self.sort_by.as_ref().is_some_and(|field| field.trim().is_empty()) {
+            return Err(ConfidantError::validation("sort_by must be a metadata field name"));
+        }
+        Ok(())
+    }
+}
+
+/// One page of documents matching a filter, with the number of matches across all pages.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FilterPage {
+    pub documents: Vec<FilterDocument>,
+    pub total: u64,
+    pub offset: u32,
+    pub limit: Option<u32>,
+}
+
+/// Row counts from an upsert: ids that were new vs. ids that replaced an existing document.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpsertResult {
+    pub inserted: u64,
+    pub updated: u64,
+}
+
+/// Row count from a delete by ids or by filter.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteResult {
+    pub deleted: u64,
+}
+
+/// Documents fetched by id, plus the requested ids that were not found.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetDocumentsResult {
+    pub documents: Vec<FilterDocument>,
+    pub found: u64,
+    pub missing: Vec<String>,
 }
 
 /// Get the app data directory for storing ChromaDB. Uses Tauri's writable app data dir
 /// so the packaged app can write when run from DMG.
-fn get_app_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+fn get_app_data_dir(app: &AppHandle) -> Result<PathBuf, ConfidantError> {
     let base_dir = app
         .path()
         .app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+        .map_err(|e| ConfidantError::io(format!("Failed to get app data dir: {}", e)))?;
     let db_path = base_dir.join("data").join("chromadb");
     std::fs::create_dir_all(&db_path)
-        .map_err(|e| format!("Failed to create ChromaDB directory: {}", e))?;
+        .map_err(|e| ConfidantError::io(format!("Failed to create ChromaDB directory: {}", e)))?;
     Ok(db_path)
 }
 
 /// Get path to Python helper script
-fn get_helper_script_path() -> Result<PathBuf, String> {
+fn get_helper_script_path() -> Result<PathBuf, ConfidantError> {
     // Script is in src-tauri/scripts/chromadb_helper.py
     let exe_path = std::env::current_exe()
-        .map_err(|e| format!("Failed to get executable path: {}", e))?;
+        .map_err(|e| ConfidantError::io(format!("Failed to get executable path: {}", e)))?;
     
     // Go up from target/debug/dant-desktop to src-tauri/scripts/
     let script_path = exe_path
         .parent() // target/debug/
         .and_then(|p| p.parent()) // target/
         .and_then(|p| p.parent()) // src-tauri/
-        .ok_or("Failed to find script directory")?
+        .ok_or_else(|| ConfidantError::not_found("Failed to find script directory"))?
         .join("scripts")
         .join("chromadb_helper.py");
     
     if !script_path.exists() {
         // Fallback: try relative to current_dir
         let current_dir = std::env::current_dir()
-            .map_err(|e| format!("Failed to get current directory: {}", e))?;
+            .map_err(|e| ConfidantError::io(format!("Failed to get current directory: {}", e)))?;
         let fallback_path = current_dir.join("src-tauri").join("scripts").join("chromadb_helper.py");
         if fallback_path.exists() {
             return Ok(fallback_path);
         }
-        return Err(format!("Helper script not found at: {:?}", script_path));
+        return Err(ConfidantError::not_found(format!("Helper script not found at: {:?}", script_path)));
     }
     
     Ok(script_path)
@@ -91,16 +219,17 @@ fn get_helper_script_path() -> Result<PathBuf, String> {
 
 /// Call Python helper script.
 /// If `bundled` is Some((python_exe, scripts_dir)), use that Python and scripts_dir/chromadb_helper.py.
-fn call_python_helper(
+/// Runs as an async child process, so concurrent calls overlap without tying up a runtime thread.
+pub(crate) async fn call_python_helper(
     bundled: Option<(PathBuf, PathBuf)>,
     command: &str,
     args: &[&str],
     stdin_data: Option<&str>,
-) -> Result<String, String> {
+) -> Result<String, ConfidantError> {
     let (python_cmd, script_path) = if let Some((python_exe, scripts_dir)) = bundled {
         let script = scripts_dir.join("chromadb_helper.py");
         if !python_exe.exists() || !script.exists() {
-            return Err("Bundled Python or script not found.".to_string());
+            return Err(ConfidantError::python_missing("Bundled Python or script not found."));
         }
         (python_exe.to_string_lossy().to_string(), script)
     } else {
@@ -108,21 +237,21 @@ fn call_python_helper(
         let python_cmd = {
             let venv_python = crate::python_bundle::find_venv_python();
             if let Some(venv_py) = venv_python {
-                if Command::new(&venv_py).arg("--version").output().is_ok() {
+                if Command::new(&venv_py).arg("--version").output().await.is_ok() {
                     venv_py.to_string_lossy().to_string()
-                } else if Command::new("python3").arg("--version").output().is_ok() {
+                } else if Command::new("python3").arg("--version").output().await.is_ok() {
                     "python3".to_string()
-                } else if Command::new("python").arg("--version").output().is_ok() {
+                } else if Command::new("python").arg("--version").output().await.is_ok() {
                     "python".to_string()
                 } else {
-                    return Err("Python not found. Please install Python 3.".to_string());
+                    return Err(ConfidantError::python_missing("Python not found. Please install Python 3."));
                 }
-            } else if Command::new("python3").arg("--version").output().is_ok() {
+            } else if Command::new("python3").arg("--version").output().await.is_ok() {
                 "python3".to_string()
-            } else if Command::new("python").arg("--version").output().is_ok() {
+            } else if Command::new("python").arg("--version").output().await.is_ok() {
                 "python".to_string()
             } else {
-                return Err("Python not found. Please install Python 3.".to_string());
+                return Err(ConfidantError::python_missing("Python not found. Please install Python 3."));
             }
         };
         (python_cmd, script_path)
@@ -132,6 +261,7 @@ fn call_python_helper(
     cmd.arg(&script_path);
     cmd.arg(command);
     cmd.args(args);
+    cmd.kill_on_drop(true);
     
     if stdin_data.is_some() {
         cmd.stdin(std::process::Stdio::piped());
@@ -140,18 +270,18 @@ fn call_python_helper(
     let mut child = cmd.stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped())
         .spawn()
-        .map_err(|e| format!("Failed to spawn Python process: {}", e))?;
+        .map_err(|e| ConfidantError::helper(format!("Failed to spawn Python process: {}", e)))?;
     
-    // Write stdin data if provided
+    // Write stdin data if provided (dropping the handle closes the pipe)
     if let Some(ref data) = stdin_data {
         if let Some(mut stdin) = child.stdin.take() {
-            stdin.write_all(data.as_bytes())
-                .map_err(|e| format!("Failed to write to Python stdin: {}", e))?;
+            stdin.write_all(data.as_bytes()).await
+                .map_err(|e| ConfidantError::helper(format!("Failed to write to Python stdin: {}", e)))?;
         }
     }
     
-    let output = child.wait_with_output()
-        .map_err(|e| format!("Failed to wait for Python process: {}", e))?;
+    let output = child.wait_with_output().await
+        .map_err(|e| ConfidantError::helper(format!("Failed to wait for Python process: {}", e)))?;
     
     #[cfg(debug_assertions)]
     if !output.stderr.is_empty() {
@@ -162,27 +292,112 @@ fn call_python_helper(
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         let stdout = String::from_utf8_lossy(&output.stdout);
-        return Err(format!("Python script failed (exit code: {}): stderr: {}, stdout: {}", 
-            output.status.code().unwrap_or(-1), stderr, stdout));
+        let error = ConfidantError::from_helper_stderr(
+            format!("Python script failed (exit code: {})", output.status.code().unwrap_or(-1)),
+            &stderr,
+        );
+        // The helper reports its own errors as JSON on stdout
+        return Err(match error.details() {
+            None if !stdout.trim().is_empty() => error.with_details(stdout.trim()),
+            _ => error,
+        });
     }
     
     let stdout = String::from_utf8(output.stdout)
-        .map_err(|e| format!("Failed to parse Python output: {}", e))?;
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse Python output: {}", e)))?;
     
     if stdout.trim().is_empty() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Python script returned empty output. stderr: {}", stderr));
+        return Err(ConfidantError::helper("Python script returned empty output.").with_details(stderr));
     }
     
     Ok(stdout)
 }
 
+/// Shared access to the store for one operation, with a copy of the state taken under a short lock.
+/// Holding it keeps backup/restore/repair (which need exclusive access) from starting meanwhile.
+pub(crate) struct StoreAccess {
+    _guard: RwLockReadGuard<'static, ()>,
+    pub db_path: String,
+    pub collection_name: Option<String>,
+}
+
+/// Shared access to an initialized store.
+async fn open_store() -> Result<StoreAccess, ConfidantError> {
+    let guard = STORE_ACCESS.read().await;
+    let state = VECTOR_STORE_STATE.lock()
+        .map_err(|e| ConfidantError::internal(format!("Failed to lock state: {}", e)))?;
+
+    if !state.is_initialized {
+        return Err(ConfidantError::not_initialized("Vector store not initialized. Call initialize_vector_store first."));
+    }
+
+    let db_path = state.db_path.as_ref()
+        .ok_or_else(|| ConfidantError::not_initialized("Database path not set"))?
+        .to_str()
+        .ok_or_else(|| ConfidantError::validation("Invalid database path"))?
+        .to_string();
+    Ok(StoreAccess {
+        _guard: guard,
+        db_path,
+        collection_name: state.collection_name.clone(),
+    })
+}
+
+/// Shared access to the store, falling back to the default path if it was not initialized yet
+/// (startup maintenance, delete_user, background jobs).
+pub(crate) async fn open_store_or_default(app: &AppHandle) -> Result<StoreAccess, ConfidantError> {
+    let guard = STORE_ACCESS.read().await;
+    let (db_path, collection_name) = {
+        let state = VECTOR_STORE_STATE.lock()
+            .map_err(|e| ConfidantError::internal(format!("Failed to lock state: {}", e)))?;
+        (state.db_path.clone(), state.collection_name.clone())
+    };
+    let db_path = match db_path {
+        Some(path) => path,
+        None => get_app_data_dir(app)?,
+    };
+    Ok(StoreAccess {
+        _guard: guard,
+        db_path: db_path.to_str().ok_or_else(|| ConfidantError::validation("Invalid database path"))?.to_string(),
+        collection_name,
+    })
+}
+
+/// Exclusive access to the store directory: waits for running operations and blocks new ones until the
+/// guard is dropped (backup, restore, verify).
+pub(crate) async fn open_store_exclusive(app: &AppHandle) -> Result<(RwLockWriteGuard<'static, ()>, PathBuf), ConfidantError> {
+    let guard = STORE_ACCESS.write().await;
+    let db_path = VECTOR_STORE_STATE.lock()
+        .map_err(|e| ConfidantError::internal(format!("Failed to lock state: {}", e)))?
+        .db_path
+        .clone();
+    let db_path = match db_path {
+        Some(path) => path,
+        None => get_app_data_dir(app)?,
+    };
+    Ok((guard, db_path))
+}
+
+fn collection_lock(collection_name: &str) -> Arc<RwLock<()>> {
+    let mut locks = COLLECTION_LOCKS.lock().unwrap_or_else(|e| e.into_inner());
+    locks.entry(collection_name.to_string()).or_default().clone()
+}
+
+/// Read access to one collection: any number of readers run at once.
+pub(crate) async fn lock_collection_read(collection_name: &str) -> OwnedRwLockReadGuard<()> {
+    collection_lock(collection_name).read_owned().await
+}
+
+/// Write access to one collection: ChromaDB's on-disk index must not be written by two helper
+/// processes at once, nor read while a write is in progress.
+pub(crate) async fn lock_collection_write(collection_name: &str) -> OwnedRwLockWriteGuard<()> {
+    collection_lock(collection_name).write_owned().await
+}
+
 /// Initialize vector store with ChromaDB
 #[tauri::command]
-pub async fn initialize_vector_store(app: AppHandle, collection_name: String, db_path: Option<String>) -> Result<(), String> {
-    let mut state = VECTOR_STORE_STATE.lock()
-        .map_err(|e| format!("Failed to lock state: {}", e))?;
-
+pub async fn initialize_vector_store(app: AppHandle, collection_name: String, db_path: Option<String>) -> Result<(), ConfidantError> {
     #[cfg(debug_assertions)]
     eprintln!("[Vector Store] Initializing collection: {}", collection_name);
 
@@ -193,50 +408,131 @@ pub async fn initialize_vector_store(app: AppHandle, collection_name: String, db
         get_app_data_dir(&app)?
     };
     
-    // Ensure db_path is set in state
-    if state.db_path.is_none() {
-        state.db_path = Some(db_path.clone());
-    }
-    
     let db_path_str = db_path.to_str()
-        .ok_or("Invalid database path")?;
+        .ok_or_else(|| ConfidantError::validation("Invalid database path"))?;
     
-    // Call Python helper to initialize ChromaDB
+    // Call Python helper to initialize ChromaDB (new collections record the embedding model)
     let bundled = crate::python_bundle::resolve_bundled_python(&app);
-    let result_json = call_python_helper(bundled, "init", &[db_path_str, &collection_name], None)?;
-    let result: serde_json::Value = serde_json::from_str(&result_json)
-        .map_err(|e| format!("Failed to parse Python response: {}", e))?;
+    let result = {
+        let _access = STORE_ACCESS.read().await;
+        let _write = lock_collection_write(&collection_name).await;
+        let result_json = call_python_helper(bundled, "init", &[db_path_str, &collection_name, &active_embedding_model_id()], None).await?;
+        serde_json::from_str::<serde_json::Value>(&result_json)
+            .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?
+    };
     
     if result["status"].as_str() != Some("success") {
-        return Err(format!("ChromaDB initialization failed: {:?}", result));
+        return Err(ConfidantError::helper("ChromaDB initialization failed").with_details(result.to_string()));
     }
     
-    state.is_initialized = true;
+    {
+        let mut state = VECTOR_STORE_STATE.lock()
+            .map_err(|e| ConfidantError::internal(format!("Failed to lock state: {}", e)))?;
+        // Ensure db_path is set in state
+        if state.db_path.is_none() {
+            state.db_path = Some(db_path.clone());
+        }
+        state.is_initialized = true;
+    }
+
+    if let Some(model) = result["embedding_model"].as_str() {
+        let active = active_embedding_model_id();
+        if !same_embedding_model(model, &active) {
+            eprintln!(
+                "[Vector Store] Collection {} was embedded with {} but this app embeds with {}; re-embed it before use",
+                collection_name, model, active
+            );
+        }
+        record_collection_model(&collection_name, model);
+    }
 
     Ok(())
 }
 
+/// Drop the recorded collection models (after a restore replaced the collections).
+pub(crate) fn forget_collection_models() {
+    if let Ok(mut models) = COLLECTION_MODELS.lock() {
+        models.clear();
+    }
+}
+
+/// Remember the embedding model a collection was built with (after open or re-embed).
+pub(crate) fn record_collection_model(collection_name: &str, model: &str) {
+    if let Ok(mut models) = COLLECTION_MODELS.lock() {
+        models.insert(collection_name.to_string(), model.to_string());
+    }
+}
+
+/// Refuse to use a collection whose vectors came from a different model than the one queries are embedded with.
+/// Collections that have not been opened yet are not checked.
+fn check_collection_model(collection_name: &str) -> Result<(), ConfidantError> {
+    let models = COLLECTION_MODELS.lock()
+        .map_err(|e| ConfidantError::internal(format!("Failed to lock state: {}", e)))?;
+    let active = active_embedding_model_id();
+    match models.get(collection_name) {
+        Some(model) if !same_embedding_model(model, &active) => Err(ConfidantError::validation(format!(
+            "Collection {} was embedded with {} but this app embeds with {}. Re-embed it with reembed_collection first.",
+            collection_name, model, active
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// No writes while a re-embed is copying the collection (they would be lost at the swap).
+fn check_not_reembedding(collection_name: &str) -> Result<(), ConfidantError> {
+    if crate::reembed::is_reembedding(collection_name) {
+        return Err(ConfidantError::validation(format!(
+            "Collection {} is being re-embedded; try again when the job finishes.",
+            collection_name
+        )));
+    }
+    Ok(())
+}
+
+/// Checks before adding vectors: they must match the collection's model, and no re-embed may be running.
+fn check_collection_writable(collection_name: &str) -> Result<(), ConfidantError> {
+    check_collection_model(collection_name)?;
+    check_not_reembedding(collection_name)
+}
+
+/// Embedding model a collection was built with and whether it matches this app's model.
+/// Opens the collection (recording the model) if it was not opened yet.
+#[tauri::command]
+pub async fn get_collection_embedding_info(app: AppHandle, collection_name: String) -> Result<CollectionEmbeddingInfo, ConfidantError> {
+    let known = COLLECTION_MODELS.lock()
+        .map_err(|e| ConfidantError::internal(format!("Failed to lock state: {}", e)))?
+        .contains_key(&collection_name);
+    if !known {
+        initialize_vector_store(app, collection_name.clone(), None).await?;
+    }
+    let recorded_model = COLLECTION_MODELS.lock()
+        .map_err(|e| ConfidantError::internal(format!("Failed to lock state: {}", e)))?
+        .get(&collection_name)
+        .cloned();
+    let current_model = active_embedding_model_id();
+    Ok(CollectionEmbeddingInfo {
+        compatible: recorded_model.as_deref().is_none_or(|m| same_embedding_model(m, &current_model)),
+        reembedding: crate::reembed::is_reembedding(&collection_name),
+        collection: collection_name,
+        recorded_model,
+        current_model,
+    })
+}
+
 /// Add documents to vector store (uses current collection from state)
 #[tauri::command]
 pub async fn add_documents(
     app: AppHandle,
     documents: Vec<VectorDocument>,
-) -> Result<(), String> {
+) -> Result<(), ConfidantError> {
     let bundled = crate::python_bundle::resolve_bundled_python(&app);
-    let state = VECTOR_STORE_STATE.lock()
-        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    let store = open_store().await?;
+    let db_path = store.db_path.as_str();
     
-    if !state.is_initialized {
-        return Err("Vector store not initialized. Call initialize_vector_store first.".to_string());
-    }
-
-    let db_path = state.db_path.as_ref()
-        .ok_or("Database path not set")?
-        .to_str()
-        .ok_or("Invalid database path")?;
-    
-    let collection_name = state.collection_name.as_ref()
-        .ok_or("Collection name not set")?;
+    let collection_name = store.collection_name.as_ref()
+        .ok_or_else(|| ConfidantError::not_initialized("Collection name not set"))?;
+    check_collection_writable(collection_name)?;
+    let _write = lock_collection_write(collection_name).await;
 
     #[cfg(debug_assertions)]
     eprintln!("[Vector Store] Adding {} documents", documents.len());
@@ -252,15 +548,15 @@ pub async fn add_documents(
     }).collect();
     
     let stdin_data = serde_json::to_string(&docs_json)
-        .map_err(|e| format!("Failed to serialize documents: {}", e))?;
+        .map_err(|e| ConfidantError::internal(format!("Failed to serialize documents: {}", e)))?;
     
     // Call Python helper
-    let result_json = call_python_helper(bundled, "add", &[db_path, collection_name], Some(&stdin_data))?;
+    let result_json = call_python_helper(bundled, "add", &[db_path, collection_name], Some(&stdin_data)).await?;
     let result: serde_json::Value = serde_json::from_str(&result_json)
-        .map_err(|e| format!("Failed to parse Python response: {}", e))?;
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?;
     
     if result["status"].as_str() != Some("success") {
-        return Err(format!("Failed to add documents: {:?}", result));
+        return Err(ConfidantError::helper("Failed to add documents").with_details(result.to_string()));
     }
     
     Ok(())
@@ -272,19 +568,13 @@ pub async fn add_documents_to_collection(
     app: AppHandle,
     collection_name: String,
     documents: Vec<VectorDocument>,
-) -> Result<(), String> {
-    let state = VECTOR_STORE_STATE.lock()
-        .map_err(|e| format!("Failed to lock state: {}", e))?;
-    
-    if !state.is_initialized {
-        return Err("Vector store not initialized. Call initialize_vector_store first.".to_string());
-    }
-
+) -> Result<(), ConfidantError> {
     let bundled = crate::python_bundle::resolve_bundled_python(&app);
-    let db_path = state.db_path.as_ref()
-        .ok_or("Database path not set")?
-        .to_str()
-        .ok_or("Invalid database path")?;
+    let store = open_store().await?;
+    let db_path = store.db_path.as_str();
+
+    check_collection_writable(&collection_name)?;
+    let _write = lock_collection_write(&collection_name).await;
 
     #[cfg(debug_assertions)]
     eprintln!("[Vector Store] Adding {} documents to collection: {}", documents.len(), collection_name);
@@ -300,15 +590,15 @@ pub async fn add_documents_to_collection(
     }).collect();
     
     let stdin_data = serde_json::to_string(&docs_json)
-        .map_err(|e| format!("Failed to serialize documents: {}", e))?;
+        .map_err(|e| ConfidantError::internal(format!("Failed to serialize documents: {}", e)))?;
     
     // Call Python helper
-    let result_json = call_python_helper(bundled, "add", &[db_path, &collection_name], Some(&stdin_data))?;
+    let result_json = call_python_helper(bundled, "add", &[db_path, &collection_name], Some(&stdin_data)).await?;
     let result: serde_json::Value = serde_json::from_str(&result_json)
-        .map_err(|e| format!("Failed to parse Python response: {}", e))?;
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?;
     
     if result["status"].as_str() != Some("success") {
-        return Err(format!("Failed to add documents: {:?}", result));
+        return Err(ConfidantError::helper("Failed to add documents").with_details(result.to_string()));
     }
     
     Ok(())
@@ -320,248 +610,886 @@ pub async fn search_similar(
     app: AppHandle,
     query_embedding: Vec<f32>,
     limit: u32,
-) -> Result<Vec<SearchResult>, String> {
+) -> Result<Vec<SearchResult>, ConfidantError> {
     let bundled = crate::python_bundle::resolve_bundled_python(&app);
-    let state = VECTOR_STORE_STATE.lock()
-        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    let store = open_store().await?;
+    let db_path = store.db_path.as_str();
     
-    if !state.is_initialized {
-        return Err("Vector store not initialized. Call initialize_vector_store first.".to_string());
-    }
-
-    let db_path = state.db_path.as_ref()
-        .ok_or("Database path not set")?
-        .to_str()
-        .ok_or("Invalid database path")?;
-    
-    let collection_name = state.collection_name.as_ref()
-        .ok_or("Collection name not set")?;
+    let collection_name = store.collection_name.as_ref()
+        .ok_or_else(|| ConfidantError::not_initialized("Collection name not set"))?;
+    check_collection_model(collection_name)?;
+    let _read = lock_collection_read(collection_name).await;
 
     #[cfg(debug_assertions)]
     eprintln!("[Vector Store] Search limit: {}", limit);
 
     // Convert embedding to JSON
     let embedding_json = serde_json::to_string(&query_embedding)
-        .map_err(|e| format!("Failed to serialize embedding: {}", e))?;
+        .map_err(|e| ConfidantError::internal(format!("Failed to serialize embedding: {}", e)))?;
     
     // Call Python helper
-    let result_json = call_python_helper(bundled, "search", &[db_path, collection_name, &limit.to_string()], Some(&embedding_json))?;
+    let result_json = call_python_helper(bundled, "search", &[db_path, collection_name, &limit.to_string()], Some(&embedding_json)).await?;
     let result: serde_json::Value = serde_json::from_str(&result_json)
-        .map_err(|e| format!("Failed to parse Python response: {}", e))?;
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?;
     
     if result["status"].as_str() != Some("success") {
-        return Err(format!("Search failed: {:?}", result));
+        return Err(ConfidantError::helper("Search failed").with_details(result.to_string()));
     }
     
     // Parse results
     let results_array = result["results"].as_array()
-        .ok_or("Invalid search results format")?;
+        .ok_or_else(|| ConfidantError::helper("Invalid search results format"))?;
     
     let search_results: Result<Vec<SearchResult>, _> = results_array.iter().map(|r| {
+        let id = r["id"].as_str().unwrap_or("").to_string();
         Ok(SearchResult {
-            id: r["id"].as_str().unwrap_or("").to_string(),
+            provenance: Provenance::from_metadata(&id, &r["metadata"]),
+            id,
             text: r["text"].as_str().unwrap_or("").to_string(),
             score: r["score"].as_f64().unwrap_or(0.0) as f32,
             metadata: r["metadata"].clone(),
+            rerank_score: None,
+            neighbors: Vec::new(),
+            expanded_text: None,
+            embedding: Vec::new(),
         })
     }).collect();
     
-    let search_results = search_results.map_err(|e: serde_json::Error| format!("Failed to parse results: {}", e))?;
+    let search_results = search_results.map_err(|e: serde_json::Error| ConfidantError::helper(format!("Failed to parse results: {}", e)))?;
     
     Ok(search_results)
 }
 
-/// Search a specific collection
+/// Search a specific collection.
+/// `options` (or the collection's saved search options) rescore candidates with the chosen metric,
+/// drop results below `min_score` and apply MMR. When the collection has reranking enabled and
+/// `query_text` is given, candidates are re-scored with a cross-encoder before selection.
+/// With `neighbors` = n, each hit is expanded with up to n chunks on either side from the same document.
 #[tauri::command]
 pub async fn search_collection(
     app: AppHandle,
     collection_name: String,
     query_embedding: Vec<f32>,
     limit: u32,
-) -> Result<Vec<SearchResult>, String> {
+    query_text: Option<String>,
+    options: Option<SearchOptions>,
+    neighbors: Option<u32>,
+) -> Result<Vec<SearchResult>, ConfidantError> {
     let bundled = crate::python_bundle::resolve_bundled_python(&app);
-    let state = VECTOR_STORE_STATE.lock()
-        .map_err(|e| format!("Failed to lock state: {}", e))?;
-    
-    if !state.is_initialized {
-        return Err("Vector store not initialized. Call initialize_vector_store first.".to_string());
-    }
-
-    let db_path = state.db_path.as_ref()
-        .ok_or("Database path not set")?
-        .to_str()
-        .ok_or("Invalid database path")?;
+    let settings = crate::collection_settings::load_collection_settings(&app, &collection_name)?;
+    let store = open_store().await?;
+    let db_path = store.db_path.clone();
 
     #[cfg(debug_assertions)]
     eprintln!("[Vector Store] Searching collection: {} with limit: {}", collection_name, limit);
 
+    let mut results = run_collection_search(
+        &app,
+        &db_path,
+        &collection_name,
+        settings,
+        &query_embedding,
+        limit,
+        query_text.as_deref(),
+        options,
+    ).await?;
+    if let Some(radius) = neighbors.filter(|n| *n > 0) {
+        for hit in results.iter_mut() {
+            expand_hit(bundled.clone(), &db_path, &collection_name, hit, radius).await?;
+        }
+    }
+    Ok(results)
+}
+
+/// Attach neighboring chunks of the same document to a hit and merge them into `expanded_text`.
+/// Hits without a document key and chunk index (whole-document entries) are left as they are.
+async fn expand_hit(
+    bundled: Option<(PathBuf, PathBuf)>,
+    db_path: &str,
+    collection_name: &str,
+    hit: &mut SearchResult,
+    radius: u32,
+) -> Result<(), ConfidantError> {
+    let Some(filter) = citations::neighbor_filter(&hit.metadata, radius) else {
+        return Ok(());
+    };
+    let _read = lock_collection_read(collection_name).await;
+    let result_json = call_python_helper(
+        bundled.clone(),
+        "get_by_filter",
+        &[db_path, collection_name, &filter.to_string()],
+        None,
+    ).await?;
+    let result: serde_json::Value = serde_json::from_str(&result_json)
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?;
+    if result["status"].as_str() != Some("success") {
+        return Err(ConfidantError::helper("Neighbor lookup failed").with_details(result.to_string()));
+    }
+
+    let mut chunks: Vec<(Provenance, NeighborChunk)> = result["documents"]
+        .as_array()
+        .map(|docs| {
+            docs.iter()
+                .filter_map(|d| {
+                    let id = d["id"].as_str().unwrap_or("").to_string();
+                    let provenance = Provenance::from_metadata(&id, &d["metadata"]);
+                    let chunk_index = provenance.chunk_index?;
+                    let text = d["text"].as_str().unwrap_or("").to_string();
+                    Some((provenance, NeighborChunk { id, text, chunk_index }))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    chunks.sort_by_key(|(_, c)| c.chunk_index);
+    if chunks.len() <= 1 {
+        return Ok(());
+    }
+
+    let parts: Vec<(&Provenance, &str)> = chunks.iter().map(|(p, c)| (p, c.text.as_str())).collect();
+    hit.expanded_text = Some(citations::merge_chunks(&parts));
+    hit.neighbors = chunks.into_iter().map(|(_, c)| c).collect();
+    Ok(())
+}
+
+/// Full search pipeline for one collection: vector query, then optional rescoring/threshold,
+/// cross-encoder rerank and MMR. The caller holds shared store access.
+#[allow(clippy::too_many_arguments)]
+async fn run_collection_search(
+    app: &AppHandle,
+    db_path: &str,
+    collection_name: &str,
+    settings: crate::collection_settings::CollectionSettings,
+    query_embedding: &[f32],
+    limit: u32,
+    query_text: Option<&str>,
+    options: Option<SearchOptions>,
+) -> Result<Vec<SearchResult>, ConfidantError> {
+    check_collection_model(collection_name)?;
+    let bundled = crate::python_bundle::resolve_bundled_python(app);
+    let rerank = match query_text {
+        Some(q) if !q.trim().is_empty() => settings.rerank,
+        _ => None,
+    };
+    let options = options.or(settings.search);
+    if let Some(ref opts) = options {
+        opts.validate()?;
+    }
+    let fetch_limit = options.as_ref().map_or(limit, |o| o.candidate_count(limit));
+    let fetch_limit = rerank.as_ref().map_or(fetch_limit, |r| r.candidates.max(fetch_limit));
+
     // Convert embedding to JSON
-    let embedding_json = serde_json::to_string(&query_embedding)
-        .map_err(|e| format!("Failed to serialize embedding: {}", e))?;
+    let embedding_json = serde_json::to_string(query_embedding)
+        .map_err(|e| ConfidantError::internal(format!("Failed to serialize embedding: {}", e)))?;
     
-    // Call Python helper
-    let result_json = call_python_helper(bundled, "search", &[db_path, &collection_name, &limit.to_string()], Some(&embedding_json))?;
+    // Call Python helper (candidate embeddings are only needed to rescore / diversify)
+    let fetch_limit_str = fetch_limit.to_string();
+    let mut args = vec![db_path, collection_name, fetch_limit_str.as_str()];
+    if options.is_some() {
+        args.push("embeddings");
+    }
+    let result_json = {
+        let _read = lock_collection_read(collection_name).await;
+        call_python_helper(bundled.clone(), "search", &args, Some(&embedding_json)).await?
+    };
     let result: serde_json::Value = serde_json::from_str(&result_json)
-        .map_err(|e| format!("Failed to parse Python response: {}", e))?;
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?;
     
     if result["status"].as_str() != Some("success") {
-        return Err(format!("Search failed: {:?}", result));
+        return Err(ConfidantError::helper("Search failed").with_details(result.to_string()));
     }
     
     // Parse results
     let results_array = result["results"].as_array()
-        .ok_or("Invalid search results format")?;
+        .ok_or_else(|| ConfidantError::helper("Invalid search results format"))?;
     
-    let search_results: Result<Vec<SearchResult>, _> = results_array.iter().map(|r| {
-        Ok(SearchResult {
-            id: r["id"].as_str().unwrap_or("").to_string(),
+    let mut search_results: Vec<SearchResult> = results_array.iter().map(|r| {
+        let id = r["id"].as_str().unwrap_or("").to_string();
+        SearchResult {
+            provenance: Provenance::from_metadata(&id, &r["metadata"]),
+            id,
             text: r["text"].as_str().unwrap_or("").to_string(),
             score: r["score"].as_f64().unwrap_or(0.0) as f32,
             metadata: r["metadata"].clone(),
-        })
+            rerank_score: None,
+            neighbors: Vec::new(),
+            expanded_text: None,
+            embedding: r["embedding"]
+                .as_array()
+                .map(|arr| arr.iter().filter_map(|v| v.as_f64().map(|f| f as f32)).collect())
+                .unwrap_or_default(),
+        }
     }).collect();
-    
-    let search_results = search_results.map_err(|e: serde_json::Error| format!("Failed to parse results: {}", e))?;
-    
-    Ok(search_results)
+
+    if let Some(ref opts) = options {
+        search_results = rescore_results(query_embedding, opts, search_results);
+    }
+    if let (Some(rerank), Some(query)) = (rerank, query_text) {
+        search_results = rerank_results(app, &rerank, query, search_results).await?;
+    }
+    match options.as_ref().and_then(|o| o.mmr_lambda.map(|lambda| (lambda, o.metric))) {
+        Some((lambda, metric)) => Ok(mmr_results(search_results, limit, lambda, metric)),
+        None => {
+            search_results.truncate(limit as usize);
+            Ok(search_results)
+        }
+    }
 }
 
-/// Get collection statistics (uses current collection from state)
+/// Search several collections at once (e.g. global KB, user KB and phone book) and merge.
+/// Each collection runs its own search pipeline concurrently; each collection's scores are min-max
+/// normalized to [0, 1] on their own and multiplied by the collection's weight (default 1.0).
+/// Duplicates (same id or same normalized text) keep the highest-scoring hit.
+/// Collections that fail (e.g. a user KB that was never created) are skipped.
 #[tauri::command]
-pub async fn get_collection_stats(app: AppHandle) -> Result<serde_json::Value, String> {
+pub async fn search_many(
+    app: AppHandle,
+    collections: Vec<String>,
+    query_embedding: Vec<f32>,
+    limit: u32,
+    per_collection_weights: Option<HashMap<String, f32>>,
+    query_text: Option<String>,
+    neighbors: Option<u32>,
+) -> Result<Vec<FederatedSearchResult>, ConfidantError> {
+    if collections.is_empty() {
+        return Ok(Vec::new());
+    }
     let bundled = crate::python_bundle::resolve_bundled_python(&app);
-    let state = VECTOR_STORE_STATE.lock()
-        .map_err(|e| format!("Failed to lock state: {}", e))?;
-    
-    if !state.is_initialized {
-        return Err("Vector store not initialized. Call initialize_vector_store first.".to_string());
+    let store = open_store().await?;
+    let db_path = store.db_path.clone();
+
+    #[cfg(debug_assertions)]
+    eprintln!("[Vector Store] Federated search over {} collections with limit: {}", collections.len(), limit);
+
+    // Each collection's pipeline runs as its own future; their helper processes overlap
+    let mut searches = Vec::with_capacity(collections.len());
+    for collection_name in &collections {
+        let settings = crate::collection_settings::load_collection_settings(&app, collection_name)?;
+        searches.push(run_collection_search(
+            &app,
+            &db_path,
+            collection_name,
+            settings,
+            &query_embedding,
+            limit,
+            query_text.as_deref(),
+            None,
+        ));
     }
 
-    let db_path = state.db_path.as_ref()
-        .ok_or("Database path not set")?
-        .to_str()
-        .ok_or("Invalid database path")?;
+    let mut hits: Vec<(String, SearchResult)> = Vec::new();
+    let mut errors: Vec<String> = Vec::new();
+    for (collection_name, result) in collections.iter().zip(futures_util::future::join_all(searches).await) {
+        match result {
+            Ok(results) => hits.extend(results.into_iter().map(|r| (collection_name.clone(), r))),
+            Err(e) => {
+                #[cfg(debug_assertions)]
+                eprintln!("[Vector Store] Skipping collection {} in federated search: {}", collection_name, e);
+                errors.push(format!("{}: {}", collection_name, e));
+            }
+        }
+    }
+    if errors.len() == collections.len() {
+        return Err(ConfidantError::helper("Federated search failed for all collections").with_details(errors.join("; ")));
+    }
+
+    let mut merged = merge_federated_results(hits, per_collection_weights.as_ref(), limit);
+    if let Some(radius) = neighbors.filter(|n| *n > 0) {
+        // Expand only the final hits
+        for hit in merged.iter_mut() {
+            expand_hit(bundled.clone(), &db_path, &hit.collection, &mut hit.result, radius).await?;
+        }
+    }
+    Ok(merged)
+}
+
+/// Normalize (per collection), weight, dedupe and rank hits from several collections.
+fn merge_federated_results(
+    hits: Vec<(String, SearchResult)>,
+    weights: Option<&HashMap<String, f32>>,
+    limit: u32,
+) -> Vec<FederatedSearchResult> {
+    // Normalize within each collection, so one collection's score range does not rescale the others
+    let mut by_collection: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, (collection, _)) in hits.iter().enumerate() {
+        by_collection.entry(collection.as_str()).or_default().push(i);
+    }
+    let mut normalized = vec![0.0f32; hits.len()];
+    for indices in by_collection.values() {
+        let raw_scores: Vec<f32> = indices.iter().map(|&i| hits[i].1.score).collect();
+        for (&i, norm) in indices.iter().zip(ranking::normalize_scores(&raw_scores)) {
+            normalized[i] = norm;
+        }
+    }
+
+    let mut merged: Vec<FederatedSearchResult> = hits
+        .into_iter()
+        .zip(normalized)
+        .map(|((collection, mut result), norm)| {
+            let weight = weights
+                .and_then(|w| w.get(&collection))
+                .copied()
+                .unwrap_or(1.0);
+            let raw_score = result.score;
+            result.score = norm * weight;
+            FederatedSearchResult {
+                collection,
+                result,
+                raw_score,
+            }
+        })
+        .collect();
+    merged.sort_by(|a, b| b.result.score.partial_cmp(&a.result.score).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut seen_ids = std::collections::HashSet::new();
+    let mut seen_texts = std::collections::HashSet::new();
+    merged.retain(|hit| {
+        let text_key = hit.result.text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+        if seen_ids.contains(&hit.result.id) || seen_texts.contains(&text_key) {
+            return false;
+        }
+        seen_ids.insert(hit.result.id.clone());
+        seen_texts.insert(text_key);
+        true
+    });
+    merged.truncate(limit as usize);
+    merged
+}
+
+/// Recompute scores with the requested metric, drop results below `min_score` and sort by score.
+fn rescore_results(query_embedding: &[f32], options: &SearchOptions, results: Vec<SearchResult>) -> Vec<SearchResult> {
+    let mut results: Vec<SearchResult> = results
+        .into_iter()
+        .map(|mut r| {
+            if !r.embedding.is_empty() {
+                r.score = ranking::similarity(options.metric, query_embedding, &r.embedding);
+            }
+            r
+        })
+        .filter(|r| options.min_score.is_none_or(|min| r.score >= min))
+        .collect();
+    results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    results
+}
+
+/// Re-score search results with a cross-encoder and sort by that score.
+async fn rerank_results(
+    app: &AppHandle,
+    rerank: &crate::collection_settings::RerankConfig,
+    query: &str,
+    mut results: Vec<SearchResult>,
+) -> Result<Vec<SearchResult>, ConfidantError> {
+    if results.is_empty() {
+        return Ok(results);
+    }
+
+    #[cfg(debug_assertions)]
+    eprintln!("[Vector Store] Reranking {} candidates with {}", results.len(), rerank.model);
+
+    let passages: Vec<String> = results.iter().map(|r| r.text.clone()).collect();
+    let scores = crate::embeddings::rerank_passages(app, &rerank.model, query, &passages).await?;
+    for (result, score) in results.iter_mut().zip(scores) {
+        result.rerank_score = Some(score);
+    }
+    results.sort_by(|a, b| {
+        b.rerank_score
+            .partial_cmp(&a.rerank_score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    Ok(results)
+}
+
+/// Keep `limit` results chosen by maximal marginal relevance. Relevance is the (normalized)
+/// cross-encoder score when present, otherwise the vector score.
+fn mmr_results(results: Vec<SearchResult>, limit: u32, lambda: f32, metric: DistanceMetric) -> Vec<SearchResult> {
+    let relevance = if results.iter().all(|r| r.rerank_score.is_some()) {
+        let rerank_scores: Vec<f32> = results.iter().filter_map(|r| r.rerank_score).collect();
+        ranking::normalize_scores(&rerank_scores)
+    } else {
+        results.iter().map(|r| r.score).collect()
+    };
+    let embeddings: Vec<Vec<f32>> = results.iter().map(|r| r.embedding.clone()).collect();
+    let order = ranking::mmr_select(&relevance, &embeddings, limit as usize, lambda, metric);
+
+    let mut slots: Vec<Option<SearchResult>> = results.into_iter().map(Some).collect();
+    order.into_iter().filter_map(|i| slots[i].take()).collect()
+}
+
+/// Get collection statistics (uses current collection from state)
+#[tauri::command]
+pub async fn get_collection_stats(app: AppHandle) -> Result<serde_json::Value, ConfidantError> {
+    let bundled = crate::python_bundle::resolve_bundled_python(&app);
+    let store = open_store().await?;
+    let db_path = store.db_path.as_str();
     
-    let collection_name = state.collection_name.as_deref()
+    let collection_name = store.collection_name.as_deref()
         .unwrap_or("dant_knowledge");
+    let _read = lock_collection_read(collection_name).await;
 
     // Call Python helper
-    let result_json = call_python_helper(bundled, "stats", &[db_path, collection_name], None)?;
+    let result_json = call_python_helper(bundled, "stats", &[db_path, collection_name], None).await?;
     let result: serde_json::Value = serde_json::from_str(&result_json)
-        .map_err(|e| format!("Failed to parse Python response: {}", e))?;
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?;
     
     Ok(result)
 }
 
-/// Get documents by metadata filter only (no query embedding). Used for phone book lookup.
+/// Get documents by metadata filter only (no query embedding). Used for phone book lookup and for browsing
+/// a collection page by page: `query` sets offset/limit, the fields to return and a metadata field to sort by.
+/// Without `query`, every match is returned with text and metadata.
 #[tauri::command]
 pub async fn get_documents_by_filter(
     app: AppHandle,
     collection_name: String,
     where_json: String,
-) -> Result<Vec<FilterDocument>, String> {
+    query: Option<FilterQuery>,
+) -> Result<FilterPage, ConfidantError> {
+    let query = query.unwrap_or_default();
+    query.validate()?;
     let bundled = crate::python_bundle::resolve_bundled_python(&app);
-    let state = VECTOR_STORE_STATE.lock()
-        .map_err(|e| format!("Failed to lock state: {}", e))?;
+    let store = open_store().await?;
+    let db_path = store.db_path.as_str();
 
-    if !state.is_initialized {
-        return Err("Vector store not initialized. Call initialize_vector_store first.".to_string());
+    let query_json = serde_json::to_string(&query)
+        .map_err(|e| ConfidantError::internal(format!("Failed to serialize query: {}", e)))?;
+    let _read = lock_collection_read(&collection_name).await;
+    let result_json = call_python_helper(
+        bundled,
+        "get_by_filter",
+        &[db_path, &collection_name, &where_json, &query_json],
+        None,
+    ).await?;
+    let result: serde_json::Value = serde_json::from_str(&result_json)
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?;
+
+    if result["status"].as_str() != Some("success") {
+        return Err(ConfidantError::helper("get_documents_by_filter failed").with_details(result.to_string()));
     }
 
-    let db_path = state.db_path.as_ref()
-        .ok_or("Database path not set")?
-        .to_str()
-        .ok_or("Invalid database path")?;
+    let documents: Vec<FilterDocument> = serde_json::from_value(result["documents"].clone())
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse documents: {}", e)))?;
+    Ok(FilterPage {
+        total: result["total"].as_u64().unwrap_or(documents.len() as u64),
+        documents,
+        offset: query.offset,
+        limit: query.limit,
+    })
+}
+
+/// Insert or replace documents in a collection by id (e.g. fix a wrong phone number or re-seed an article).
+#[tauri::command]
+pub async fn upsert_documents(
+    app: AppHandle,
+    collection_name: String,
+    documents: Vec<VectorDocument>,
+) -> Result<UpsertResult, ConfidantError> {
+    let bundled = crate::python_bundle::resolve_bundled_python(&app);
+    let store = open_store().await?;
+    let db_path = store.db_path.as_str();
+
+    check_collection_writable(&collection_name)?;
+    let _write = lock_collection_write(&collection_name).await;
+
+    #[cfg(debug_assertions)]
+    eprintln!("[Vector Store] Upserting {} documents into collection: {}", documents.len(), collection_name);
+
+    let docs_json: Vec<serde_json::Value> = documents.iter().map(|doc| {
+        serde_json::json!({
+            "id": doc.id,
+            "text": doc.text,
+            "embedding": doc.embedding,
+            "metadata": doc.metadata
+        })
+    }).collect();
+
+    let stdin_data = serde_json::to_string(&docs_json)
+        .map_err(|e| ConfidantError::internal(format!("Failed to serialize documents: {}", e)))?;
+
+    let result_json = call_python_helper(bundled, "upsert", &[db_path, &collection_name], Some(&stdin_data)).await?;
+    let result: serde_json::Value = serde_json::from_str(&result_json)
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?;
+
+    if result["status"].as_str() != Some("success") {
+        return Err(ConfidantError::helper("Failed to upsert documents").with_details(result.to_string()));
+    }
+
+    Ok(UpsertResult {
+        inserted: result["inserted"].as_u64().unwrap_or(0),
+        updated: result["updated"].as_u64().unwrap_or(0),
+    })
+}
+
+/// Delete documents from a collection by id. Ids that do not exist are ignored.
+#[tauri::command]
+pub async fn delete_documents(
+    app: AppHandle,
+    collection_name: String,
+    ids: Vec<String>,
+) -> Result<DeleteResult, ConfidantError> {
+    let bundled = crate::python_bundle::resolve_bundled_python(&app);
+    let store = open_store().await?;
+    let db_path = store.db_path.as_str();
+
+    check_not_reembedding(&collection_name)?;
+    let _write = lock_collection_write(&collection_name).await;
+
+    #[cfg(debug_assertions)]
+    eprintln!("[Vector Store] Deleting {} documents from collection: {}", ids.len(), collection_name);
+
+    let ids_json = serde_json::to_string(&ids)
+        .map_err(|e| ConfidantError::internal(format!("Failed to serialize ids: {}", e)))?;
+
+    let result_json = call_python_helper(bundled, "delete_documents", &[db_path, &collection_name], Some(&ids_json)).await?;
+    let result: serde_json::Value = serde_json::from_str(&result_json)
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?;
+
+    if result["status"].as_str() != Some("success") {
+        return Err(ConfidantError::helper("Failed to delete documents").with_details(result.to_string()));
+    }
+
+    Ok(DeleteResult {
+        deleted: result["deleted"].as_u64().unwrap_or(0),
+    })
+}
+
+/// Refuse filters that would match every document: `{}`, a non-object, or `$and`/`$or` with no conditions.
+fn check_delete_filter(where_json: &str) -> Result<(), ConfidantError> {
+    fn has_condition(filter: &serde_json::Value) -> bool {
+        let Some(obj) = filter.as_object() else {
+            return false;
+        };
+        !obj.is_empty()
+            && obj.iter().all(|(key, value)| match key.as_str() {
+                "$and" | "$or" => value
+                    .as_array()
+                    .is_some_and(|items| !items.is_empty() && items.iter().all(has_condition)),
+                _ => true,
+            })
+    }
+    let filter: serde_json::Value = serde_json::from_str(where_json)
+        .map_err(|e| ConfidantError::validation(format!("Invalid filter JSON: {}", e)))?;
+    if !has_condition(&filter) {
+        return Err(ConfidantError::validation(
+            "delete_documents_by_filter needs a non-empty filter; it would delete the whole collection",
+        ));
+    }
+    Ok(())
+}
+
+/// Delete every document in a collection matching a metadata filter (same `where_json` format as get_documents_by_filter).
+#[tauri::command]
+pub async fn delete_documents_by_filter(
+    app: AppHandle,
+    collection_name: String,
+    where_json: String,
+) -> Result<DeleteResult, ConfidantError> {
+    check_delete_filter(&where_json)?;
+    let bundled = crate::python_bundle::resolve_bundled_python(&app);
+    let store = open_store().await?;
+    let db_path = store.db_path.as_str();
+
+    check_not_reembedding(&collection_name)?;
+    let _write = lock_collection_write(&collection_name).await;
 
     let result_json = call_python_helper(
         bundled,
-        "get_by_filter",
+        "delete_by_filter",
         &[db_path, &collection_name, &where_json],
         None,
-    )?;
+    ).await?;
     let result: serde_json::Value = serde_json::from_str(&result_json)
-        .map_err(|e| format!("Failed to parse Python response: {}", e))?;
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?;
 
     if result["status"].as_str() != Some("success") {
-        return Err(format!("get_documents_by_filter failed: {:?}", result));
+        return Err(ConfidantError::helper("delete_documents_by_filter failed").with_details(result.to_string()));
     }
 
-    let docs_array = result["documents"]
+    Ok(DeleteResult {
+        deleted: result["deleted"].as_u64().unwrap_or(0),
+    })
+}
+
+/// Get documents from a collection by id.
+#[tauri::command]
+pub async fn get_documents(
+    app: AppHandle,
+    collection_name: String,
+    ids: Vec<String>,
+) -> Result<GetDocumentsResult, ConfidantError> {
+    let bundled = crate::python_bundle::resolve_bundled_python(&app);
+    let store = open_store().await?;
+    let db_path = store.db_path.as_str();
+
+    let ids_json = serde_json::to_string(&ids)
+        .map_err(|e| ConfidantError::internal(format!("Failed to serialize ids: {}", e)))?;
+
+    let _read = lock_collection_read(&collection_name).await;
+    let result_json = call_python_helper(bundled, "get_documents", &[db_path, &collection_name], Some(&ids_json)).await?;
+    let result: serde_json::Value = serde_json::from_str(&result_json)
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?;
+
+    if result["status"].as_str() != Some("success") {
+        return Err(ConfidantError::helper("get_documents failed").with_details(result.to_string()));
+    }
+
+    let documents: Vec<FilterDocument> = result["documents"]
         .as_array()
-        .ok_or("Invalid response: missing documents")?;
-    let documents: Result<Vec<FilterDocument>, _> = docs_array
+        .ok_or_else(|| ConfidantError::helper("Invalid response: missing documents"))?
         .iter()
-        .map(|d| {
-            Ok(FilterDocument {
-                id: d["id"].as_str().unwrap_or("").to_string(),
-                text: d["text"].as_str().unwrap_or("").to_string(),
-                metadata: d["metadata"].clone(),
-            })
+        .map(|d| FilterDocument {
+            id: d["id"].as_str().unwrap_or("").to_string(),
+            text: d["text"].as_str().unwrap_or("").to_string(),
+            metadata: d["metadata"].clone(),
+            embedding: None,
         })
         .collect();
-    documents.map_err(|e: serde_json::Error| format!("Failed to parse documents: {}", e))
+    let missing: Vec<String> = result["missing"]
+        .as_array()
+        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+        .unwrap_or_default();
+
+    Ok(GetDocumentsResult {
+        found: documents.len() as u64,
+        documents,
+        missing,
+    })
+}
+
+/// Get every document in a collection, including embeddings (used to export KB packages).
+pub async fn get_all_documents(app: &AppHandle, collection_name: &str) -> Result<Vec<VectorDocument>, ConfidantError> {
+    let bundled = crate::python_bundle::resolve_bundled_python(app);
+    let store = open_store().await?;
+    let db_path = store.db_path.as_str();
+
+    let _read = lock_collection_read(collection_name).await;
+    export_documents(bundled, db_path, collection_name).await
+}
+
+/// Every document of a collection, with embeddings. The caller holds the store and collection locks.
+pub(crate) async fn export_documents(
+    bundled: Option<(PathBuf, PathBuf)>,
+    db_path: &str,
+    collection_name: &str,
+) -> Result<Vec<VectorDocument>, ConfidantError> {
+    let result_json = call_python_helper(bundled, "export", &[db_path, collection_name], None).await?;
+    let result: serde_json::Value = serde_json::from_str(&result_json)
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?;
+
+    if result["status"].as_str() != Some("success") {
+        return Err(ConfidantError::helper("Failed to export documents").with_details(result.to_string()));
+    }
+
+    let documents = result["documents"]
+        .as_array()
+        .ok_or_else(|| ConfidantError::helper("Invalid response: missing documents"))?
+        .iter()
+        .map(|d| VectorDocument {
+            id: d["id"].as_str().unwrap_or("").to_string(),
+            text: d["text"].as_str().unwrap_or("").to_string(),
+            embedding: d["embedding"]
+                .as_array()
+                .map(|arr| arr.iter().filter_map(|v| v.as_f64().map(|f| f as f32)).collect())
+                .unwrap_or_default(),
+            metadata: d["metadata"].clone(),
+        })
+        .collect();
+    Ok(documents)
 }
 
 /// Get statistics for a specific collection
 #[tauri::command]
-pub async fn get_collection_stats_by_name(app: AppHandle, collection_name: String) -> Result<serde_json::Value, String> {
+pub async fn get_collection_stats_by_name(app: AppHandle, collection_name: String) -> Result<serde_json::Value, ConfidantError> {
     let bundled = crate::python_bundle::resolve_bundled_python(&app);
-    let state = VECTOR_STORE_STATE.lock()
-        .map_err(|e| format!("Failed to lock state: {}", e))?;
-    
-    if !state.is_initialized {
-        return Err("Vector store not initialized. Call initialize_vector_store first.".to_string());
-    }
-
-    let db_path = state.db_path.as_ref()
-        .ok_or("Database path not set")?
-        .to_str()
-        .ok_or("Invalid database path")?;
+    let store = open_store().await?;
+    let db_path = store.db_path.as_str();
 
     // Call Python helper
-    let result_json = call_python_helper(bundled, "stats", &[db_path, &collection_name], None)?;
+    let _read = lock_collection_read(&collection_name).await;
+    let result_json = call_python_helper(bundled, "stats", &[db_path, &collection_name], None).await?;
     let result: serde_json::Value = serde_json::from_str(&result_json)
-        .map_err(|e| format!("Failed to parse Python response: {}", e))?;
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?;
     
     Ok(result)
 }
 
 /// Initialize user vector store collection
 #[tauri::command]
-pub async fn initialize_user_vector_store(app: AppHandle, user_id: String) -> Result<(), String> {
+pub async fn initialize_user_vector_store(app: AppHandle, user_id: String) -> Result<(), ConfidantError> {
     let collection_name = format!("dant_knowledge_user_{}", user_id);
     initialize_vector_store(app, collection_name, None).await
 }
 
-/// Delete user knowledge base collection
+/// Delete user knowledge base collection. Works before the store was initialized (e.g. from delete_user).
 #[tauri::command]
-pub async fn delete_user_knowledge_base(app: AppHandle, user_id: String) -> Result<(), String> {
+pub async fn delete_user_knowledge_base(app: AppHandle, user_id: String) -> Result<(), ConfidantError> {
     let bundled = crate::python_bundle::resolve_bundled_python(&app);
-    let state = VECTOR_STORE_STATE.lock()
-        .map_err(|e| format!("Failed to lock state: {}", e))?;
-    
-    if !state.is_initialized {
-        return Err("Vector store not initialized. Call initialize_vector_store first.".to_string());
-    }
-
-    let db_path = state.db_path.as_ref()
-        .ok_or("Database path not set")?
-        .to_str()
-        .ok_or("Invalid database path")?;
-    
     let collection_name = format!("dant_knowledge_user_{}", user_id);
 
     #[cfg(debug_assertions)]
     eprintln!("[Vector Store] Deleting user KB collection: {}", collection_name);
 
+    let store = open_store_or_default(&app).await?;
+    let _write = lock_collection_write(&collection_name).await;
+
     // Call Python helper to delete collection
-    let result_json = call_python_helper(bundled, "delete_collection", &[db_path, &collection_name], None)?;
+    let result_json = call_python_helper(bundled, "delete_collection", &[&store.db_path, &collection_name], None).await?;
     let result: serde_json::Value = serde_json::from_str(&result_json)
-        .map_err(|e| format!("Failed to parse Python response: {}", e))?;
-    
+        .map_err(|e| ConfidantError::helper(format!("Failed to parse Python response: {}", e)))?;
+
     if result["status"].as_str() != Some("success") {
-        return Err(format!("Failed to delete collection: {:?}", result));
+        return Err(ConfidantError::helper("Failed to delete collection").with_details(result.to_string()));
+    }
+
+    if let Ok(mut models) = COLLECTION_MODELS.lock() {
+        models.remove(&collection_name);
     }
-    
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::time::{Duration, Instant};
+
+    fn hit(collection: &str, id: &str, text: &str, score: f32) -> (String, SearchResult) {
+        let result: SearchResult = serde_json::from_value(serde_json::json!({
+            "id": id, "text": text, "score": score, "metadata": {},
+        }))
+        .unwrap();
+        (collection.to_string(), result)
+    }
+
+    #[test]
+    fn federated_merge_normalizes_per_collection_and_applies_weights() {
+        let hits = vec![
+            hit("global", "g1", "Breathing exercises", 0.9),
+            hit("global", "g2", "Sleep hygiene", 0.5),
+            hit("user", "u1", "My therapist's number", 0.4),
+            hit("user", "u2", "Journal entry", 0.2),
+        ];
+        // A weak collection no longer drags the other collection's scores down
+        let merged = merge_federated_results(hits, None, 10);
+        let score = |id: &str| merged.iter().find(|h| h.result.id == id).unwrap().result.score;
+        assert_eq!(score("g1"), 1.0);
+        assert_eq!(score("u1"), 1.0);
+        assert_eq!(score("g2"), 0.0);
+        let raw = merged.iter().find(|h| h.result.id == "u1").unwrap().raw_score;
+        assert_eq!(raw, 0.4);
+
+        let hits = vec![
+            hit("global", "g1", "Breathing exercises", 0.9),
+            hit("global", "g2", "Sleep hygiene", 0.5),
+            hit("global", "g3", "Crisis lines", 0.7),
+            hit("user", "u1", "My therapist's number", 0.4),
+            hit("user", "u2", "Journal entry", 0.2),
+        ];
+        let weights = HashMap::from([("global".to_string(), 0.5), ("user".to_string(), 2.0)]);
+        let merged = merge_federated_results(hits, Some(&weights), 10);
+        let order: Vec<&str> = merged.iter().map(|h| h.result.id.as_str()).collect();
+        assert_eq!(order[0], "u1");
+        assert_eq!(merged[0].result.score, 2.0);
+        let g3 = merged.iter().find(|h| h.result.id == "g3").unwrap();
+        assert!((g3.result.score - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn federated_merge_dedupes_by_id_and_text_and_limits() {
+        let hits = vec![
+            hit("global", "a", "Call 988 for support", 0.9),
+            hit("global", "b", "Other text", 0.3),
+            hit("user", "a", "Call 988 for support", 0.8),
+            hit("user", "c", "  call 988   FOR support ", 0.6),
+            hit("user", "d", "Unrelated", 0.1),
+        ];
+        let weights = HashMap::from([("global".to_string(), 1.0), ("user".to_string(), 0.5)]);
+        let merged = merge_federated_results(hits, Some(&weights), 10);
+        let ids: Vec<(&str, &str)> = merged.iter().map(|h| (h.collection.as_str(), h.result.id.as_str())).collect();
+        // The highest-scoring copy survives; same id and same normalized text are duplicates
+        assert_eq!(ids.len(), 3);
+        assert_eq!(ids[0], ("global", "a"));
+        assert!(ids.contains(&("global", "b")));
+        assert!(ids.contains(&("user", "d")));
+
+        let hits = vec![hit("global", "a", "x", 0.9), hit("global", "b", "y", 0.5), hit("global", "c", "z", 0.1)];
+        assert_eq!(merge_federated_results(hits, None, 2).len(), 2);
+    }
+
+    #[test]
+    fn delete_filter_must_have_a_condition() {
+        for empty in ["{}", "[]", "null", r#"{"$and": []}"#, r#"{"$or": [{}]}"#, r#"{"$and": [{"a": 1}, {}]}"#] {
+            let err = check_delete_filter(empty).unwrap_err();
+            assert_eq!(err.code(), "validation", "{}", empty);
+        }
+        assert!(check_delete_filter("not json").is_err());
+        assert!(check_delete_filter(r#"{"source": "seed"}"#).is_ok());
+        assert!(check_delete_filter(r#"{"$and": [{"a": 1}, {"b": {"$ne": 2}}]}"#).is_ok());
+    }
+
+    /// Helper stand-in that takes 0.5s per call, like a slow ChromaDB query.
+    const SLOW_HELPER: &str = "import time\ntime.sleep(0.5)\nprint('{\"status\": \"success\"}')\n";
+
+    /// Absolute path of a Python 3 on PATH (the bundled-python branch requires one that exists on disk).
+    fn find_python() -> Option<PathBuf> {
+        ["python3", "python"].into_iter().find_map(|name| {
+            let output = std::process::Command::new(name)
+                .args(["-c", "import sys; print(sys.executable)"])
+                .output()
+                .ok()
+                .filter(|o| o.status.success())?;
+            Some(PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()))
+        })
+    }
+
+    #[tokio::test]
+    async fn reads_and_writes_to_other_collections_run_in_parallel() {
+        let Some(python) = find_python() else {
+            eprintln!("python not found; skipping");
+            return;
+        };
+        let dir = std::env::temp_dir().join(format!("confidant-vs-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("chromadb_helper.py"), SLOW_HELPER).unwrap();
+        {
+            let mut state = VECTOR_STORE_STATE.lock().unwrap();
+            state.is_initialized = true;
+            state.db_path = Some(dir.clone());
+        }
+        let bundled = Some((python, dir.clone()));
+
+        let read = |collection: &'static str| {
+            let bundled = bundled.clone();
+            async move {
+                let store = open_store().await?;
+                let _read = lock_collection_read(collection).await;
+                call_python_helper(bundled, "stats", &[store.db_path.as_str(), collection], None).await
+            }
+        };
+        let write = |collection: &'static str| {
+            let bundled = bundled.clone();
+            async move {
+                let store = open_store().await?;
+                let _write = lock_collection_write(collection).await;
+                call_python_helper(bundled, "upsert", &[store.db_path.as_str(), collection], Some("[]")).await
+            }
+        };
+
+        let started = Instant::now();
+        let results = futures_util::future::join_all(vec![
+            Box::pin(read("c")) as Pin<Box<dyn Future<Output = Result<String, ConfidantError>>>>,
+            Box::pin(read("c")),
+            Box::pin(read("c")),
+            Box::pin(write("other")),
+        ])
+        .await;
+        let elapsed = started.elapsed();
+        let _ = fs::remove_dir_all(&dir);
+
+        for result in results {
+            assert!(result.unwrap().contains("success"));
+        }
+        // Four 0.5s calls one after another would take 2s
+        assert!(elapsed < Duration::from_millis(1500), "calls did not overlap: {:?}", elapsed);
+    }
+}
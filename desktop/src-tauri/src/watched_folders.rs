@@ -0,0 +1,555 @@
+// Watched Folders - Opt-in per-user folder sync into the user's personal knowledge collection
+//
+// Each user may register one folder. A background poller (and sync_watched_folder on demand) scans it,
+// compares files against a content-hash manifest, and re-chunks/re-embeds only new or changed files into
+// dant_knowledge_user_{id}; chunks of deleted files are removed. The manifest is persisted after every
+// file, so a sync interrupted by a restart resumes where it left off.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::ConfidantError;
+use crate::ingest::{ingest_file, is_supported_file, DEFAULT_CHUNK_OVERLAP, DEFAULT_CHUNK_SIZE};
+use crate::vector_store::{delete_documents_by_filter, initialize_vector_store};
+
+/// How often the background poller rescans registered folders.
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Event emitted after a sync that changed anything.
+pub const WATCHED_FOLDER_SYNCED_EVENT: &str = "watched-folder-synced";
+
+lazy_static::lazy_static! {
+    /// Users with a sync in progress (poller and manual sync never run concurrently for one user).
+    static ref SYNCING_USERS: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+}
+
+/// Folder registered by a user.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchedFolderConfig {
+    pub path: String,
+    pub added_at: String,
+}
+
+/// Manifest entry for one synced file.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SyncedFile {
+    /// SHA-256 of the file content.
+    hash: String,
+    /// Size and mtime at last sync; when both are unchanged the file is not re-hashed.
+    size: u64,
+    modified: u64,
+    chunks: usize,
+    synced_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct SyncManifest {
+    /// Folder the entries were synced from; after switching folders, the old files are removed on the next sync.
+    folder: String,
+    files: BTreeMap<String, SyncedFile>,
+    #[serde(default)]
+    last_sync: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedFile {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderSyncReport {
+    pub user_id: String,
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+    pub failed: Vec<FailedFile>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchedFolderStatus {
+    pub path: String,
+    pub file_count: usize,
+    pub last_sync: Option<String>,
+}
+
+fn user_collection(user_id: &str) -> String {
+    format!("dant_knowledge_user_{}", user_id)
+}
+
+/// Get app data dir for watched folder state (data/).
+fn get_data_dir(app: &AppHandle) -> Result<PathBuf, ConfidantError> {
+    let base_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| ConfidantError::io(format!("Failed to get app data dir: {}", e)))?;
+    let data_dir = base_dir.join("data");
+    fs::create_dir_all(&data_dir)
+        .map_err(|e| ConfidantError::io(format!("Failed to create data directory: {}", e)))?;
+    Ok(data_dir)
+}
+
+fn load_configs(app: &AppHandle) -> Result<HashMap<String, WatchedFolderConfig>, ConfidantError> {
+    let path = get_data_dir(app)?.join("watched_folders.json");
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| ConfidantError::io(format!("Failed to read watched folders: {}", e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| ConfidantError::internal(format!("Failed to parse watched folders: {}", e)))
+}
+
+fn save_configs(app: &AppHandle, configs: &HashMap<String, WatchedFolderConfig>) -> Result<(), ConfidantError> {
+    let path = get_data_dir(app)?.join("watched_folders.json");
+    let content = serde_json::to_string_pretty(configs)
+        .map_err(|e| ConfidantError::internal(format!("Failed to serialize watched folders: {}", e)))?;
+    fs::write(&path, content).map_err(|e| ConfidantError::io(format!("Failed to write watched folders: {}", e)))
+}
+
+/// Per-user sync manifest. `user_id` is checked against the known users, so it cannot name another path.
+fn manifest_path(app: &AppHandle, user_id: &str) -> Result<PathBuf, ConfidantError> {
+    let users = crate::user_management::load_users(app)?;
+    if !users.iter().any(|u| u.id == user_id) {
+        return Err(ConfidantError::not_found("User not found"));
+    }
+    manifest_file(app, user_id)
+}
+
+/// Manifest path without the user check; only for ids that came from the user list.
+fn manifest_file(app: &AppHandle, user_id: &str) -> Result<PathBuf, ConfidantError> {
+    let dir = get_data_dir(app)?.join("watched_folders");
+    fs::create_dir_all(&dir)
+        .map_err(|e| ConfidantError::io(format!("Failed to create watched folder directory: {}", e)))?;
+    Ok(dir.join(format!("manifest_{}.json", user_id)))
+}
+
+fn load_manifest(app: &AppHandle, user_id: &str) -> Result<SyncManifest, ConfidantError> {
+    let path = manifest_path(app, user_id)?;
+    if !path.exists() {
+        return Ok(SyncManifest::default());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| ConfidantError::io(format!("Failed to read sync manifest: {}", e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| ConfidantError::internal(format!("Failed to parse sync manifest: {}", e)))
+}
+
+fn save_manifest(app: &AppHandle, user_id: &str, manifest: &SyncManifest) -> Result<(), ConfidantError> {
+    let path = manifest_path(app, user_id)?;
+    let tmp = path.with_extension("json.tmp");
+    let content = serde_json::to_string_pretty(manifest)
+        .map_err(|e| ConfidantError::internal(format!("Failed to serialize sync manifest: {}", e)))?;
+    fs::write(&tmp, content).map_err(|e| ConfidantError::io(format!("Failed to write sync manifest: {}", e)))?;
+    fs::rename(&tmp, &path).map_err(|e| ConfidantError::io(format!("Failed to write sync manifest: {}", e)))
+}
+
+/// Supported files under `dir`, recursively, skipping hidden files and directories.
+fn scan_folder(dir: &Path, out: &mut Vec<PathBuf>) -> Result<(), ConfidantError> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| ConfidantError::io(format!("Failed to read folder {}: {}", dir.display(), e)))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        match entry.file_type() {
+            Ok(t) if t.is_dir() => scan_folder(&path, out)?,
+            Ok(t) if t.is_file() && is_supported_file(&path) => out.push(path),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn hash_file(path: &Path) -> Result<String, ConfidantError> {
+    let bytes = fs::read(path).map_err(|e| ConfidantError::io(format!("Failed to read {}: {}", path.display(), e)))?;
+    Ok(hex::encode(Sha256::digest(&bytes)))
+}
+
+fn file_stamp(path: &Path) -> (u64, u64) {
+    fs::metadata(path)
+        .map(|m| {
+            let modified = m
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            (m.len(), modified)
+        })
+        .unwrap_or((0, 0))
+}
+
+/// What a sync has to do with a scanned file whose size or mtime differs from the manifest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileChange {
+    /// Not in the manifest yet.
+    Added,
+    /// Content hash differs from the manifest.
+    Changed,
+    /// Stamp moved but the content is the same; only the stamp is refreshed.
+    Touched,
+}
+
+/// True when size and mtime match the manifest, so the file is skipped without hashing.
+fn stamp_matches(previous: Option<&SyncedFile>, size: u64, modified: u64) -> bool {
+    previous.is_some_and(|f| f.size == size && f.modified == modified)
+}
+
+fn classify_file(previous: Option<&SyncedFile>, hash: &str) -> FileChange {
+    match previous {
+        None => FileChange::Added,
+        Some(f) if f.hash == hash => FileChange::Touched,
+        Some(_) => FileChange::Changed,
+    }
+}
+
+/// Manifest entries whose files are no longer in the folder.
+fn stale_entries(manifest: &SyncManifest, present: &HashSet<String>) -> Vec<String> {
+    manifest.files.keys().filter(|p| !present.contains(*p)).cloned().collect()
+}
+
+async fn remove_file_chunks(app: &AppHandle, collection: &str, path: &str) -> Result<(), ConfidantError> {
+    let where_json = serde_json::json!({ "source_path": path }).to_string();
+    delete_documents_by_filter(app.clone(), collection.to_string(), where_json).await?;
+    Ok(())
+}
+
+/// Bring the user's collection in line with their folder. Only new or changed files are re-embedded.
+async fn sync_user_folder(app: &AppHandle, user_id: &str, folder: &str) -> Result<FolderSyncReport, ConfidantError> {
+    let collection = user_collection(user_id);
+    initialize_vector_store(app.clone(), collection.clone(), None).await?;
+
+    let mut manifest = load_manifest(app, user_id)?;
+    if manifest.folder != folder {
+        // Folder changed: everything from the old folder is removed below
+        manifest.folder = folder.to_string();
+    }
+
+    let root = PathBuf::from(folder);
+    let mut paths = tauri::async_runtime::spawn_blocking(move || {
+        let mut paths = Vec::new();
+        scan_folder(&root, &mut paths).map(|_| paths)
+    })
+    .await
+    .map_err(|e| ConfidantError::internal(format!("Folder scan failed: {}", e)))??;
+    paths.sort();
+
+    let mut report = FolderSyncReport {
+        user_id: user_id.to_string(),
+        ..Default::default()
+    };
+    let present: HashSet<String> = paths.iter().map(|p| p.to_string_lossy().to_string()).collect();
+
+    for path in stale_entries(&manifest, &present) {
+        match remove_file_chunks(app, &collection, &path).await {
+            Ok(()) => {
+                manifest.files.remove(&path);
+                report.removed += 1;
+                save_manifest(app, user_id, &manifest)?;
+            }
+            Err(e) => report.failed.push(FailedFile { path, error: e.to_string() }),
+        }
+    }
+
+    for path in paths {
+        let key = path.to_string_lossy().to_string();
+        let (size, modified) = file_stamp(&path);
+        if stamp_matches(manifest.files.get(&key), size, modified) {
+            report.unchanged += 1;
+            continue;
+        }
+        let hash_path = path.clone();
+        let hash = match tauri::async_runtime::spawn_blocking(move || hash_file(&hash_path)).await {
+            Ok(Ok(h)) => h,
+            Ok(Err(e)) => {
+                report.failed.push(FailedFile { path: key, error: e.to_string() });
+                continue;
+            }
+            Err(e) => {
+                report.failed.push(FailedFile { path: key, error: format!("Hashing failed: {}", e) });
+                continue;
+            }
+        };
+        let change = classify_file(manifest.files.get(&key), &hash);
+        if change == FileChange::Touched {
+            if let Some(entry) = manifest.files.get_mut(&key) {
+                entry.size = size;
+                entry.modified = modified;
+            }
+            report.unchanged += 1;
+            save_manifest(app, user_id, &manifest)?;
+            continue;
+        }
+
+        match ingest_file(app, &path, &collection, DEFAULT_CHUNK_SIZE, DEFAULT_CHUNK_OVERLAP, |_, _, _| {}).await {
+            Ok(chunks) => {
+                manifest.files.insert(
+                    key,
+                    SyncedFile {
+                        hash,
+                        size,
+                        modified,
+                        chunks,
+                        synced_at: chrono::Utc::now().to_rfc3339(),
+                    },
+                );
+                if change == FileChange::Changed {
+                    report.updated += 1;
+                } else {
+                    report.added += 1;
+                }
+                save_manifest(app, user_id, &manifest)?;
+            }
+            Err(e) => report.failed.push(FailedFile { path: key, error: e.to_string() }),
+        }
+    }
+
+    manifest.last_sync = Some(chrono::Utc::now().to_rfc3339());
+    save_manifest(app, user_id, &manifest)?;
+    Ok(report)
+}
+
+/// Run a sync unless one is already running for this user (returns None in that case).
+async fn sync_if_idle(
+    app: &AppHandle,
+    user_id: &str,
+    folder: &str,
+) -> Result<Option<FolderSyncReport>, ConfidantError> {
+    {
+        let mut syncing = SYNCING_USERS.lock()
+            .map_err(|e| ConfidantError::internal(format!("Failed to lock sync state: {}", e)))?;
+        if !syncing.insert(user_id.to_string()) {
+            return Ok(None);
+        }
+    }
+    let result = sync_user_folder(app, user_id, folder).await;
+    if let Ok(mut syncing) = SYNCING_USERS.lock() {
+        syncing.remove(user_id);
+    }
+    let report = result?;
+    if report.added + report.updated + report.removed > 0 || !report.failed.is_empty() {
+        let _ = app.emit(WATCHED_FOLDER_SYNCED_EVENT, &report);
+    }
+    Ok(Some(report))
+}
+
+/// Start the background poller. Call once at startup; it only touches users who opted in.
+pub fn start_folder_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Ok(configs) = load_configs(&app) {
+                for (user_id, config) in &configs {
+                    if let Err(e) = sync_if_idle(&app, user_id, &config.path).await {
+                        eprintln!("[Confidant] Watched folder sync failed for user {}: {}", user_id, e);
+                    }
+                }
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// Register (or replace) the folder watched for a user and run a first sync.
+#[tauri::command]
+pub async fn set_watched_folder(
+    app: AppHandle,
+    user_id: String,
+    path: String,
+) -> Result<FolderSyncReport, ConfidantError> {
+    let users = crate::user_management::load_users(&app)?;
+    if !users.iter().any(|u| u.id == user_id) {
+        return Err(ConfidantError::not_found("User not found"));
+    }
+    let folder = PathBuf::from(&path);
+    if !folder.is_dir() {
+        return Err(ConfidantError::validation(format!("Not a folder: {}", path)));
+    }
+    let folder = folder
+        .canonicalize()
+        .map_err(|e| ConfidantError::io(format!("Failed to resolve folder: {}", e)))?
+        .to_string_lossy()
+        .to_string();
+
+    let mut configs = load_configs(&app)?;
+    configs.insert(
+        user_id.clone(),
+        WatchedFolderConfig {
+            path: folder.clone(),
+            added_at: chrono::Utc::now().to_rfc3339(),
+        },
+    );
+    save_configs(&app, &configs)?;
+
+    sync_if_idle(&app, &user_id, &folder)
+        .await?
+        .ok_or_else(|| ConfidantError::validation("A sync is already running for this user"))
+}
+
+/// Stop watching a user's folder. With `remove_documents`, chunks ingested from it are deleted too.
+#[tauri::command]
+pub async fn remove_watched_folder(
+    app: AppHandle,
+    user_id: String,
+    remove_documents: Option<bool>,
+) -> Result<(), ConfidantError> {
+    let mut configs = load_configs(&app)?;
+    if configs.remove(&user_id).is_none() {
+        return Ok(());
+    }
+    save_configs(&app, &configs)?;
+
+    if remove_documents.unwrap_or(false) {
+        let collection = user_collection(&user_id);
+        initialize_vector_store(app.clone(), collection.clone(), None).await?;
+        let manifest = load_manifest(&app, &user_id)?;
+        for path in manifest.files.keys() {
+            remove_file_chunks(&app, &collection, path).await?;
+        }
+        let path = manifest_path(&app, &user_id)?;
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| ConfidantError::io(format!("Failed to remove sync manifest: {}", e)))?;
+        }
+    }
+    Ok(())
+}
+
+/// Drop a user's watched folder registration and manifest (used when the user is deleted, so `user_id`
+/// is no longer in the user list; delete_user looked it up there).
+pub fn forget_user(app: &AppHandle, user_id: &str) -> Result<(), ConfidantError> {
+    let mut configs = load_configs(app)?;
+    if configs.remove(user_id).is_some() {
+        save_configs(app, &configs)?;
+    }
+    let path = manifest_file(app, user_id)?;
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| ConfidantError::io(format!("Failed to remove sync manifest: {}", e)))?;
+    }
+    Ok(())
+}
+
+/// Sync a user's watched folder now.
+#[tauri::command]
+pub async fn sync_watched_folder(app: AppHandle, user_id: String) -> Result<FolderSyncReport, ConfidantError> {
+    let configs = load_configs(&app)?;
+    let config = configs
+        .get(&user_id)
+        .ok_or_else(|| ConfidantError::not_found(format!("No watched folder for user {}", user_id)))?;
+    sync_if_idle(&app, &user_id, &config.path)
+        .await?
+        .ok_or_else(|| ConfidantError::validation("A sync is already running for this user"))
+}
+
+/// Watched folder and sync state for a user, if one is registered.
+#[tauri::command]
+pub async fn get_watched_folder(
+    app: AppHandle,
+    user_id: String,
+) -> Result<Option<WatchedFolderStatus>, ConfidantError> {
+    let configs = load_configs(&app)?;
+    let Some(config) = configs.get(&user_id) else {
+        return Ok(None);
+    };
+    let manifest = load_manifest(&app, &user_id)?;
+    Ok(Some(WatchedFolderStatus {
+        path: config.path.clone(),
+        file_count: manifest.files.len(),
+        last_sync: manifest.last_sync,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn synced(hash: &str, size: u64, modified: u64) -> SyncedFile {
+        SyncedFile {
+            hash: hash.to_string(),
+            size,
+            modified,
+            chunks: 1,
+            synced_at: "2026-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn new_file_is_added() {
+        assert!(!stamp_matches(None, 10, 100));
+        assert_eq!(classify_file(None, "aa"), FileChange::Added);
+    }
+
+    #[test]
+    fn changed_content_is_reingested() {
+        let prev = synced("aa", 10, 100);
+        assert!(!stamp_matches(Some(&prev), 12, 200));
+        assert_eq!(classify_file(Some(&prev), "bb"), FileChange::Changed);
+    }
+
+    #[test]
+    fn touched_file_with_same_hash_is_not_reingested() {
+        let prev = synced("aa", 10, 100);
+        assert!(!stamp_matches(Some(&prev), 10, 200));
+        assert_eq!(classify_file(Some(&prev), "aa"), FileChange::Touched);
+    }
+
+    #[test]
+    fn removed_files_are_stale() {
+        let mut manifest = SyncManifest::default();
+        manifest.files.insert("/w/a.md".to_string(), synced("aa", 1, 1));
+        manifest.files.insert("/w/b.md".to_string(), synced("bb", 1, 1));
+        let present: HashSet<String> = ["/w/a.md".to_string()].into_iter().collect();
+        assert_eq!(stale_entries(&manifest, &present), vec!["/w/b.md".to_string()]);
+    }
+
+    #[test]
+    fn interrupted_sync_resumes_with_unsynced_files() {
+        // The manifest is saved after each file, so a restart sees only the files synced so far
+        let mut manifest = SyncManifest {
+            folder: "/w".to_string(),
+            ..Default::default()
+        };
+        manifest.files.insert("/w/a.md".to_string(), synced("aa", 10, 100));
+        let saved = serde_json::to_string(&manifest).unwrap();
+        let resumed: SyncManifest = serde_json::from_str(&saved).unwrap();
+        assert_eq!(resumed.last_sync, None);
+
+        assert!(stamp_matches(resumed.files.get("/w/a.md"), 10, 100));
+        assert!(!stamp_matches(resumed.files.get("/w/b.md"), 20, 100));
+        assert_eq!(classify_file(resumed.files.get("/w/b.md"), "bb"), FileChange::Added);
+        let present: HashSet<String> = ["/w/a.md".to_string(), "/w/b.md".to_string()].into_iter().collect();
+        assert!(stale_entries(&resumed, &present).is_empty());
+    }
+
+    #[test]
+    fn scan_skips_hidden_and_unsupported_files() {
+        let dir = std::env::temp_dir().join(format!("confidant-watch-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::create_dir_all(dir.join(".hidden")).unwrap();
+        fs::write(dir.join("a.md"), "a").unwrap();
+        fs::write(dir.join("sub").join("b.txt"), "b").unwrap();
+        fs::write(dir.join(".hidden").join("c.md"), "c").unwrap();
+        fs::write(dir.join("d.exe"), "d").unwrap();
+
+        let mut paths = Vec::new();
+        scan_folder(&dir, &mut paths).unwrap();
+        paths.sort();
+        assert_eq!(paths, vec![dir.join("a.md"), dir.join("sub").join("b.txt")]);
+        assert_eq!(hash_file(&dir.join("a.md")).unwrap(), hex::encode(Sha256::digest(b"a")));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
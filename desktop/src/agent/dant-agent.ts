@@ -7,11 +7,10 @@ import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import { getCachedResponse, cacheResponse, normalizeQuery, initializeCache } from '../utils/response-cache';
 import { logLLMGeneration, logLLMStreamStats } from '../utils/appTiming';
+import { CommandError, ConfidantErrorCode, toCommandError } from '../utils/errors';
+import { findNearestContacts, NearbyContact } from '../utils/phonebook';
 import { LanguageCode } from '../i18n';
 
-const PHONEBOOK_COLLECTION = 'dant_phonebook';
-const MIN_RESULTS_FOR_FALLBACK = 3;
-
 /** Extract country (ISO 3166-1 alpha-2) and postal code from text. Simple heuristics for v1. */
 function extractCountryAndPostal(text: string): { country?: string; postal_code?: string } {
   const lower = text.toLowerCase().trim();
@@ -34,53 +33,13 @@ function extractCountryAndPostal(text: string): { country?: string; postal_code?
   return { country, postal_code };
 }
 
-/** Fetch phone book entries: national for country, then local with fallback if ≤3 results. */
-async function fetchPhoneBookEntries(country: string, postalCode: string): Promise<Array<{ id: string; text: string; metadata: Record<string, unknown> }>> {
-  const nationalWhere = JSON.stringify({ country, postal_code: 'NATIONAL' });
-  const localWhereStrict = JSON.stringify({ country, postal_code: postalCode });
-  let national: Array<{ id: string; text: string; metadata: Record<string, unknown> }> = [];
-  let local: Array<{ id: string; text: string; metadata: Record<string, unknown> }> = [];
+/** Fetch phone book entries: the user's own contacts, local ones nearest first, then national crisis lines. */
+async function fetchPhoneBookEntries(country: string, postalCode: string, userId?: string): Promise<NearbyContact[]> {
   try {
-    [national, local] = await Promise.all([
-      invoke<Array<{ id: string; text: string; metadata: Record<string, unknown> }>>('get_documents_by_filter', {
-        collectionName: PHONEBOOK_COLLECTION,
-        whereJson: nationalWhere,
-      }).catch(() => []),
-      invoke<Array<{ id: string; text: string; metadata: Record<string, unknown> }>>('get_documents_by_filter', {
-        collectionName: PHONEBOOK_COLLECTION,
-        whereJson: localWhereStrict,
-      }).catch(() => []),
-    ]);
+    return (await findNearestContacts(country, postalCode, { userId })).contacts;
   } catch {
     return [];
   }
-  // Fallback: if ≤3 local results, try postal prefix then country only
-  if (local.length <= MIN_RESULTS_FOR_FALLBACK && postalCode.length >= 3) {
-    const prefix = postalCode.slice(0, 5);
-    const prefixWhere = JSON.stringify({ country, postal_code: prefix });
-    const byPrefix = await invoke<Array<{ id: string; text: string; metadata: Record<string, unknown> }>>('get_documents_by_filter', {
-      collectionName: PHONEBOOK_COLLECTION,
-      whereJson: prefixWhere,
-    }).catch(() => []);
-    if (byPrefix.length > local.length) local = byPrefix;
-  }
-  if (local.length <= MIN_RESULTS_FOR_FALLBACK) {
-    const countryOnlyWhere = JSON.stringify({ country, postal_code: { $ne: 'NATIONAL' } });
-    const byCountry = await invoke<Array<{ id: string; text: string; metadata: Record<string, unknown> }>>('get_documents_by_filter', {
-      collectionName: PHONEBOOK_COLLECTION,
-      whereJson: countryOnlyWhere,
-    }).catch(() => []);
-    if (byCountry.length > local.length) local = byCountry;
-  }
-  const seen = new Set<string>();
-  const result: Array<{ id: string; text: string; metadata: Record<string, unknown> }> = [];
-  for (const e of national) {
-    if (!seen.has(e.id)) { seen.add(e.id); result.push(e); }
-  }
-  for (const e of local) {
-    if (!seen.has(e.id)) { seen.add(e.id); result.push(e); }
-  }
-  return result;
 }
 
 /** Language code to display name for system prompt (model responds in this language) */
@@ -104,9 +63,19 @@ export interface ResponseMetrics {
   charsCount: number;
 }
 
+/** Citation info attached to search hits by the backend (see citations.rs). */
+export interface SourceProvenance {
+  source_title: string;
+  document_id: string;
+  chunk_index?: number;
+  page?: number;
+  start_offset?: number;
+  end_offset?: number;
+}
+
 export interface AgentResponse {
   response: string;
-  sources?: Array<{ id: string; text: string; score: number }>;
+  sources?: Array<{ id: string; text: string; score: number; provenance?: SourceProvenance }>;
   usedRAG: boolean;
   isFirstMessage?: boolean;
   /** True when the response came from cache (for UI e.g. fake thinking delay). */
@@ -198,12 +167,12 @@ export class DantAgent {
     // Check if model is loaded
     const isLoaded = await invoke<boolean>('is_model_loaded');
     if (!isLoaded) {
-      throw new Error('Model not initialized. Open Settings and initialize a model.');
+      throw new CommandError('Model not initialized. Open Settings and initialize a model.', 'not_initialized');
     }
 
     // If RAG is enabled, search for relevant context
     let context = '';
-    let sources: Array<{ id: string; text: string; score: number }> = [];
+    let sources: Array<{ id: string; text: string; score: number; provenance?: SourceProvenance }> = [];
     
     if (useRAG) {
       try {
@@ -229,59 +198,46 @@ export class DantAgent {
         }
         
         if (shouldUseRAG) {
-          // Generate embedding ONCE (biggest bottleneck - single call)
-          const queryEmbedding = await invoke<number[]>('generate_embedding', { text: optimizedQuery });
+          // Generate embedding ONCE (biggest bottleneck - single call); embed_query adds the model's query prefix
+          const queryEmbedding = await invoke<number[]>('embed_query', { text: optimizedQuery });
           
-          // Query both KBs IN PARALLEL (not sequential)
+          // Query both KBs in one federated search (backend runs them in parallel, merges and dedupes)
           const userId = options.userId;
-          const [globalResults, userResults] = await Promise.all([
-            invoke<Array<{
-              id: string;
-              text: string;
-              score: number;
-              metadata: any;
-            }>>('search_collection', {
-              collectionName: 'dant_knowledge_global',
-              queryEmbedding,
-              limit: 3  // Get top 3 from global
-            }).catch(() => []), // Fallback to empty array if query fails
-            userId ? invoke<Array<{
-              id: string;
-              text: string;
-              score: number;
-              metadata: any;
-            }>>('search_collection', {
-              collectionName: `dant_knowledge_user_${userId}`,
-              queryEmbedding,
-              limit: 2  // Get top 2 from user KB (more personal, smaller set)
-            }).catch(() => []) : Promise.resolve([]) // Empty if no userId or query fails
-          ]);
-
-          // Merge results by score (descending)
-          const mergedResults = [...globalResults, ...userResults]
-            .filter(r => r.score >= 0.3) // Apply threshold
-            .sort((a, b) => b.score - a.score)
-            .slice(0, 4); // Top 4 total
-
-          // Deduplicate similar results (if score difference < 0.05, keep higher)
-          const deduplicated = mergedResults.reduce((acc, result) => {
-            const isDuplicate = acc.some(existing => 
-              Math.abs(existing.score - result.score) < 0.05 &&
-              existing.text.substring(0, 50) === result.text.substring(0, 50)
-            );
-            if (!isDuplicate) acc.push(result);
-            return acc;
-          }, [] as typeof mergedResults);
-
-          if (deduplicated.length > 0) {
-            sources = deduplicated.map(r => ({
+          const collections = ['dant_knowledge_global'];
+          if (userId) collections.push(`dant_knowledge_user_${userId}`);
+          const merged = await invoke<Array<{
+            id: string;
+            text: string;
+            score: number;
+            raw_score: number;
+            collection: string;
+            metadata: any;
+            provenance?: SourceProvenance;
+            expanded_text?: string;
+          }>>('search_many', {
+            collections,
+            queryEmbedding,
+            limit: 4,  // Top 4 total
+            perCollectionWeights: null,
+            queryText: optimizedQuery,  // Used for reranking when enabled for a collection
+            neighbors: 1  // Include adjacent chunks of ingested documents so passages are not cut mid-paragraph
+          }).catch(() => []); // Fallback to empty array if query fails
+
+          const relevant = merged.filter(r => r.raw_score >= 0.3); // Apply threshold
+
+          if (relevant.length > 0) {
+            sources = relevant.map(r => ({
               id: r.id,
               text: r.text,
-              score: r.score
+              score: r.score,
+              provenance: r.provenance
             }));
 
             // Build context with smart truncation (preserve important info)
-            context = this.buildSmartContext(deduplicated, 800);
+            context = this.buildSmartContext(
+              relevant.map(r => ({ ...r, text: r.expanded_text ?? r.text })),
+              800
+            );
           }
         }
       } catch (err) {
@@ -307,7 +263,7 @@ Acknowledge limits and suggest professional help when appropriate. Never diagnos
     // Phone book: if user provided country + postal code, fetch and inject entries
     const { country: extractedCountry, postal_code: extractedPostal } = extractCountryAndPostal(query);
     if (extractedCountry && extractedPostal) {
-      const phoneBookEntries = await fetchPhoneBookEntries(extractedCountry, extractedPostal);
+      const phoneBookEntries = await fetchPhoneBookEntries(extractedCountry, extractedPostal, options.userId);
       if (phoneBookEntries.length > 0) {
         const phoneBookLines = phoneBookEntries.map(e => {
           const m = e.metadata as Record<string, string>;
@@ -315,9 +271,11 @@ Acknowledge limits and suggest professional help when appropriate. Never diagnos
           const profession = m?.profession ?? '';
           const phone = m?.phone ?? '';
           const address = [m?.address, m?.city, m?.state].filter(Boolean).join(', ');
-          return `${name} | ${profession} | ${phone}${address ? ` | ${address}` : ''}`;
+          const distance = e.distanceKm != null ? ` | ${Math.round(e.distanceKm)} km` : '';
+          const personal = e.personal ? " | (user's own contact)" : '';
+          return `${name} | ${profession} | ${phone}${address ? ` | ${address}` : ''}${distance}${personal}`;
         });
-        context = (context ? context + '\n' : '') + 'Phone book (national and local):\n' + phoneBookLines.join('\n');
+        context = (context ? context + '\n' : '') + 'Phone book (personal, local and national):\n' + phoneBookLines.join('\n');
       }
     }
 
@@ -369,13 +327,13 @@ Assistant:`;
         if (e.payload.streamId !== streamId) return;
         resolveDone(e.payload.full);
       });
-      const unlistenError = await listen<{ streamId: string; error: string }>('llm-stream-error', (e) => {
+      const unlistenError = await listen<{ streamId: string; error: string; code?: ConfidantErrorCode; details?: string | null }>('llm-stream-error', (e) => {
         if (e.payload.streamId !== streamId) return;
-        rejectErr(e.payload.error ?? 'Stream error');
+        rejectErr(new CommandError(e.payload.error ?? 'Stream error', e.payload.code, e.payload.details));
       });
 
       let resolveDone!: (full: string) => void;
-      let rejectErr!: (err: string) => void;
+      let rejectErr!: (err: CommandError) => void;
       const streamPromise = new Promise<string>((resolve, reject) => {
         resolveDone = resolve;
         rejectErr = reject;
@@ -391,8 +349,7 @@ Assistant:`;
         prompt: fullPrompt,
         config,
       }).catch((invokeErr: unknown) => {
-        const msg = invokeErr instanceof Error ? invokeErr.message : String(invokeErr);
-        rejectErr(msg);
+        rejectErr(toCommandError(invokeErr, 'Stream error'));
       });
 
       try {
@@ -484,18 +441,13 @@ Assistant:`;
       metrics,
     };
     } catch (err) {
-      // Extract the actual error message (stream rejects with string, invoke/timeout throw Error)
-      let errorMessage = 'Failed to generate response';
-      if (typeof err === 'string') {
-        errorMessage = err;
-      } else if (err instanceof Error) {
-        errorMessage = err.message;
-        // Remove duplicate "Failed to process query:" prefix if present
-        if (errorMessage.includes('Failed to process query:')) {
-          errorMessage = errorMessage.replace('Failed to process query:', '').trim();
-        }
+      // Keep the backend error code (commands reject with { code, message, details }, the stream with a CommandError)
+      const error = toCommandError(err, 'Failed to generate response');
+      // Remove duplicate "Failed to process query:" prefix if present
+      if (error.message.includes('Failed to process query:')) {
+        error.message = error.message.replace('Failed to process query:', '').trim();
       }
-      throw new Error(errorMessage);
+      throw error;
     }
   }
 
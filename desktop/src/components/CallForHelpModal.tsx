@@ -1,18 +1,11 @@
 import { useState, useRef } from 'react';
-import { invoke } from '@tauri-apps/api/core';
 import { useTranslation } from '../i18n/hooks/useTranslation';
 import { useModalFocusTrap } from '../hooks/useModalFocusTrap';
 import OptionGrid from './OptionGrid';
+import { errorMessage as describeError } from '../utils/errors';
+import { findNearestContacts, NearbyContact } from '../utils/phonebook';
 import './CallForHelpModal.css';
 
-const PHONEBOOK_COLLECTION = 'dant_phonebook';
-const MIN_RESULTS_FOR_FALLBACK = 3;
-
-/** Build ChromaDB-compatible where filter (explicit $eq so national hotlines query returns results). */
-function phonebookWhere(country: string, postalCode: string) {
-  return { $and: [{ country: { $eq: country } }, { postal_code: { $eq: postalCode } }] };
-}
-
 const COUNTRY_OPTIONS = [
   { value: 'US', label: 'United States', flag: '🇺🇸' },
   { value: 'CA', label: 'Canada', flag: '🇨🇦' },
@@ -20,12 +13,6 @@ const COUNTRY_OPTIONS = [
   { value: 'OTHER', label: 'Other', flag: '🌐' },
 ];
 
-interface PhoneBookEntry {
-  id: string;
-  text: string;
-  metadata: Record<string, string>;
-}
-
 interface CallForHelpModalProps {
   isOpen: boolean;
   onClose: () => void;
@@ -37,8 +24,9 @@ export default function CallForHelpModal({ isOpen, onClose, userId }: CallForHel
   const [country, setCountry] = useState<string | undefined>(undefined);
   const [postalCode, setPostalCode] = useState('');
   const [view, setView] = useState<'form' | 'table'>('form');
-  const [national, setNational] = useState<PhoneBookEntry[]>([]);
-  const [local, setLocal] = useState<PhoneBookEntry[]>([]);
+  const [personal, setPersonal] = useState<NearbyContact[]>([]);
+  const [national, setNational] = useState<NearbyContact[]>([]);
+  const [local, setLocal] = useState<NearbyContact[]>([]);
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const dialogRef = useRef<HTMLDivElement>(null);
@@ -50,47 +38,13 @@ export default function CallForHelpModal({ isOpen, onClose, userId }: CallForHel
     setLoading(true);
     setError(null);
     try {
-      const countryCode = country.trim();
-      const nationalWhere = JSON.stringify(phonebookWhere(countryCode, 'NATIONAL'));
-      const nationalList = await invoke<PhoneBookEntry[]>(`get_documents_by_filter`, {
-        collectionName: PHONEBOOK_COLLECTION,
-        whereJson: nationalWhere,
-      }).catch(() => []);
-      setNational(Array.isArray(nationalList) ? nationalList : []);
-
-      let localList: PhoneBookEntry[] = [];
-      if (postalCode.trim()) {
-        const localWhere = JSON.stringify(phonebookWhere(countryCode, postalCode.trim()));
-        localList = await invoke<PhoneBookEntry[]>(`get_documents_by_filter`, {
-          collectionName: PHONEBOOK_COLLECTION,
-          whereJson: localWhere,
-        }).catch(() => []);
-        localList = Array.isArray(localList) ? localList : [];
-
-        if (localList.length <= MIN_RESULTS_FOR_FALLBACK && postalCode.trim().length >= 3) {
-          const prefix = postalCode.trim().slice(0, 5);
-          const prefixWhere = JSON.stringify(phonebookWhere(countryCode, prefix));
-          const byPrefix = await invoke<PhoneBookEntry[]>(`get_documents_by_filter`, {
-            collectionName: PHONEBOOK_COLLECTION,
-            whereJson: prefixWhere,
-          }).catch(() => []);
-          if (Array.isArray(byPrefix) && byPrefix.length > localList.length) localList = byPrefix;
-        }
-        if (localList.length <= MIN_RESULTS_FOR_FALLBACK) {
-          const countryOnlyWhere = JSON.stringify({
-            $and: [{ country: { $eq: countryCode } }, { postal_code: { $ne: 'NATIONAL' } }],
-          });
-          const byCountry = await invoke<PhoneBookEntry[]>(`get_documents_by_filter`, {
-            collectionName: PHONEBOOK_COLLECTION,
-            whereJson: countryOnlyWhere,
-          }).catch(() => []);
-          if (Array.isArray(byCountry) && byCountry.length > localList.length) localList = byCountry;
-        }
-      }
-      setLocal(localList);
+      const { contacts } = await findNearestContacts(country.trim(), postalCode.trim(), { userId: userId || undefined });
+      setPersonal(contacts.filter((c) => c.personal));
+      setNational(contacts.filter((c) => c.national));
+      setLocal(contacts.filter((c) => !c.national && !c.personal));
       setView('table');
     } catch (err) {
-      setError(err instanceof Error ? err.message : t('errors.somethingWentWrong'));
+      setError(describeError(err, t('errors.somethingWentWrong')));
     } finally {
       setLoading(false);
     }
@@ -101,7 +55,7 @@ export default function CallForHelpModal({ isOpen, onClose, userId }: CallForHel
     setError(null);
   };
 
-  const hasResults = national.length > 0 || local.length > 0;
+  const hasResults = personal.length > 0 || national.length > 0 || local.length > 0;
 
   if (!isOpen) return null;
 
@@ -181,6 +135,37 @@ export default function CallForHelpModal({ isOpen, onClose, userId }: CallForHel
               )}
               {!error && hasResults && (
                 <div className="call-for-help-results">
+                  {personal.length > 0 && (
+                    <section className="call-for-help-section" aria-labelledby="personal-heading">
+                      <h3 id="personal-heading" className="call-for-help-section-title">
+                        {t('ui.personalContacts')}
+                      </h3>
+                      <table className="call-for-help-table" role="table">
+                        <thead>
+                          <tr>
+                            <th scope="col">{t('ui.phoneBookName')}</th>
+                            <th scope="col">{t('ui.phoneBookProfession')}</th>
+                            <th scope="col">{t('ui.phoneBookPhone')}</th>
+                            <th scope="col">{t('ui.phoneBookAddress')}</th>
+                          </tr>
+                        </thead>
+                        <tbody>
+                          {personal.map((e) => (
+                            <tr key={e.id}>
+                              <td>{(e.metadata?.name as string) ?? e.text}</td>
+                              <td>{(e.metadata?.profession as string) ?? ''}</td>
+                              <td>{(e.metadata?.phone as string) ?? ''}</td>
+                              <td>
+                                {[e.metadata?.address, e.metadata?.city, e.metadata?.state]
+                                  .filter(Boolean)
+                                  .join(', ')}
+                              </td>
+                            </tr>
+                          ))}
+                        </tbody>
+                      </table>
+                    </section>
+                  )}
                   {national.length > 0 && (
                     <section className="call-for-help-section" aria-labelledby="national-heading">
                       <h3 id="national-heading" className="call-for-help-section-title">
@@ -224,6 +209,7 @@ export default function CallForHelpModal({ isOpen, onClose, userId }: CallForHel
                             <th scope="col">{t('ui.phoneBookProfession')}</th>
                             <th scope="col">{t('ui.phoneBookPhone')}</th>
                             <th scope="col">{t('ui.phoneBookAddress')}</th>
+                            <th scope="col">{t('ui.phoneBookDistance')}</th>
                           </tr>
                         </thead>
                         <tbody>
@@ -237,6 +223,7 @@ export default function CallForHelpModal({ isOpen, onClose, userId }: CallForHel
                                   .filter(Boolean)
                                   .join(', ')}
                               </td>
+                              <td>{e.distanceKm != null ? `${Math.round(e.distanceKm)} km` : ''}</td>
                             </tr>
                           ))}
                         </tbody>
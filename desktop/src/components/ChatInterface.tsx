@@ -2,6 +2,7 @@ import { useState, useEffect, useLayoutEffect, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { ArrowUp, Copy, LogOut, Phone, Settings, Trash2 } from 'lucide-react';
 import { DantAgent } from '../agent/dant-agent';
+import { errorCode, errorMessage as describeError } from '../utils/errors';
 import ReactMarkdown from 'react-markdown';
 import { useTranslation } from '../i18n/hooks/useTranslation';
 import { ChatLayout, ChatSidebarShell, ChatMessages, ChatInputBar } from 'confidant-chat-ui';
@@ -252,7 +253,7 @@ export default function ChatInterface({ disabled = false, modelReady = false, kb
       setAgent(dantAgent);
       setIsInitialized(true);
     } catch (err) {
-      const errorMessage = err instanceof Error ? err.message : 'Failed to initialize agent';
+      const errorMessage = describeError(err, 'Failed to initialize agent');
       setError(`Failed to initialize chat: ${errorMessage}. Please check that the model is loaded correctly.`);
       setIsInitialized(false);
     } finally {
@@ -329,13 +330,14 @@ export default function ChatInterface({ disabled = false, modelReady = false, kb
         return next;
       });
     } catch (err) {
-      const errorMessage = err instanceof Error ? err.message : 'Failed to process query';
+      const errorMessage = describeError(err, 'Failed to process query');
+      const code = errorCode(err);
       
       // Provide more helpful error messages
       let userFriendlyError = t('errors.somethingWentWrong');
-      if (errorMessage.includes('Model not initialized')) {
+      if (code === 'not_initialized') {
         userFriendlyError = t('errors.modelNotSetUp');
-      } else if (errorMessage.includes('Python')) {
+      } else if (code === 'python_missing' || code === 'helper_failed') {
         // Show the actual backend error so users see spawn/import details; add hint if short
         userFriendlyError = errorMessage.length > 80
           ? errorMessage
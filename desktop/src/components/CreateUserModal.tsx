@@ -3,6 +3,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { Eye, EyeOff } from 'lucide-react';
 import { useTranslation } from '../i18n/hooks/useTranslation';
 import { useModalFocusTrap } from '../hooks/useModalFocusTrap';
+import { errorMessage as describeError } from '../utils/errors';
 import './CreateUserModal.css';
 
 interface CreateUserModalProps {
@@ -57,8 +58,7 @@ export default function CreateUserModal({ onUserCreated, onCancel }: CreateUserM
       onUserCreated(user.id);
     } catch (err) {
       console.error('[CreateUserModal] Failed to create user:', err);
-      const errorMessage = err instanceof Error ? err.message : String(err);
-      setError(errorMessage || t('errors.failedToCreateUser'));
+      setError(describeError(err, t('errors.failedToCreateUser')));
     } finally {
       setIsCreating(false);
     }
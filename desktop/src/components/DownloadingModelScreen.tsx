@@ -1,6 +1,7 @@
 import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useTranslation } from '../i18n/hooks/useTranslation';
+import { errorMessage as describeError } from '../utils/errors';
 import './DownloadingModelScreen.css';
 
 interface DownloadingModelScreenProps {
@@ -29,7 +30,7 @@ export default function DownloadingModelScreen({
         await invoke('initialize_model', { modelPath: outputPath });
         onComplete();
       } catch (err) {
-        const message = err instanceof Error ? err.message : 'Failed to download or load model';
+        const message = describeError(err, 'Failed to download or load model');
         onError(message);
       }
     })();
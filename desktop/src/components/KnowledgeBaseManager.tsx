@@ -2,6 +2,7 @@ import { useState, useEffect, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useTranslation } from '../i18n/hooks/useTranslation';
 import { KnowledgeBaseLoader } from '../knowledge/knowledge-loader';
+import { errorMessage as describeError } from '../utils/errors';
 import './KnowledgeBaseManager.css';
 
 export default function KnowledgeBaseManager() {
@@ -25,7 +26,7 @@ export default function KnowledgeBaseManager() {
       setLoader(new KnowledgeBaseLoader());
       await updateStats();
     } catch (err) {
-      setError(err instanceof Error ? err.message : 'Failed to initialize vector store');
+      setError(describeError(err, 'Failed to initialize vector store'));
       console.error('Failed to initialize vector store:', err);
     }
   };
@@ -60,7 +61,7 @@ export default function KnowledgeBaseManager() {
         fileInputRef.current.value = '';
       }
     } catch (err) {
-      setError(err instanceof Error ? err.message : 'Failed to load knowledge base');
+      setError(describeError(err, 'Failed to load knowledge base'));
       setIsLoading(false);
     }
   };
@@ -80,7 +81,7 @@ export default function KnowledgeBaseManager() {
       await updateStats();
       setIsLoading(false);
     } catch (err) {
-      setError(err instanceof Error ? err.message : 'Failed to download knowledge base');
+      setError(describeError(err, 'Failed to download knowledge base'));
       setIsLoading(false);
     }
   };
@@ -94,7 +95,7 @@ export default function KnowledgeBaseManager() {
       // TODO: Implement clear command in Rust backend
       setError('Clear functionality not yet implemented');
     } catch (err) {
-      setError(err instanceof Error ? err.message : 'Failed to clear knowledge base');
+      setError(describeError(err, 'Failed to clear knowledge base'));
     }
   };
 
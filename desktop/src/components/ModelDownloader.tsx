@@ -1,6 +1,7 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useTranslation } from '../i18n/hooks/useTranslation';
+import { errorMessage as describeError } from '../utils/errors';
 import './ModelDownloader.css';
 
 type InitializationState = 'not-initialized' | 'initializing' | 'initialized' | 'error';
@@ -41,7 +42,7 @@ export default function ModelDownloader() {
       setInitState('initialized');
       setIsModelLoaded(true);
     } catch (err) {
-      const errorMessage = err instanceof Error ? err.message : String(err);
+      const errorMessage = describeError(err, 'Failed to initialize model');
       console.error('[Model Downloader] Initialization error:', err);
       setError(errorMessage);
       setInitState('error');
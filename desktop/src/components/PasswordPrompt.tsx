@@ -3,6 +3,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { Eye, EyeOff } from 'lucide-react';
 import { useTranslation } from '../i18n/hooks/useTranslation';
 import { useModalFocusTrap } from '../hooks/useModalFocusTrap';
+import { errorMessage as describeError } from '../utils/errors';
 import './PasswordPrompt.css';
 
 interface PasswordPromptProps {
@@ -45,7 +46,7 @@ export default function PasswordPrompt({ userId, userName, onVerified, onCancel
         setPassword('');
       }
     } catch (err) {
-      setError(err instanceof Error ? err.message : t('errors.failedToVerifyPassword'));
+      setError(describeError(err, t('errors.failedToVerifyPassword')));
       setPassword('');
     } finally {
       setIsVerifying(false);
@@ -4,6 +4,7 @@ import { MODEL_OPTIONS, ModelOption, getDefaultModel } from '../config/model-opt
 import { KB_OPTIONS, KBOption, getDefaultKB } from '../config/kb-options';
 import { useTranslation } from '../i18n/hooks/useTranslation';
 import { useModalFocusTrap } from '../hooks/useModalFocusTrap';
+import { errorMessage as describeError } from '../utils/errors';
 import './SetupModal.css';
 
 type DownloadStatus = 'not-started' | 'downloading' | 'downloaded' | 'error' | 'checking';
@@ -195,7 +196,7 @@ export default function SetupModal({
       await invoke('initialize_model', { modelPath });
       onModelReady(modelPath);
     } catch (err) {
-      const errorMessage = err instanceof Error ? err.message : 'Failed to download model';
+      const errorMessage = describeError(err, 'Failed to download model');
       setModelError(errorMessage);
       setModelStatus('error');
       console.error('[Setup] Model download error:', err);
@@ -222,7 +223,7 @@ export default function SetupModal({
       setKBStatus('downloaded');
       onKBReady();
     } catch (err) {
-      const errorMessage = err instanceof Error ? err.message : 'Failed to download knowledge base';
+      const errorMessage = describeError(err, 'Failed to download knowledge base');
       setKBError(errorMessage);
       setKBStatus('error');
       console.error('[Setup] KB download error:', err);
@@ -351,7 +352,7 @@ export default function SetupModal({
                           onModelReady(selectedPath);
                           setShowExistingModels(false);
                         } catch (err) {
-                          const errorMessage = err instanceof Error ? err.message : 'Failed to use existing model';
+                          const errorMessage = describeError(err, 'Failed to use existing model');
                           setModelError(errorMessage);
                           setModelStatus('error');
                           console.error('[Setup] Failed to use existing model:', err);
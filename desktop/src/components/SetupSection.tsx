@@ -3,6 +3,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { MODEL_OPTIONS, ModelOption, getDefaultModel } from '../config/model-options';
 import { KB_OPTIONS, KBOption, getDefaultKB } from '../config/kb-options';
 import { useTranslation } from '../i18n/hooks/useTranslation';
+import { errorMessage as describeError } from '../utils/errors';
 import './SetupSection.css';
 
 type DownloadStatus = 'not-started' | 'downloading' | 'downloaded' | 'error' | 'checking';
@@ -162,7 +163,7 @@ export default function SetupSection({ onModelReady, onKBReady }: SetupSectionPr
       await invoke('initialize_model', { modelPath });
       onModelReady(modelPath);
     } catch (err) {
-      const errorMessage = err instanceof Error ? err.message : 'Failed to download model';
+      const errorMessage = describeError(err, 'Failed to download model');
       setModelError(errorMessage);
       setModelStatus('error');
       console.error('[Setup] Model download error:', err);
@@ -190,7 +191,7 @@ export default function SetupSection({ onModelReady, onKBReady }: SetupSectionPr
       setKBStatus('downloaded');
       onKBReady();
     } catch (err) {
-      const errorMessage = err instanceof Error ? err.message : 'Failed to download knowledge base';
+      const errorMessage = describeError(err, 'Failed to download knowledge base');
       setKBError(errorMessage);
       setKBStatus('error');
       console.error('[Setup] KB download error:', err);
@@ -289,7 +290,7 @@ export default function SetupSection({ onModelReady, onKBReady }: SetupSectionPr
                       onModelReady(selectedPath);
                       setShowExistingModels(false);
                     } catch (err) {
-                      const errorMessage = err instanceof Error ? err.message : 'Failed to use existing model';
+                      const errorMessage = describeError(err, 'Failed to use existing model');
                       setModelError(errorMessage);
                       setModelStatus('error');
                       console.error('[Setup] Failed to use existing model:', err);
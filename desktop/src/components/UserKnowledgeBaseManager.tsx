@@ -133,8 +133,8 @@ export default function UserKnowledgeBaseManager({ userId }: UserKnowledgeBaseMa
       // Add to vector store
       const collectionName = `dant_knowledge_user_${userId}`;
       
-      // Generate embedding
-      const embedding = await invoke<number[]>('generate_embedding', { text: entryText });
+      // Generate embedding (stored passages get the model's passage prefix)
+      const [embedding] = await invoke<number[][]>('embed_passages', { texts: [entryText] });
       
       // Add to collection
       await invoke('add_documents_to_collection', {
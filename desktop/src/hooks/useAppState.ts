@@ -248,9 +248,19 @@ export function useAppState() {
               kb_ready: boolean;
               default_model_download_url?: string;
               default_model_output_path?: string;
+              kb_signature?: 'valid' | 'unsigned' | 'invalid';
+              phonebook_signature?: 'valid' | 'unsigned' | 'invalid';
             }>('ensure_bundled_defaults_initialized');
             logAppTiming('ensure_bundled_defaults_initialized done');
 
+            // Bundled packages that fail signature verification are not ingested
+            if (bundled.kb_signature && bundled.kb_signature !== 'valid') {
+              console.warn(`Bundled knowledge base signature: ${bundled.kb_signature}`);
+            }
+            if (bundled.phonebook_signature && bundled.phonebook_signature !== 'valid') {
+              console.warn(`Bundled phone book signature: ${bundled.phonebook_signature}`);
+            }
+
             // If no model but we can auto-download, show downloading screen
             if (
               !bundled.model_ready &&
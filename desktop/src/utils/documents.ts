@@ -0,0 +1,48 @@
+/**
+ * Filter queries against the vector store (get_documents_by_filter)
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface FilterDocument {
+  id: string;
+  text: string;
+  metadata: Record<string, unknown>;
+  embedding?: number[];
+}
+
+export interface FilterQuery {
+  offset?: number;
+  /** Page size; omit to get every match from offset on. */
+  limit?: number;
+  /** Fields to return (default text and metadata). */
+  include?: Array<'text' | 'metadata' | 'embeddings'>;
+  /** Metadata field to sort by; documents without it come last. */
+  sort_by?: string;
+  descending?: boolean;
+}
+
+export interface FilterPage {
+  documents: FilterDocument[];
+  /** Matches across all pages. */
+  total: number;
+  offset: number;
+  limit: number | null;
+}
+
+export function getDocumentsByFilter(
+  collectionName: string,
+  whereJson: string,
+  query?: FilterQuery
+): Promise<FilterPage> {
+  return invoke<FilterPage>('get_documents_by_filter', { collectionName, whereJson, query });
+}
+
+/** Every document matching the filter, or none if the lookup fails. */
+export async function findDocuments(collectionName: string, whereJson: string): Promise<FilterDocument[]> {
+  try {
+    return (await getDocumentsByFilter(collectionName, whereJson)).documents;
+  } catch {
+    return [];
+  }
+}
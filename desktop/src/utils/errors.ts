@@ -0,0 +1,63 @@
+/**
+ * Backend errors
+ * Tauri commands reject with { code, message, details } (ConfidantError in src-tauri/src/error.rs)
+ */
+
+export type ConfidantErrorCode =
+  | 'not_initialized'
+  | 'python_missing'
+  | 'helper_failed'
+  | 'validation'
+  | 'not_found'
+  | 'io'
+  | 'internal';
+
+export interface ConfidantErrorPayload {
+  code: ConfidantErrorCode;
+  message: string;
+  details: string | null;
+}
+
+/** Error that keeps the backend's code, so it survives being rethrown as an Error. */
+export class CommandError extends Error {
+  code?: ConfidantErrorCode;
+  details?: string | null;
+
+  constructor(message: string, code?: ConfidantErrorCode, details?: string | null) {
+    super(message);
+    this.name = 'CommandError';
+    this.code = code;
+    this.details = details;
+  }
+}
+
+export function isConfidantError(err: unknown): err is ConfidantErrorPayload {
+  return (
+    typeof err === 'object' &&
+    err !== null &&
+    typeof (err as ConfidantErrorPayload).code === 'string' &&
+    typeof (err as ConfidantErrorPayload).message === 'string'
+  );
+}
+
+/** Code of a rejected command (or a CommandError built from one). */
+export function errorCode(err: unknown): ConfidantErrorCode | undefined {
+  if (err instanceof CommandError) return err.code;
+  if (isConfidantError(err)) return err.code;
+  return undefined;
+}
+
+/** Readable message for anything a command or the agent may throw. */
+export function errorMessage(err: unknown, fallback: string): string {
+  if (isConfidantError(err)) return err.message || fallback;
+  if (err instanceof Error) return err.message || fallback;
+  if (typeof err === 'string') return err || fallback;
+  return fallback;
+}
+
+/** Convert a rejection into an Error, keeping the code when there is one. */
+export function toCommandError(err: unknown, fallback: string): CommandError {
+  if (err instanceof CommandError) return err;
+  if (isConfidantError(err)) return new CommandError(err.message || fallback, err.code, err.details);
+  return new CommandError(errorMessage(err, fallback));
+}
@@ -0,0 +1,116 @@
+/**
+ * Phone book lookup by proximity (find_nearest_contacts), per-user personal contacts and vCard/CSV import
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import { FilterDocument } from './documents';
+
+export interface GeoPlace {
+  country: string;
+  postal_code: string;
+  city: string;
+  state: string;
+  lat: number;
+  lon: number;
+}
+
+export interface NearbyContact extends FilterDocument {
+  /** Kilometres from the user's location; null for national lines. */
+  distanceKm: number | null;
+  national: boolean;
+  /** One of the user's own contacts (listed first). */
+  personal: boolean;
+}
+
+export interface NearestContacts {
+  /** Where the postal code (or city) was placed; null when it is not in the geocoding table. */
+  origin: GeoPlace | null;
+  radiusKm: number;
+  /** Personal contacts, then local entries nearest first, then the country's national crisis lines. */
+  contacts: NearbyContact[];
+}
+
+export function findNearestContacts(
+  country: string,
+  postalCode: string,
+  options: { profession?: string; radiusKm?: number; userId?: string } = {}
+): Promise<NearestContacts> {
+  return invoke<NearestContacts>('find_nearest_contacts', {
+    country,
+    postalCode,
+    profession: options.profession,
+    radiusKm: options.radiusKm,
+    userId: options.userId,
+  });
+}
+
+/** Editable fields of a personal contact; name and phone are required. */
+export interface PersonalContactInput {
+  name: string;
+  phone: string;
+  profession?: string;
+  address?: string;
+  city?: string;
+  state?: string;
+  postal_code?: string;
+  country?: string;
+  notes?: string;
+}
+
+export interface PersonalContact extends Required<PersonalContactInput> {
+  id: string;
+  created_at: string;
+  updated_at: string;
+}
+
+export function listPersonalContacts(userId: string): Promise<PersonalContact[]> {
+  return invoke<PersonalContact[]>('list_personal_contacts', { userId });
+}
+
+export function addPersonalContact(userId: string, contact: PersonalContactInput): Promise<PersonalContact> {
+  return invoke<PersonalContact>('add_personal_contact', { userId, contact });
+}
+
+export function updatePersonalContact(
+  userId: string,
+  contactId: string,
+  contact: PersonalContactInput
+): Promise<PersonalContact> {
+  return invoke<PersonalContact>('update_personal_contact', { userId, contactId, contact });
+}
+
+export function deletePersonalContact(userId: string, contactId: string): Promise<void> {
+  return invoke<void>('delete_personal_contact', { userId, contactId });
+}
+
+/** Phone book row (fields of phonebook_seed.json). */
+export interface PhonebookEntry {
+  country: string;
+  postal_code: string;
+  profession: string;
+  name: string;
+  phone: string;
+  address: string;
+  city: string;
+  state: string;
+}
+
+export interface PhonebookImportReport {
+  format: 'vcard' | 'csv';
+  dryRun: boolean;
+  /** Entries that passed validation (the preview on a dry run). */
+  entries: PhonebookEntry[];
+  /** CSV row (header is row 1) or vCard position, with the reason it was skipped. */
+  rejected: Array<{ row: number; name: string; reason: string }>;
+  /** Entries written to the phone book; 0 on a dry run. */
+  imported: number;
+}
+
+/** Import a .vcf or .csv referral list; preview with dryRun before writing. */
+export function importPhonebookFile(
+  path: string,
+  dryRun: boolean,
+  defaults?: { country?: string; profession?: string }
+): Promise<PhonebookImportReport> {
+  return invoke<PhonebookImportReport>('import_phonebook_file', { path, dryRun, defaults });
+}